@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS lease_until;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS worker;
+DROP INDEX IF EXISTS service_version_build_queue_idx;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+CREATE INDEX IF NOT EXISTS service_version_build_queue_idx ON service_version_build (status, id);
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS worker uuid;
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS lease_until timestamp with time zone;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS cancel_requested;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS error_reason;
+
+ALTER TABLE builder DROP COLUMN IF EXISTS timeout;
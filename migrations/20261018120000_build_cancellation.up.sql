@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+ALTER TABLE builder ADD COLUMN IF NOT EXISTS timeout integer;
+
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS error_reason character varying;
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS cancel_requested boolean NOT NULL DEFAULT false;
@@ -0,0 +1,11 @@
+-- Add down migration script here
+
+ALTER TABLE builder ADD COLUMN IF NOT EXISTS build_command character varying;
+
+UPDATE builder SET build_command = (
+    SELECT string_agg(s.stage->>'command', ' && ' ORDER BY s.position)
+    FROM jsonb_array_elements(stages) WITH ORDINALITY AS s(stage, position)
+);
+
+ALTER TABLE builder ALTER COLUMN build_command SET NOT NULL;
+ALTER TABLE builder DROP COLUMN IF EXISTS stages;
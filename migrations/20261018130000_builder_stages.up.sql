@@ -0,0 +1,9 @@
+-- Add up migration script here
+
+ALTER TABLE builder ADD COLUMN IF NOT EXISTS stages jsonb;
+
+UPDATE builder SET stages = jsonb_build_array(jsonb_build_object('name', 'build', 'command', build_command, 'continue_on_error', false))
+WHERE stages IS NULL;
+
+ALTER TABLE builder ALTER COLUMN stages SET NOT NULL;
+ALTER TABLE builder DROP COLUMN IF EXISTS build_command;
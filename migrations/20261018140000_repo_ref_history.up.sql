@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS service_version_repo_ref_history
+(
+    id              uuid                     NOT NULL,
+    service_version uuid                     NOT NULL,
+    repo_ref        uuid                     NOT NULL,
+    pinned_ref      uuid                     NOT NULL,
+    pinned_on       timestamp with time zone NOT NULL,
+    CONSTRAINT      service_version_repo_ref_history_pkey PRIMARY KEY (id),
+    CONSTRAINT      service_version_repo_ref_history_service_version_fkey FOREIGN KEY (service_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      service_version_repo_ref_history_repo_ref_fkey FOREIGN KEY (repo_ref) REFERENCES repo_reference (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      service_version_repo_ref_history_pinned_ref_fkey FOREIGN KEY (pinned_ref) REFERENCES repo_reference (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+SELECT audit.audit_table('public.service_version_repo_ref_history');
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE service_version_build DROP CONSTRAINT IF EXISTS service_version_build_retry_of_fkey;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS rerun_from;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS retry_of;
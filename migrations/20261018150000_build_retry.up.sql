@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS retry_of uuid;
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS rerun_from integer;
+ALTER TABLE service_version_build ADD CONSTRAINT service_version_build_retry_of_fkey FOREIGN KEY (retry_of) REFERENCES service_version_build (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION;
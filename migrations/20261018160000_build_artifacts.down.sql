@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS service_version_artifact;
+
+ALTER TABLE builder DROP COLUMN IF EXISTS artifacts;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+
+ALTER TABLE builder ADD COLUMN IF NOT EXISTS artifacts jsonb NOT NULL DEFAULT '[]';
+
+CREATE TABLE IF NOT EXISTS service_version_artifact
+(
+    id              uuid                     NOT NULL,
+    service_version uuid                     NOT NULL,
+    build           uuid                     NOT NULL,
+    name            character varying        NOT NULL,
+    size            bigint                   NOT NULL,
+    sha256          character varying        NOT NULL,
+    created_on      timestamp with time zone NOT NULL,
+    CONSTRAINT      service_version_artifact_pkey PRIMARY KEY (id),
+    CONSTRAINT      service_version_artifact_build_name_key UNIQUE (build, name),
+    CONSTRAINT      service_version_artifact_service_version_fkey FOREIGN KEY (service_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      service_version_artifact_build_fkey FOREIGN KEY (build) REFERENCES service_version_build (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+SELECT audit.audit_table('public.service_version_artifact');
@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS service_version_build_cache_idx;
+
+ALTER TABLE service_version_build DROP CONSTRAINT IF EXISTS service_version_build_cached_from_fkey;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS cached_from;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS builder_digest;
+ALTER TABLE service_version_build DROP COLUMN IF EXISTS commit;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS commit character varying;
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS builder_digest character varying;
+ALTER TABLE service_version_build ADD COLUMN IF NOT EXISTS cached_from uuid;
+ALTER TABLE service_version_build ADD CONSTRAINT service_version_build_cached_from_fkey FOREIGN KEY (cached_from) REFERENCES service_version_build (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION;
+
+CREATE INDEX IF NOT EXISTS service_version_build_cache_idx ON service_version_build (commit, builder_digest, status);
@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS environment
+(
+    id         uuid              NOT NULL,
+    name       character varying NOT NULL,
+    tenant     uuid              NOT NULL,
+    position   integer           NOT NULL,
+    CONSTRAINT environment_pkey PRIMARY KEY (id),
+    CONSTRAINT environment_name_tenant_key UNIQUE (name, tenant),
+    CONSTRAINT environment_position_tenant_key UNIQUE (position, tenant),
+    CONSTRAINT environment_tenant_fkey FOREIGN KEY (tenant) REFERENCES tenant (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+SELECT audit.audit_table('public.environment');
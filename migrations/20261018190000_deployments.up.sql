@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS deployment
+(
+    id               uuid                     NOT NULL,
+    service_version  uuid                     NOT NULL,
+    environment      uuid                     NOT NULL,
+    status           character varying        NOT NULL,
+    deployed_by      character varying        NOT NULL,
+    previous_version uuid,
+    command          character varying,
+    error_reason     character varying,
+    logs_link        character varying,
+    started_on       timestamp with time zone NOT NULL,
+    finished_on      timestamp with time zone,
+    CONSTRAINT       deployment_pkey PRIMARY KEY (id),
+    CONSTRAINT       deployment_service_version_fkey FOREIGN KEY (service_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT       deployment_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT       deployment_previous_version_fkey FOREIGN KEY (previous_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+CREATE INDEX IF NOT EXISTS deployment_environment_idx ON deployment (environment, status, id);
+
+SELECT audit.audit_table('public.deployment');
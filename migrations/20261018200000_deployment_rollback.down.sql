@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE deployment DROP CONSTRAINT IF EXISTS deployment_rollback_of_fkey;
+ALTER TABLE deployment DROP COLUMN IF EXISTS rollback_of;
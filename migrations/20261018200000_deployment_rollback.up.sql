@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+ALTER TABLE deployment ADD COLUMN IF NOT EXISTS rollback_of uuid;
+ALTER TABLE deployment ADD CONSTRAINT deployment_rollback_of_fkey FOREIGN KEY (rollback_of) REFERENCES deployment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION;
@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS release;
+
+ALTER TABLE environment DROP COLUMN IF EXISTS require_approval;
+ALTER TABLE environment DROP COLUMN IF EXISTS require_build;
+ALTER TABLE environment DROP COLUMN IF EXISTS soak_minutes;
@@ -0,0 +1,23 @@
+-- Add up migration script here
+
+ALTER TABLE environment ADD COLUMN IF NOT EXISTS soak_minutes integer;
+ALTER TABLE environment ADD COLUMN IF NOT EXISTS require_build boolean NOT NULL DEFAULT false;
+ALTER TABLE environment ADD COLUMN IF NOT EXISTS require_approval boolean NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS release
+(
+    id              uuid                     NOT NULL,
+    service_version uuid                     NOT NULL,
+    environment     uuid                     NOT NULL,
+    status          character varying        NOT NULL,
+    promoted_by     character varying        NOT NULL,
+    promoted_on     timestamp with time zone NOT NULL,
+    from_deployment uuid                     NOT NULL,
+    CONSTRAINT      release_pkey PRIMARY KEY (id),
+    CONSTRAINT      release_service_version_environment_key UNIQUE (service_version, environment),
+    CONSTRAINT      release_service_version_fkey FOREIGN KEY (service_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      release_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      release_from_deployment_fkey FOREIGN KEY (from_deployment) REFERENCES deployment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+SELECT audit.audit_table('public.release');
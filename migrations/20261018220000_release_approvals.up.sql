@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS release_approval
+(
+    id          uuid                     NOT NULL,
+    release     uuid                     NOT NULL,
+    decision    character varying        NOT NULL,
+    approver    character varying        NOT NULL,
+    comment     character varying,
+    decided_on  timestamp with time zone NOT NULL,
+    CONSTRAINT  release_approval_pkey PRIMARY KEY (id),
+    CONSTRAINT  release_approval_release_fkey FOREIGN KEY (release) REFERENCES release (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+CREATE INDEX IF NOT EXISTS release_approval_release_idx ON release_approval (release);
+
+SELECT audit.audit_table('public.release_approval');
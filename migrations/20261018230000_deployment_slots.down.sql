@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS deployment_slot;
+
+ALTER TABLE deployment DROP COLUMN IF EXISTS slot;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+ALTER TABLE deployment ADD COLUMN IF NOT EXISTS slot character varying;
+
+CREATE TABLE IF NOT EXISTS deployment_slot
+(
+    id              uuid              NOT NULL,
+    service         uuid              NOT NULL,
+    environment     uuid              NOT NULL,
+    name            character varying NOT NULL,
+    service_version uuid              NOT NULL,
+    deployment      uuid              NOT NULL,
+    active          boolean           NOT NULL DEFAULT false,
+    CONSTRAINT      deployment_slot_pkey PRIMARY KEY (id),
+    CONSTRAINT      deployment_slot_service_environment_name_key UNIQUE (service, environment, name),
+    CONSTRAINT      deployment_slot_service_fkey FOREIGN KEY (service) REFERENCES service (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      deployment_slot_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      deployment_slot_service_version_fkey FOREIGN KEY (service_version) REFERENCES service_version (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT      deployment_slot_deployment_fkey FOREIGN KEY (deployment) REFERENCES deployment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+SELECT audit.audit_table('public.deployment_slot');
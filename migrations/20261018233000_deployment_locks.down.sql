@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS lock_override;
+DROP TABLE IF EXISTS freeze_window;
+DROP TABLE IF EXISTS deployment_lock;
@@ -0,0 +1,53 @@
+-- Add up migration script here
+
+CREATE TABLE IF NOT EXISTS deployment_lock
+(
+    id          uuid                     NOT NULL,
+    tenant      uuid                     NOT NULL,
+    environment uuid,
+    locked_by   character varying        NOT NULL,
+    reason      character varying        NOT NULL,
+    locked_on   timestamp with time zone NOT NULL,
+    expires_on  timestamp with time zone,
+    CONSTRAINT  deployment_lock_pkey PRIMARY KEY (id),
+    CONSTRAINT  deployment_lock_tenant_fkey FOREIGN KEY (tenant) REFERENCES tenant (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT  deployment_lock_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+CREATE TABLE IF NOT EXISTS freeze_window
+(
+    id          uuid                   NOT NULL,
+    name        character varying      NOT NULL,
+    tenant      uuid                   NOT NULL,
+    environment uuid,
+    days        character varying      NOT NULL,
+    starts_at   time without time zone NOT NULL,
+    ends_at     time without time zone NOT NULL,
+    reason      character varying,
+    CONSTRAINT  freeze_window_pkey PRIMARY KEY (id),
+    CONSTRAINT  freeze_window_name_tenant_key UNIQUE (name, tenant),
+    CONSTRAINT  freeze_window_tenant_fkey FOREIGN KEY (tenant) REFERENCES tenant (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT  freeze_window_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+CREATE TABLE IF NOT EXISTS lock_override
+(
+    id            uuid                     NOT NULL,
+    tenant        uuid                     NOT NULL,
+    environment   uuid,
+    action        character varying        NOT NULL,
+    locked        character varying        NOT NULL,
+    overridden_by character varying        NOT NULL,
+    justification character varying        NOT NULL,
+    overridden_on timestamp with time zone NOT NULL,
+    CONSTRAINT    lock_override_pkey PRIMARY KEY (id),
+    CONSTRAINT    lock_override_tenant_fkey FOREIGN KEY (tenant) REFERENCES tenant (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION,
+    CONSTRAINT    lock_override_environment_fkey FOREIGN KEY (environment) REFERENCES environment (id) MATCH SIMPLE ON UPDATE NO ACTION ON DELETE NO ACTION
+);
+
+COMMENT ON COLUMN freeze_window.starts_at IS 'Time of day the window starts, in UTC';
+COMMENT ON COLUMN freeze_window.ends_at IS 'Time of day the window ends, in UTC, before starts_at when the window runs past midnight';
+
+SELECT audit.audit_table('public.deployment_lock');
+SELECT audit.audit_table('public.freeze_window');
+SELECT audit.audit_table('public.lock_override');
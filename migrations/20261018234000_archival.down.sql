@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+ALTER TABLE service_version DROP COLUMN IF EXISTS archived_by;
+ALTER TABLE service_version DROP COLUMN IF EXISTS archived_at;
+ALTER TABLE service DROP COLUMN IF EXISTS archived_by;
+ALTER TABLE service DROP COLUMN IF EXISTS archived_at;
+ALTER TABLE application DROP COLUMN IF EXISTS archived_by;
+ALTER TABLE application DROP COLUMN IF EXISTS archived_at;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+ALTER TABLE application ADD COLUMN IF NOT EXISTS archived_at timestamp with time zone;
+ALTER TABLE application ADD COLUMN IF NOT EXISTS archived_by character varying;
+ALTER TABLE service ADD COLUMN IF NOT EXISTS archived_at timestamp with time zone;
+ALTER TABLE service ADD COLUMN IF NOT EXISTS archived_by character varying;
+ALTER TABLE service_version ADD COLUMN IF NOT EXISTS archived_at timestamp with time zone;
+ALTER TABLE service_version ADD COLUMN IF NOT EXISTS archived_by character varying;
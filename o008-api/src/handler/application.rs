@@ -0,0 +1,148 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, ApplicationRequest, ArchiveRequest, DispatchCommand, ListRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::{ArchivedParams, CascadeParams, ListParams, message_into_response};
+
+/// Create an Application of a tenant
+///
+/// Create an Application of the tenant by name, with its class unit and functional group. Return status 201 on success, 400 if the request is invalid or the Application already exists, or 404 if tenant is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/app/{app}",
+request_body = ApplicationRequest,
+responses(
+(status = 201, description = "create application done successfully", body = Application),
+(status = 400, description = "Invalid or duplicated application"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+)
+)]
+pub async fn application_post(Path((tenant, application)): Path<(String, String)>,
+                              Json(payload): Json<ApplicationRequest>) -> impl IntoResponse {
+    let req = payload.with_target(application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CreateApplication { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get an Application by name and tenant name
+///
+/// Get an Application of the tenant by name, an archived Application only when included. Return status 200 on success or 404 if Application is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/app/{app}",
+responses(
+(status = 200, description = "Get application done successfully", body = Application),
+(status = 404, description = "Application not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+ArchivedParams,
+)
+)]
+pub async fn application_get(Path((tenant, application)): Path<(String, String)>,
+                             Query(params): Query<ArchivedParams>) -> impl IntoResponse {
+    let req = ApplicationRequest::build_get_request(application, tenant).with_include_archived(params.include_archived());
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetApplication { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// List the Applications of a tenant
+///
+/// List the Applications of the tenant a page at a time, filtered by name prefix and ordered by creation. Pass the `next` cursor of a page as `after` to get the following page, `next` is missing on the last page. Return status 200 on success, 400 if the request is invalid or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/app",
+responses(
+(status = 200, description = "List applications done successfully", body = ApplicationPage),
+(status = 400, description = "Invalid list request"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+ListParams,
+)
+)]
+pub async fn applications_get(Path(tenant): Path<String>,
+                              Query(params): Query<ListParams>) -> impl IntoResponse {
+    let req = ListRequest::from(params).with_scope(Some(tenant), None, None);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListApplications { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Delete an Application by name and tenant name
+///
+/// Delete an Application of the tenant by name. Without cascade the Application is only deleted when it has no Service, otherwise the response lists them. With cascade the Application is deleted with its Services, their versions and everything referencing them in one transaction. Return status 200 on success, 404 if Application is not found, 409 if the Application is referenced or 410 if the Application can not be deleted.
+#[utoipa::path(
+delete,
+path = "/tenant/{tenant}/app/{app}",
+responses(
+(status = 200, description = "Delete application done successfully"),
+(status = 404, description = "Application not found"),
+(status = 409, description = "Application referenced by dependents, listed in the response body"),
+(status = 410, description = "Application not deleted")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+CascadeParams,
+)
+)]
+pub async fn application_delete(Path((tenant, application)): Path<(String, String)>,
+                                Query(params): Query<CascadeParams>) -> impl IntoResponse {
+    let req = ApplicationRequest::build_get_request(application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteApplication { request: req, cascade: params.cascade() }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Archive an Application by name and tenant name
+///
+/// Archive an Application of the tenant by name, recording who archived it. An archived Application and its Services are hidden from the reads and lists unless archived items are included. Return status 200 on success, 400 if the request is invalid or the Application is already archived, or 404 if Application is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/app/{app}/archive",
+request_body = ArchiveRequest,
+responses(
+(status = 200, description = "Archive application done successfully", body = Application),
+(status = 400, description = "Invalid request or application already archived"),
+(status = 404, description = "Application not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+)
+)]
+pub async fn application_archive_post(Path((tenant, application)): Path<(String, String)>,
+                                      Json(payload): Json<ArchiveRequest>) -> impl IntoResponse {
+    let req = ApplicationRequest::build_get_request(application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ArchiveApplication { request: req, archive: payload }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Restore an archived Application by name and tenant name
+///
+/// Restore an archived Application of the tenant by name, making it and its Services visible again. Return status 200 on success, 400 if the Application is not archived, or 404 if Application is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/app/{app}/restore",
+responses(
+(status = 200, description = "Restore application done successfully", body = Application),
+(status = 400, description = "Application not archived"),
+(status = 404, description = "Application not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+)
+)]
+pub async fn application_restore_post(Path((tenant, application)): Path<(String, String)>) -> impl IntoResponse {
+    let req = ApplicationRequest::build_get_request(application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RestoreApplication { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -0,0 +1,107 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, ApprovalPolicyRequest, ApprovalRequest, DispatchCommand, ServiceVersionRequest, TenantRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+/// Approve the release of a Service version to an environment
+///
+/// Approve a release pending approval, the Service version can then be deployed to the environment. The decision is recorded with the approver and the comment. Return status 201 when the approval is recorded, 400 if the request is invalid, the release is not pending approval or the approver promoted it, 404 if Service version, environment or release is not found, or 423 if the environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/releases/{environment}/approve",
+request_body = ApprovalRequest,
+responses(
+(status = 201, description = "approval done", body = ReleaseApproval),
+(status = 400, description = "Invalid approval request or release not pending approval"),
+(status = 404, description = "Service version, environment or release not found"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn release_approve_post(Path((name, application, tenant, version, environment)): Path<(String, String, String, String, String)>,
+                                  Json(payload): Json<ApprovalRequest>) -> impl IntoResponse {
+    let svr = ServiceVersionRequest::build_get_request(version, name, application, tenant);
+    let req = payload.with_target(svr, environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ApproveDeployment { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Reject the release of a Service version to an environment
+///
+/// Reject a release pending approval, the Service version can not be deployed to the environment until it is promoted and approved again. The decision is recorded with the approver and the comment. Return status 201 when the rejection is recorded, 400 if the request is invalid, the release is not pending approval or the approver promoted it, or 404 if Service version, environment or release is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/releases/{environment}/reject",
+request_body = ApprovalRequest,
+responses(
+(status = 201, description = "rejection done", body = ReleaseApproval),
+(status = 400, description = "Invalid approval request or release not pending approval"),
+(status = 404, description = "Service version, environment or release not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn release_reject_post(Path((name, application, tenant, version, environment)): Path<(String, String, String, String, String)>,
+                                 Json(payload): Json<ApprovalRequest>) -> impl IntoResponse {
+    let svr = ServiceVersionRequest::build_get_request(version, name, application, tenant);
+    let req = payload.with_target(svr, environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RejectDeployment { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Set the approval policy of a tenant
+///
+/// Set the Environments of the tenant whose releases require a manual approval, the releases to the other Environments are released on promotion. Return status 200 on success, 400 if the request is invalid or it requires an approval for the first Environment of the tenant, or 404 if tenant or an Environment is not found.
+#[utoipa::path(
+put,
+path = "/tenant/{tenant}/approval_policy",
+request_body = ApprovalPolicyRequest,
+responses(
+(status = 200, description = "Set approval policy done successfully"),
+(status = 400, description = "Invalid approval policy"),
+(status = 404, description = "Tenant or environment not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn approval_policy_put(Path(tenant): Path<String>,
+                                 Json(payload): Json<ApprovalPolicyRequest>) -> impl IntoResponse {
+    let req = payload.with_tenant(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::SetApprovalPolicy { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Get the approval policy of a tenant
+///
+/// Get the Environments of the tenant whose releases require a manual approval. Return status 200 on success or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/approval_policy",
+responses(
+(status = 200, description = "Get approval policy done successfully"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn approval_policy_get(Path(tenant): Path<String>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetApprovalPolicy { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
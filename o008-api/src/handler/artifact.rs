@@ -0,0 +1,90 @@
+use axum::body::Body;
+use axum::extract::Path;
+use axum::http::{HeaderValue, StatusCode};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE, ETAG};
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use tokio::fs::File;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+use o008_business::executor::ArtifactStore;
+use o008_common::{AppCommand, ArtifactRequest, DispatchCommand, ServiceVersionRequest};
+use o008_message_bus::{RequestMessage};
+use crate::handler::{message_into_response, message_into_result};
+
+/// Get the artifacts of a Service version
+///
+/// Get the artifacts stored by the builds of the Service version, with their size and SHA-256. Return status 200 on success or 404 if Service version is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/artifacts",
+responses(
+(status = 200, description = "Get artifacts done successfully", body = [ServiceVersionArtifact]),
+(status = 404, description = "Service version not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+)
+)]
+pub async fn artifacts_get(Path((name, application, tenant, version)): Path<(String, String, String, String)>) -> impl IntoResponse {
+    let req = ServiceVersionRequest::build_get_request(version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetArtifacts { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Download a Service version artifact
+///
+/// Get the content of an artifact from the artifact store, its SHA-256 is sent as the ETag. Return status 200 on success or 404 if the artifact is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/artifacts/{artifact}",
+responses(
+(status = 200, description = "Download artifact done successfully", body = Vec<u8>, content_type = "application/octet-stream"),
+(status = 404, description = "Artifact not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("artifact" = Uuid, Path, description = "Artifact id"),
+)
+)]
+pub async fn artifact_get(Path((name, application, tenant, version, artifact)): Path<(String, String, String, String, Uuid)>) -> Response {
+    let req = ArtifactRequest::build_get_request(artifact, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetArtifact { request: Box::new(req) }));
+    match message_into_result(msg).await {
+        Ok(artifact) => match (artifact.get("name").and_then(Value::as_str), artifact.get("sha256").and_then(Value::as_str)) {
+            (Some(name), Some(sha256)) => artifact_file_into_response(name, sha256).await,
+            (_, _) => (StatusCode::NOT_FOUND, "artifact has no content").into_response()
+        },
+        Err(r) => r
+    }
+}
+
+async fn artifact_file_into_response(name: &str, sha256: &str) -> Response {
+    let file = match File::open(ArtifactStore::path(sha256)).await {
+        Ok(f) => f,
+        Err(e) => return (StatusCode::NOT_FOUND, format!("could not open artifact: {}", e)).into_response()
+    };
+    let len = match file.metadata().await {
+        Ok(m) => m.len(),
+        Err(e) => return (StatusCode::NOT_FOUND, format!("could not read artifact: {}", e)).into_response()
+    };
+    let file_name = std::path::Path::new(name).file_name().and_then(|n| n.to_str()).unwrap_or(sha256);
+    let mut res = (
+        StatusCode::OK,
+        [(CONTENT_TYPE, "application/octet-stream".to_string()), (CONTENT_LENGTH, len.to_string())],
+        Body::from_stream(ReaderStream::new(file))
+    ).into_response();
+    if let Ok(v) = HeaderValue::from_str(&format!("\"{}\"", sha256)) {
+        res.headers_mut().insert(ETAG, v);
+    }
+    if let Ok(v) = HeaderValue::from_str(&format!("attachment; filename=\"{}\"", file_name.replace('"', ""))) {
+        res.headers_mut().insert(CONTENT_DISPOSITION, v);
+    }
+    res
+}
@@ -0,0 +1,281 @@
+use std::io::SeekFrom;
+use axum::body::Body;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::http::header::{ACCEPT_RANGES, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use o008_common::{AppCommand, BuildRequest, BuildStageRequest, BuildTriggerRequest, DispatchCommand};
+use o008_message_bus::{RequestMessage};
+use crate::handler::{message_into_response, message_into_result};
+
+#[derive(Deserialize, IntoParams)]
+pub struct TriggerParams {
+    /// Queue a new build even if the commit was already built with the same builder definition
+    force: Option<bool>,
+}
+
+/// Trigger a build of a Service version by version, service name, application name and tenant name
+///
+/// Queue a new build of the Service version and return it with its queue position, the build is run by the build worker. When its reference resolves to a commit already built successfully with the same builder definition, the build is completed as `Cached` with the artifacts of that build, unless `force=true`. Return status 201 on success or 404 if Service version is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/build",
+responses(
+(status = 201, description = "build queued or cached", body = ServiceVersionBuild),
+(status = 404, description = "Service version not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+TriggerParams,
+)
+)]
+pub async fn build_post(Path((name, application, tenant, version)): Path<(String, String, String, String)>,
+                        Query(params): Query<TriggerParams>) -> impl IntoResponse {
+    let req = BuildTriggerRequest::build_get_request(version, name, application, tenant, params.force);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::TriggerBuild { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get a Service version build by id
+///
+/// Get the build state with its stages, and its queue position while it is waiting to be run. Return status 200 on success or 404 if the build is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}",
+responses(
+(status = 200, description = "Get build done successfully", body = ServiceVersionBuild),
+(status = 404, description = "Build not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+)
+)]
+pub async fn build_get(Path((name, application, tenant, version, build)): Path<(String, String, String, String, Uuid)>) -> impl IntoResponse {
+    let req = BuildRequest::build_get_request(build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetBuild { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Cancel a Service version build
+///
+/// A queued build is cancelled right away, a running build has its command process tree killed by the build worker. The build and its current stage end in error with reason `cancelled`. Return status 202 on success, 400 if the build is already completed or 404 if the build is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}/cancel",
+responses(
+(status = 202, description = "Build cancel accepted", body = ServiceVersionBuild),
+(status = 400, description = "Build already completed"),
+(status = 404, description = "Build not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+)
+)]
+pub async fn build_cancel_post(Path((name, application, tenant, version, build)): Path<(String, String, String, String, Uuid)>) -> impl IntoResponse {
+    let req = BuildRequest::build_get_request(build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CancelBuild { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::ACCEPTED).await
+}
+
+/// Retry a completed Service version build
+///
+/// Queue a new build of the Service version linked to the retried one, all its stages are run again. Return status 201 on success, 400 if the build is not completed or 404 if the build is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}/retry",
+responses(
+(status = 201, description = "build retry queued", body = ServiceVersionBuild),
+(status = 400, description = "Build not completed"),
+(status = 404, description = "Build not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+)
+)]
+pub async fn build_retry_post(Path((name, application, tenant, version, build)): Path<(String, String, String, String, Uuid)>) -> impl IntoResponse {
+    let req = BuildRequest::build_get_request(build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RetryBuild { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Rerun a completed Service version build from one of its stages
+///
+/// Queue a new build of the Service version linked to the rerun one. It starts from a copy of the rerun build workspace, reuses the results of the stages before the given one and runs the others. Return status 201 on success, 400 if the build is not completed or an earlier stage did not succeed, or 404 if the build or stage is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}/stages/{stage}/rerun",
+responses(
+(status = 201, description = "build rerun queued", body = ServiceVersionBuild),
+(status = 400, description = "Build not completed or earlier stage not succeeded"),
+(status = 404, description = "Build or stage not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+("stage" = i32, Path, description = "Build stage number to rerun from"),
+)
+)]
+pub async fn build_stage_rerun_post(Path((name, application, tenant, version, build, stage)): Path<(String, String, String, String, Uuid, i32)>) -> impl IntoResponse {
+    let req = BuildStageRequest::build_get_request(stage, build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RerunFromStage { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get the logs of a Service version build stage
+///
+/// Get the captured output of a build stage as plain text. A `Range: bytes=start-end` header can be used to page large logs. Return status 200 (or 206 for a range) on success, 404 if the stage or its logs are not found or 416 if the range is not satisfiable.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}/stages/{stage}/logs",
+responses(
+(status = 200, description = "Get build stage logs done successfully", body = String, content_type = "text/plain"),
+(status = 206, description = "Get build stage logs range done successfully", body = String, content_type = "text/plain"),
+(status = 404, description = "Build stage or logs not found"),
+(status = 416, description = "Requested range not satisfiable")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+("stage" = i32, Path, description = "Build stage number"),
+)
+)]
+pub async fn build_stage_logs_get(Path((name, application, tenant, version, build, stage)): Path<(String, String, String, String, Uuid, i32)>,
+                                  headers: HeaderMap) -> Response {
+    let req = BuildStageRequest::build_get_request(stage, build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetBuildStage { request: Box::new(req) }));
+    match message_into_result(msg).await {
+        Ok(stage) => match stage.get("logs_link").and_then(Value::as_str) {
+            Some(link) => log_file_into_response(link, headers.get(RANGE)).await,
+            None => (StatusCode::NOT_FOUND, "build stage has no logs").into_response()
+        },
+        Err(r) => r
+    }
+}
+
+async fn log_file_into_response(path: &str, range: Option<&HeaderValue>) -> Response {
+    let mut file = match File::open(path).await {
+        Ok(f) => f,
+        Err(e) => return (StatusCode::NOT_FOUND, format!("could not open logs: {}", e)).into_response()
+    };
+    let len = match file.metadata().await {
+        Ok(m) => m.len(),
+        Err(e) => return (StatusCode::NOT_FOUND, format!("could not read logs: {}", e)).into_response()
+    };
+    let range = range.and_then(|r| r.to_str().ok()).filter(|r| !r.contains(','));
+    let (status, start, end) = match range {
+        None => (StatusCode::OK, 0, len),
+        Some(r) => match byte_range(r, len) {
+            Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end + 1),
+            None => return (
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(CONTENT_RANGE, format!("bytes */{}", len))]
+            ).into_response()
+        }
+    };
+    if let Err(e) = file.seek(SeekFrom::Start(start)).await {
+        return (StatusCode::INTERNAL_SERVER_ERROR, format!("could not read logs: {}", e)).into_response()
+    }
+    // the logs of a long build are streamed rather than read in memory
+    let body = Body::from_stream(ReaderStream::new(file.take(end - start)));
+    let mut res = (status, [(CONTENT_TYPE, "text/plain; charset=utf-8"), (ACCEPT_RANGES, "bytes")], body).into_response();
+    if status == StatusCode::PARTIAL_CONTENT {
+        if let Ok(v) = HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end - 1, len)) {
+            res.headers_mut().insert(CONTENT_RANGE, v);
+        }
+    }
+    res
+}
+
+fn byte_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+    match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let n: u64 = suffix.parse().ok()?;
+            if n == 0 || len == 0 {
+                None
+            } else {
+                Some((len.saturating_sub(n), len - 1))
+            }
+        },
+        (start, "") => {
+            let start: u64 = start.parse().ok()?;
+            if start < len { Some((start, len - 1)) } else { None }
+        },
+        (start, end) => {
+            let (start, end): (u64, u64) = (start.parse().ok()?, end.parse().ok()?);
+            if start <= end && start < len { Some((start, end.min(len - 1))) } else { None }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::byte_range;
+
+    #[test]
+    fn byte_range_bounded() {
+        assert_eq!(byte_range("bytes=0-9", 100), Some((0, 9)));
+        assert_eq!(byte_range("bytes=90-200", 100), Some((90, 99)));
+        assert_eq!(byte_range(" bytes=5-5 ", 100), Some((5, 5)));
+    }
+
+    #[test]
+    fn byte_range_suffix() {
+        assert_eq!(byte_range("bytes=-10", 100), Some((90, 99)));
+        assert_eq!(byte_range("bytes=-500", 100), Some((0, 99)));
+        assert_eq!(byte_range("bytes=-0", 100), None);
+        assert_eq!(byte_range("bytes=-10", 0), None);
+    }
+
+    #[test]
+    fn byte_range_open_ended() {
+        assert_eq!(byte_range("bytes=40-", 100), Some((40, 99)));
+        assert_eq!(byte_range("bytes=99-", 100), Some((99, 99)));
+    }
+
+    #[test]
+    fn byte_range_out_of_bounds() {
+        assert_eq!(byte_range("bytes=100-", 100), None);
+        assert_eq!(byte_range("bytes=100-120", 100), None);
+        assert_eq!(byte_range("bytes=0-0", 0), None);
+    }
+
+    #[test]
+    fn byte_range_malformed() {
+        assert_eq!(byte_range("0-9", 100), None);
+        assert_eq!(byte_range("items=0-9", 100), None);
+        assert_eq!(byte_range("bytes=9-0", 100), None);
+        assert_eq!(byte_range("bytes=a-9", 100), None);
+        assert_eq!(byte_range("bytes=0", 100), None);
+        assert_eq!(byte_range("bytes=-", 100), None);
+        assert_eq!(byte_range("bytes=--5", 100), None);
+    }
+}
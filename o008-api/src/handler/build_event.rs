@@ -0,0 +1,58 @@
+use std::convert::Infallible;
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+use o008_common::{AppCommand, BuildRequest, DispatchCommand};
+use o008_message_bus::{build_event_bus, RequestMessage};
+use crate::handler::message_into_result;
+
+/// Stream the log lines and status transitions of a Service version build
+///
+/// Server-Sent Events stream that first sends the current `build` state, then `log` and `status` events as the build runs. The stream ends once the build is completed. Return status 200 on success or 404 if the build is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/builds/{build}/events",
+responses(
+(status = 200, description = "Build events stream", content_type = "text/event-stream"),
+(status = 404, description = "Build not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("build" = Uuid, Path, description = "Build id"),
+)
+)]
+pub async fn build_events_get(Path((name, application, tenant, version, build)): Path<(String, String, String, String, Uuid)>) -> Response {
+    let mut rx = build_event_bus().subscribe();
+    let req = BuildRequest::build_get_request(build, version, name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetBuild { request: Box::new(req) }));
+    match message_into_result(msg).await {
+        Ok(current) => {
+            let completed = current.get("completed").and_then(Value::as_bool).unwrap_or(false);
+            let stream = async_stream::stream! {
+                yield Ok::<Event, Infallible>(Event::default().event("build").json_data(&current).unwrap());
+                if !completed {
+                    loop {
+                        match rx.recv().await {
+                            Ok(ev) if ev.build() == build => {
+                                yield Ok(Event::default().event(ev.name()).json_data(ev.as_ref()).unwrap());
+                                if ev.is_final() {
+                                    break
+                                }
+                            },
+                            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                }
+            };
+            Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+        },
+        Err(r) => r
+    }
+}
@@ -0,0 +1,91 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, BuilderRequest, DispatchCommand, ListRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::{ListParams, message_into_response};
+
+/// Create a Builder
+///
+/// Create a Builder by name with its build stages, or a single build command, its timeout and the artifacts it produces. Return status 201 on success or 400 if the request is invalid or the Builder already exists.
+#[utoipa::path(
+post,
+path = "/builder/{builder}",
+request_body = BuilderRequest,
+responses(
+(status = 201, description = "create builder done successfully", body = Builder),
+(status = 400, description = "Invalid or duplicated builder")
+),
+params(
+("builder" = String, Path, description = "Builder name"),
+)
+)]
+pub async fn builder_post(Path(builder): Path<String>,
+                          Json(payload): Json<BuilderRequest>) -> impl IntoResponse {
+    let req = payload.with_name(builder);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CreateBuilder { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get a Builder by name
+///
+/// Get a Builder by name with its build stages. Return status 200 on success or 404 if Builder is not found.
+#[utoipa::path(
+get,
+path = "/builder/{builder}",
+responses(
+(status = 200, description = "Get builder done successfully", body = Builder),
+(status = 404, description = "Builder not found")
+),
+params(
+("builder" = String, Path, description = "Builder name"),
+)
+)]
+pub async fn builder_get(Path(builder): Path<String>) -> impl IntoResponse {
+    let req = BuilderRequest::build_get_request(builder);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetBuilder { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Delete a Builder by name
+///
+/// Delete a Builder by name. A Builder is only deleted when no Service version uses it, otherwise the response lists them. Return status 200 on success, 404 if Builder is not found, 409 if the Builder is used or 410 if the Builder can not be deleted.
+#[utoipa::path(
+delete,
+path = "/builder/{builder}",
+responses(
+(status = 200, description = "Delete builder done successfully"),
+(status = 404, description = "Builder not found"),
+(status = 409, description = "Builder used by Service versions, listed in the response body"),
+(status = 410, description = "Builder not deleted")
+),
+params(
+("builder" = String, Path, description = "Builder name"),
+)
+)]
+pub async fn builder_delete(Path(builder): Path<String>) -> impl IntoResponse {
+    let req = BuilderRequest::build_get_request(builder);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteBuilder { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// List the Builders
+///
+/// List the Builders a page at a time, filtered by name prefix and active flag and ordered by creation. Pass the `next` cursor of a page as `after` to get the following page, `next` is missing on the last page. Return status 200 on success or 400 if the request is invalid.
+#[utoipa::path(
+get,
+path = "/builder",
+responses(
+(status = 200, description = "List builders done successfully", body = BuilderPage),
+(status = 400, description = "Invalid list request")
+),
+params(
+ListParams,
+)
+)]
+pub async fn builders_get(Query(params): Query<ListParams>) -> impl IntoResponse {
+    let req = ListRequest::from(params);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListBuilders { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -0,0 +1,200 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use o008_common::{AppCommand, DeploymentHistoryRequest, DeploymentRequest, DeploymentRollbackRequest, DeploymentSlotRequest, DispatchCommand, ServiceRequest, ServiceVersionRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeploymentsParams {
+    /// Only return the deployments to this environment
+    environment: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SwitchParams {
+    /// Idle slot to switch the traffic to
+    slot: Option<String>,
+    /// Switch even if the environment is locked or frozen
+    override_lock: Option<bool>,
+    /// Why the lock is overridden, mandatory with override_lock
+    justification: Option<String>,
+    /// Who overrides the lock, mandatory with override_lock
+    overridden_by: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LockOverrideParams {
+    /// Retire even if the environment is locked or frozen
+    override_lock: Option<bool>,
+    /// Why the lock is overridden, mandatory with override_lock
+    justification: Option<String>,
+    /// Who overrides the lock, mandatory with override_lock
+    overridden_by: Option<String>,
+}
+
+/// Deploy a Service version to an environment of its tenant
+///
+/// Run the configured deploy command for the Service version and the environment, and record the deployment with the version it replaces. The deployment is returned once the command is finished, with status Succeeded or Failed. Return status 201 when the deployment is recorded, 400 if the request is invalid, 404 if Service version or environment is not found, or 423 if the environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/deploy/{environment}",
+request_body = DeploymentRequest,
+responses(
+(status = 201, description = "deployment done", body = Deployment),
+(status = 400, description = "Invalid deployment request"),
+(status = 404, description = "Service version or environment not found"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn deployment_post(Path((name, application, tenant, version, environment)): Path<(String, String, String, String, String)>,
+                             Json(payload): Json<DeploymentRequest>) -> impl IntoResponse {
+    let svr = ServiceVersionRequest::build_get_request(version, name, application, tenant);
+    let req = payload.with_target(svr, environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeployServiceVersion { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get the deployment history of a Service per environment
+///
+/// Get the deployments of every version of the Service, latest first, for each environment of its tenant in their position order. Return status 200 on success or 404 if Service or environment is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/deployments",
+responses(
+(status = 200, description = "Get deployments done successfully"),
+(status = 404, description = "Service or environment not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+DeploymentsParams,
+)
+)]
+pub async fn deployments_get(Path((name, application, tenant)): Path<(String, String, String)>,
+                             Query(params): Query<DeploymentsParams>) -> impl IntoResponse {
+    let req = DeploymentHistoryRequest::build_get_request(name, application, tenant, params.environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetDeployments { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Roll a Service back to its previous version in an environment
+///
+/// Redeploy the version of the last successful deployment before the current one, skipping the deployments of the current version and the ones already rolled back. The rollback is recorded as a new deployment linked to the deployment it reverts. For a coexisting tenant the current deployment is the one of the active slot, the previous version is redeployed to the other slot and the traffic switches to it. Return status 201 when the deployment is recorded, 400 if the request is invalid, 404 if Service, environment or a previous version is not found, or 423 if the environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/deployments/{environment}/rollback",
+request_body = DeploymentRollbackRequest,
+responses(
+(status = 201, description = "rollback deployment done", body = Deployment),
+(status = 400, description = "Invalid rollback request"),
+(status = 404, description = "Service, environment or previous version not found"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn deployment_rollback_post(Path((name, application, tenant, environment)): Path<(String, String, String, String)>,
+                                      Json(payload): Json<DeploymentRollbackRequest>) -> impl IntoResponse {
+    let srq = ServiceRequest::build_get_request(name, application, tenant);
+    let req = payload.with_target(srq, environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RollbackDeployment { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get the deployment slots of a Service in an environment
+///
+/// Get the blue/green slots of a Service of a coexisting tenant in the environment, with the version each slot runs and the one serving the traffic. Return status 200 on success, 400 if the tenant deploys in place or 404 if Service or environment is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/deployments/{environment}/slots",
+responses(
+(status = 200, description = "Get deployment slots done successfully", body = [DeploymentSlot]),
+(status = 400, description = "Tenant deploys in place"),
+(status = 404, description = "Service or environment not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn deployment_slots_get(Path((name, application, tenant, environment)): Path<(String, String, String, String)>) -> impl IntoResponse {
+    let req = DeploymentSlotRequest::build_get_request(name, application, tenant, environment, None);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetDeploymentSlots { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Switch the traffic of a Service to its idle slot in an environment
+///
+/// Run the configured switch command for the idle slot and make it the active one, the previously active slot keeps running its version until it is retired. The response holds the slots and, when the lock is overridden, the recorded override. Return status 200 on success, 400 if the tenant deploys in place or there is no idle slot, 404 if Service or environment is not found, or 423 if the environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/deployments/{environment}/switch",
+responses(
+(status = 200, description = "Switch traffic done successfully"),
+(status = 400, description = "Tenant deploys in place, no idle slot or switch command failed"),
+(status = 404, description = "Service or environment not found"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("environment" = String, Path, description = "Environment name"),
+SwitchParams,
+)
+)]
+pub async fn deployment_switch_post(Path((name, application, tenant, environment)): Path<(String, String, String, String)>,
+                                    Query(params): Query<SwitchParams>) -> impl IntoResponse {
+    let req = DeploymentSlotRequest::build_get_request(name, application, tenant, environment, params.slot)
+        .with_lock_override(params.override_lock, params.justification, params.overridden_by);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::SwitchTraffic { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Retire an idle deployment slot of a Service in an environment
+///
+/// Run the configured retire command for the slot and remove it, the slot serving the traffic can not be retired. Return status 200 with the retired slot on success, 400 if the tenant deploys in place or the slot is active, 404 if Service, environment or slot is not found, 410 if the retire command failed, or 423 if the environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/deployments/{environment}/slots/{slot}/retire",
+responses(
+(status = 200, description = "Retire slot done successfully", body = DeploymentSlot),
+(status = 400, description = "Tenant deploys in place or slot is active"),
+(status = 404, description = "Service, environment or slot not found"),
+(status = 410, description = "Retire command failed"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("environment" = String, Path, description = "Environment name"),
+("slot" = String, Path, description = "Slot name"),
+LockOverrideParams,
+)
+)]
+pub async fn deployment_slot_retire_post(Path((name, application, tenant, environment, slot)): Path<(String, String, String, String, String)>,
+                                         Query(params): Query<LockOverrideParams>) -> impl IntoResponse {
+    let req = DeploymentSlotRequest::build_get_request(name, application, tenant, environment, Some(slot))
+        .with_lock_override(params.override_lock, params.justification, params.overridden_by);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RetireSlot { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -0,0 +1,71 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, DispatchCommand, EnvironmentRequest, TenantRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+/// Create an Environment of a tenant
+///
+/// Create an Environment (dev, staging, prod...) of the tenant with the rules to promote a Service version to it, without a position it is placed after the last Environment of the tenant. Return status 201 on success, 400 if the request is invalid, the Environment already exists or it would be the first Environment and require approval, or 404 if tenant is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/environment",
+request_body = EnvironmentRequest,
+responses(
+(status = 201, description = "create environment done successfully", body = Environment),
+(status = 400, description = "Invalid or duplicated environment"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn environment_post(Path(tenant): Path<String>,
+                              Json(payload): Json<EnvironmentRequest>) -> impl IntoResponse {
+    let req = payload.with_tenant(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CreateEnvironment { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get an Environment by name and tenant name
+///
+/// Get an Environment of the tenant by name. Return status 200 on success or 404 if Environment is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/environment/{environment}",
+responses(
+(status = 200, description = "Get environment done successfully", body = Environment),
+(status = 404, description = "Environment not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("environment" = String, Path, description = "Environment name"),
+)
+)]
+pub async fn environment_get(Path((tenant, name)): Path<(String, String)>) -> impl IntoResponse {
+    let req = EnvironmentRequest::build_get_request(name, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetEnvironment { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// List the Environments of a tenant
+///
+/// List the Environments of the tenant in their position order. Return status 200 on success or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/environment",
+responses(
+(status = 200, description = "List environments done successfully", body = [Environment]),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn environments_get(Path(tenant): Path<String>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListEnvironments { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
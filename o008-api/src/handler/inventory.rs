@@ -0,0 +1,26 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, DispatchCommand, TenantRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+/// Get the inventory of a tenant
+///
+/// Get, per application and Service, the version running in each Environment of the tenant, and the drift of the Environments running another version than the Environment before them, unless that one is only ahead with a version not promoted yet. Return status 200 on success or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/inventory",
+responses(
+(status = 200, description = "Get inventory done successfully"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn inventory_get(Path(tenant): Path<String>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetInventory { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -0,0 +1,126 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use o008_common::{AppCommand, DeploymentLockRequest, DispatchCommand, FreezeWindowRequest, TenantRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+#[derive(Deserialize, IntoParams)]
+pub struct UnlockParams {
+    /// Remove the locks of this environment, the tenant wide locks when missing
+    environment: Option<String>,
+}
+
+/// Lock the deployments of a tenant
+///
+/// Lock every environment of the tenant, or only the given environment, until the lock expires or is removed. While locked, the Service versions of the tenant can not be changed and its deploy, rollback, switch and retire commands are refused unless the lock is overridden with a justification. Return status 201 when the lock is recorded, 400 if the request is invalid or 404 if tenant or environment is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/locks",
+request_body = DeploymentLockRequest,
+responses(
+(status = 201, description = "Lock deployments done successfully", body = DeploymentLock),
+(status = 400, description = "Invalid lock request"),
+(status = 404, description = "Tenant or environment not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn locks_post(Path(tenant): Path<String>,
+                        Json(payload): Json<DeploymentLockRequest>) -> impl IntoResponse {
+    let req = payload.with_tenant(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::LockDeployments { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get the deployment locks and freeze windows of a tenant
+///
+/// Get the locks and the recurring freeze windows of the tenant, including the expired locks, and the recorded lock overrides. Return status 200 on success or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/locks",
+responses(
+(status = 200, description = "Get deployment locks done successfully"),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn locks_get(Path(tenant): Path<String>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListDeploymentLocks { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Unlock the deployments of a tenant
+///
+/// Remove the tenant wide locks, or the locks of the given environment. Return status 200 with the removed locks on success, 404 if tenant, environment or lock is not found, or 410 if a lock can not be removed.
+#[utoipa::path(
+delete,
+path = "/tenant/{tenant}/locks",
+responses(
+(status = 200, description = "Unlock deployments done successfully", body = [DeploymentLock]),
+(status = 404, description = "Tenant, environment or lock not found"),
+(status = 410, description = "Lock not removed")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+UnlockParams,
+)
+)]
+pub async fn locks_delete(Path(tenant): Path<String>,
+                          Query(params): Query<UnlockParams>) -> impl IntoResponse {
+    let req = DeploymentLockRequest::build_get_request(tenant, params.environment);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::UnlockDeployments { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Create a recurring freeze window of a tenant
+///
+/// Freeze every environment of the tenant, or only the given environment, on the given week days (every day when none) between the start and end times in UTC. A window ending before it starts runs past midnight. Return status 201 when the window is recorded, 400 if the request is invalid or the window already exists, or 404 if tenant or environment is not found.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}/freeze_windows",
+request_body = FreezeWindowRequest,
+responses(
+(status = 201, description = "Create freeze window done successfully", body = FreezeWindow),
+(status = 400, description = "Invalid freeze window or window already exists"),
+(status = 404, description = "Tenant or environment not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn freeze_window_post(Path(tenant): Path<String>,
+                                Json(payload): Json<FreezeWindowRequest>) -> impl IntoResponse {
+    let req = payload.with_tenant(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CreateFreezeWindow { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Remove a freeze window of a tenant
+///
+/// Remove the freeze window by name. Return status 200 with the removed window on success, 404 if tenant or window is not found, or 410 if the window can not be removed.
+#[utoipa::path(
+delete,
+path = "/tenant/{tenant}/freeze_windows/{name}",
+responses(
+(status = 200, description = "Remove freeze window done successfully", body = FreezeWindow),
+(status = 404, description = "Tenant or freeze window not found"),
+(status = 410, description = "Freeze window not removed")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("name" = String, Path, description = "Freeze window name"),
+)
+)]
+pub async fn freeze_window_delete(Path((tenant, name)): Path<(String, String)>) -> impl IntoResponse {
+    let req = FreezeWindowRequest::build_get_request(name, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RemoveFreezeWindow { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -1,21 +1,150 @@
 use axum::http::StatusCode;
 use axum::Json;
 use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::IntoParams;
+use uuid::Uuid;
 use o008_business::dispatcher;
-use o008_common::{DispatchCommand};
+use o008_common::{DispatchCommand, ListRequest, SortOrder};
 use o008_common::error::{AppCommandError, DispatcherError, InternalCommandError};
 use o008_message_bus::{RequestMessage};
 use o008_message_bus::helper::bus_processor;
 
+mod application;
+mod approval;
+mod artifact;
+mod build;
+mod build_event;
+mod builder;
+mod deployment;
+mod environment;
+mod inventory;
+mod lock;
+mod promotion;
+mod repo_reference;
 mod service;
 mod service_version;
-pub use service::{service_get, service_put, service_versions_get};
-pub use service_version::service_version_put;
+mod tenant;
+pub use application::{application_archive_post, application_delete, application_get, application_post, application_restore_post, applications_get};
+pub use approval::{approval_policy_get, approval_policy_put, release_approve_post, release_reject_post};
+pub use artifact::{artifact_get, artifacts_get};
+pub use build::{build_cancel_post, build_get, build_post, build_retry_post, build_stage_logs_get, build_stage_rerun_post};
+pub use build_event::build_events_get;
+pub use builder::{builder_delete, builder_get, builder_post, builders_get};
+pub use deployment::{deployment_post, deployment_rollback_post, deployment_slot_retire_post, deployment_slots_get, deployment_switch_post, deployments_get};
+pub use environment::{environment_get, environment_post, environments_get};
+pub use inventory::inventory_get;
+pub use lock::{freeze_window_delete, freeze_window_post, locks_delete, locks_get, locks_post};
+pub use promotion::promotion_post;
+pub use service::{service_archive_post, service_delete, service_get, service_put, service_restore_post, service_versions_get, services_get};
+pub use service_version::{service_version_archive_post, service_version_delete, service_version_put, service_version_resolve_post, service_version_restore_post, versions_get};
+pub use repo_reference::repo_reference_delete;
+pub use tenant::{tenant_delete, tenant_get, tenant_post, tenants_get};
+pub use tenant::__path_tenant_post;
+pub use tenant::__path_tenant_get;
+pub use tenant::__path_tenants_get;
+pub use tenant::__path_tenant_delete;
+pub use application::__path_application_post;
+pub use application::__path_application_get;
+pub use application::__path_applications_get;
+pub use application::__path_application_delete;
+pub use application::__path_application_archive_post;
+pub use application::__path_application_restore_post;
+pub use builder::__path_builder_post;
+pub use builder::__path_builder_get;
+pub use builder::__path_builder_delete;
+pub use builder::__path_builders_get;
 pub use service::__path_service_get;
 pub use service::__path_service_put;
 pub use service::__path_service_versions_get;
+pub use service::__path_services_get;
+pub use service::__path_service_delete;
+pub use service::__path_service_archive_post;
+pub use service::__path_service_restore_post;
 pub use service_version::__path_service_version_put;
+pub use service_version::__path_service_version_resolve_post;
+pub use service_version::__path_versions_get;
+pub use service_version::__path_service_version_delete;
+pub use service_version::__path_service_version_archive_post;
+pub use service_version::__path_service_version_restore_post;
+pub use repo_reference::__path_repo_reference_delete;
+pub use artifact::__path_artifact_get;
+pub use artifact::__path_artifacts_get;
+pub use build::__path_build_cancel_post;
+pub use build::__path_build_get;
+pub use build::__path_build_post;
+pub use build::__path_build_retry_post;
+pub use build::__path_build_stage_logs_get;
+pub use build::__path_build_stage_rerun_post;
+pub use build_event::__path_build_events_get;
+pub use deployment::__path_deployment_post;
+pub use deployment::__path_deployment_rollback_post;
+pub use deployment::__path_deployments_get;
+pub use deployment::__path_deployment_slots_get;
+pub use deployment::__path_deployment_switch_post;
+pub use deployment::__path_deployment_slot_retire_post;
+pub use environment::__path_environment_get;
+pub use environment::__path_environment_post;
+pub use environment::__path_environments_get;
+pub use inventory::__path_inventory_get;
+pub use lock::__path_locks_post;
+pub use lock::__path_locks_get;
+pub use lock::__path_locks_delete;
+pub use lock::__path_freeze_window_post;
+pub use lock::__path_freeze_window_delete;
+pub use promotion::__path_promotion_post;
+pub use approval::__path_approval_policy_get;
+pub use approval::__path_approval_policy_put;
+pub use approval::__path_release_approve_post;
+pub use approval::__path_release_reject_post;
 
+#[derive(Deserialize, IntoParams)]
+pub struct ListParams {
+    /// Only the items whose name starts with it, the version for Service versions
+    name: Option<String>,
+    /// Only the active or inactive items, Builders only
+    active: Option<bool>,
+    /// The `next` cursor of the previous page
+    after: Option<Uuid>,
+    /// Page size from 1 to 500, 50 when missing
+    limit: Option<i64>,
+    /// Order of the items by creation, asc when missing
+    sort: Option<SortOrder>,
+    /// List the archived Applications, Services and versions too, false when missing
+    include_archived: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct CascadeParams {
+    /// Delete the item with everything referencing it in one transaction, false when missing
+    cascade: Option<bool>,
+}
+
+impl CascadeParams {
+    fn cascade(&self) -> bool {
+        self.cascade.unwrap_or(false)
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ArchivedParams {
+    /// Find the item even when it or its parents are archived, false when missing
+    include_archived: Option<bool>,
+}
+
+impl ArchivedParams {
+    fn include_archived(&self) -> bool {
+        self.include_archived.unwrap_or(false)
+    }
+}
+
+impl From<ListParams> for ListRequest {
+    fn from(params: ListParams) -> Self {
+        ListRequest::new(params.name, params.active, params.after, params.limit, params.sort)
+            .with_include_archived(params.include_archived.unwrap_or(false))
+    }
+}
 
 fn dispatch_error_into_response(e: DispatcherError) -> Response {
     match e {
@@ -27,6 +156,8 @@ fn dispatch_error_into_response(e: DispatcherError) -> Response {
                 AppCommandError::Destroy(s) => (StatusCode::GONE, s).into_response(),
                 AppCommandError::InvalidRequest(s) => (StatusCode::BAD_REQUEST, s).into_response(),
                 AppCommandError::InvalidResponse(s) => (StatusCode::UNPROCESSABLE_ENTITY, s).into_response(),
+                AppCommandError::Locked(s) => (StatusCode::LOCKED, s).into_response(),
+                AppCommandError::Conflict(v) => (StatusCode::CONFLICT, Json(v)).into_response(),
             },
         DispatcherError::InternalCommand(int_error) =>
             match int_error {
@@ -35,12 +166,16 @@ fn dispatch_error_into_response(e: DispatcherError) -> Response {
     }
 }
 
-async fn message_into_response(msg: RequestMessage<DispatchCommand>, ok_status: StatusCode) -> Response {
+async fn message_into_result(msg: RequestMessage<DispatchCommand>) -> Result<Value, Response> {
     match bus_processor(msg.clone(), dispatcher::RequestMessageCommand::from(msg)).await {
-        None => (StatusCode::NO_CONTENT, "").into_response(),
-        Some(result) => match result {
-            Ok(srv) => (ok_status, Json(srv)).into_response(),
-            Err(e) => dispatch_error_into_response(e)
-        }
+        None => Err((StatusCode::NO_CONTENT, "").into_response()),
+        Some(result) => result.map_err(dispatch_error_into_response)
+    }
+}
+
+async fn message_into_response(msg: RequestMessage<DispatchCommand>, ok_status: StatusCode) -> Response {
+    match message_into_result(msg).await {
+        Ok(srv) => (ok_status, Json(srv)).into_response(),
+        Err(r) => r
     }
 }
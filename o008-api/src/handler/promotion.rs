@@ -0,0 +1,35 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, DispatchCommand, PromotionRequest, ServiceVersionRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+/// Promote a Service version to the next environment of its tenant
+///
+/// Release the Service version to the requested environment, or to the one following the last environment running it, when it is currently deployed in the previous environment and passes the target environment rules (soak time, successful build). The release is PendingApproval when the target environment requires a manual approval, Released otherwise, and a rejected release can be promoted again. Return status 201 when the release is recorded, 400 if the request is invalid or a promotion rule is not met, 404 if Service version or environment is not found, or 423 if the target environment is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/promote",
+request_body = PromotionRequest,
+responses(
+(status = 201, description = "promotion done", body = Release),
+(status = 400, description = "Invalid promotion request or promotion rule not met"),
+(status = 404, description = "Service version or environment not found"),
+(status = 423, description = "Environment locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+)
+)]
+pub async fn promotion_post(Path((name, application, tenant, version)): Path<(String, String, String, String)>,
+                            Json(payload): Json<PromotionRequest>) -> impl IntoResponse {
+    let svr = ServiceVersionRequest::build_get_request(version, name, application, tenant);
+    let req = payload.with_target(svr);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::PromoteServiceVersion { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
@@ -0,0 +1,39 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use o008_common::{AppCommand, DispatchCommand, RepoReferenceRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::message_into_response;
+
+#[derive(Deserialize, IntoParams)]
+pub struct RepoReferenceParams {
+    /// Delete the reference with the Service versions using it in one transaction, false when missing
+    cascade: Option<bool>,
+}
+
+/// Delete a repo reference
+///
+/// Delete a repo reference by id, the id is the `_id` of the `repo_ref` of a Service version. Without cascade the reference is only deleted when no Service version or repo reference history uses it, otherwise the response lists them. With cascade the reference is deleted with them, and everything referencing those Service versions, in one transaction. Return status 200 on success, 404 if repo reference is not found, 409 if the reference is used or 410 if the reference can not be deleted.
+#[utoipa::path(
+delete,
+path = "/repo_ref/{repo_ref}",
+responses(
+(status = 200, description = "Delete repo reference done successfully"),
+(status = 404, description = "Repo reference not found"),
+(status = 409, description = "Repo reference referenced by dependents, listed in the response body"),
+(status = 410, description = "Repo reference not deleted")
+),
+params(
+("repo_ref" = Uuid, Path, description = "Repo reference id"),
+RepoReferenceParams,
+)
+)]
+pub async fn repo_reference_delete(Path(id): Path<Uuid>, Query(params): Query<RepoReferenceParams>) -> impl IntoResponse {
+    let req = RepoReferenceRequest::build_id_request(id);
+    let cascade = params.cascade.unwrap_or(false);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteRepoReference { request: req, cascade }));
+    message_into_response(msg, StatusCode::OK).await
+}
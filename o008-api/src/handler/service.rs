@@ -1,16 +1,16 @@
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::StatusCode;
 use axum::Json;
 use axum::response::IntoResponse;
-use o008_common::{DispatchCommand, ServiceRequest};
+use o008_common::{ArchiveRequest, DispatchCommand, ListRequest, ServiceRequest};
 use o008_common::AppCommand;
 use o008_message_bus::{RequestMessage};
-use crate::handler::{message_into_response};
+use crate::handler::{ArchivedParams, CascadeParams, ListParams, message_into_response};
 
 
 /// Get Service item by service name, application name and tenant name
 ///
-/// Get Service item by name, application and tenant. Return status 200 on success or 404 if Service is not found.
+/// Get Service item by name, application and tenant, an archived Service only when included. Return status 200 on success or 404 if Service is not found.
 #[utoipa::path(
     get,
     path = "/service/{service}/app/{app}/tenant/{tenant}",
@@ -22,17 +22,19 @@ use crate::handler::{message_into_response};
         ("service" = String, Path, description = "Service name"),
         ("app" = String, Path, description = "Service application name"),
         ("tenant" = String, Path, description = "Service tenant name"),
+        ArchivedParams,
     )
 )]
-pub async fn service_get(Path((name, application, tenant)): Path<(String, String, String)>) -> impl IntoResponse {
-    let req = ServiceRequest::build_get_request(name, application, tenant);
+pub async fn service_get(Path((name, application, tenant)): Path<(String, String, String)>,
+                         Query(params): Query<ArchivedParams>) -> impl IntoResponse {
+    let req = ServiceRequest::build_get_request(name, application, tenant).with_include_archived(params.include_archived());
     let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetService { request: req }));
     message_into_response(msg, StatusCode::OK).await
 }
 
 /// Get Service (with its versions) item by service name, application name and tenant name
 ///
-/// Get Service (with its versions) item by name, application and tenant. Return status 200 on success or 404 if Service is not found.
+/// Get Service (with its versions) item by name, application and tenant, the archived Service and versions only when included. Return status 200 on success or 404 if Service is not found.
 #[utoipa::path(
 get,
 path = "/service/{service}/app/{app}/tenant/{tenant}/versions",
@@ -44,10 +46,12 @@ params(
 ("service" = String, Path, description = "Service name"),
 ("app" = String, Path, description = "Service application name"),
 ("tenant" = String, Path, description = "Service tenant name"),
+ArchivedParams,
 )
 )]
-pub async fn service_versions_get(Path((name, application, tenant)): Path<(String, String, String)>) -> impl IntoResponse {
-    let req = ServiceRequest::build_get_request(name, application, tenant);
+pub async fn service_versions_get(Path((name, application, tenant)): Path<(String, String, String)>,
+                                  Query(params): Query<ArchivedParams>) -> impl IntoResponse {
+    let req = ServiceRequest::build_get_request(name, application, tenant).with_include_archived(params.include_archived());
     let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetServiceVersions { request: req }));
     message_into_response(msg, StatusCode::OK).await
 }
@@ -75,4 +79,102 @@ pub async fn service_put(Path((name, application, tenant)): Path<(String, String
     let source = ServiceRequest::build_get_request(name, application, tenant);
     let msg = RequestMessage::new(DispatchCommand::from(AppCommand::PersistService { source, request: payload }));
     message_into_response(msg, StatusCode::ACCEPTED).await
-}
\ No newline at end of file
+}
+
+/// List the Services of an Application
+///
+/// List the Services of the Application a page at a time, filtered by name prefix and ordered by creation. Pass the `next` cursor of a page as `after` to get the following page, `next` is missing on the last page. Return status 200 on success, 400 if the request is invalid or 404 if Application is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}/app/{app}/service",
+responses(
+(status = 200, description = "List services done successfully", body = ServicePage),
+(status = 400, description = "Invalid list request"),
+(status = 404, description = "Application not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+("app" = String, Path, description = "Application name"),
+ListParams,
+)
+)]
+pub async fn services_get(Path((tenant, application)): Path<(String, String)>,
+                          Query(params): Query<ListParams>) -> impl IntoResponse {
+    let req = ListRequest::from(params).with_scope(Some(tenant), Some(application), None);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListServices { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Delete Service item by service name, application name and tenant name
+///
+/// Delete Service item by name, application and tenant. Without cascade the Service is only deleted when it has no version, otherwise the response lists them. With cascade the Service is deleted with its versions and everything referencing them in one transaction. Return status 200 on success, 404 if Service is not found, 409 if the Service is referenced or 410 if the Service can not be deleted.
+#[utoipa::path(
+delete,
+path = "/service/{service}/app/{app}/tenant/{tenant}",
+responses(
+(status = 200, description = "Delete service done successfully"),
+(status = 404, description = "Service not found"),
+(status = 409, description = "Service referenced by dependents, listed in the response body"),
+(status = 410, description = "Service not deleted")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+CascadeParams,
+)
+)]
+pub async fn service_delete(Path((name, application, tenant)): Path<(String, String, String)>,
+                            Query(params): Query<CascadeParams>) -> impl IntoResponse {
+    let req = ServiceRequest::build_get_request(name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteService { request: req, cascade: params.cascade() }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Archive a Service by service name, application name and tenant name
+///
+/// Archive a Service by name, application and tenant, recording who archived it. An archived Service and its versions are hidden from the reads and lists unless archived items are included. Return status 200 on success, 400 if the request is invalid, the Service is already archived, one of its versions is deployed or pending approval, or 404 if Service is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/archive",
+request_body = ArchiveRequest,
+responses(
+(status = 200, description = "Archive service done successfully", body = Service),
+(status = 400, description = "Invalid request or service already archived"),
+(status = 404, description = "Service not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+)
+)]
+pub async fn service_archive_post(Path((name, application, tenant)): Path<(String, String, String)>,
+                                  Json(payload): Json<ArchiveRequest>) -> impl IntoResponse {
+    let req = ServiceRequest::build_get_request(name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ArchiveService { request: req, archive: payload }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Restore an archived Service by service name, application name and tenant name
+///
+/// Restore an archived Service by name, application and tenant, making it and its versions visible again. Return status 200 on success, 400 if the Service is not archived, or 404 if Service is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/restore",
+responses(
+(status = 200, description = "Restore service done successfully", body = Service),
+(status = 400, description = "Service not archived"),
+(status = 404, description = "Service not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+)
+)]
+pub async fn service_restore_post(Path((name, application, tenant)): Path<(String, String, String)>) -> impl IntoResponse {
+    let req = ServiceRequest::build_get_request(name, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RestoreService { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -1,21 +1,24 @@
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::StatusCode;
 use axum::Json;
 use axum::response::IntoResponse;
-use o008_common::{AppCommand, DispatchCommand, ServiceVersionRequest};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use o008_common::{AppCommand, ArchiveRequest, DispatchCommand, ListRequest, RepoReferenceResolveRequest, ServiceVersionRequest};
 use o008_message_bus::{RequestMessage};
-use crate::handler::{message_into_response};
+use crate::handler::{CascadeParams, ListParams, message_into_response};
 
 /// Create or Update Service item by service name, application name and tenant name
 ///
-/// Create or Update Service item by name, application and tenant. Return status 200 on success or 404 if Service is not found.
+/// Create or Update Service item by name, application and tenant. Return status 200 on success, 404 if Service is not found, or 423 if the tenant is locked or frozen and the lock is not overridden.
 #[utoipa::path(
 put,
 path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}",
 request_body = ServiceVersionRequest,
 responses(
 (status = 200, description = "create service version done successfully", body = ServiceVersion),
-(status = 404, description = "Service version not found")
+(status = 404, description = "Service version not found"),
+(status = 423, description = "Tenant locked or frozen")
 ),
 params(
 ("service" = String, Path, description = "Service name"),
@@ -28,7 +31,149 @@ pub async fn service_version_put(Path((name, application, tenant, version)): Pat
                                  Json(payload) : Json<ServiceVersionRequest>) -> impl IntoResponse {
     let source = ServiceVersionRequest::build_get_request(version, name, application, tenant);
     let msg = RequestMessage::new(
-        DispatchCommand::from(AppCommand::PersistServiceVersion { source, request: payload })
+        DispatchCommand::from(AppCommand::PersistServiceVersion { source: Box::new(source), request: Box::new(payload) })
     );
     message_into_response(msg, StatusCode::ACCEPTED).await
 }
+
+#[derive(Deserialize, IntoParams)]
+pub struct ResolveParams {
+    /// Pin the Service version to the resolved commit
+    pin: Option<bool>,
+    /// Pin even if the tenant is locked or frozen
+    override_lock: Option<bool>,
+    /// Why the lock is overridden, mandatory with override_lock
+    justification: Option<String>,
+    /// Who overrides the lock, mandatory with override_lock
+    overridden_by: Option<String>,
+}
+
+/// Resolve the repository reference of a Service version to a commit
+///
+/// Resolve the Branch or Tag reference of a Service version with a local repository to its current commit SHA. With `pin=true` the Service version is moved to a Commit reference and the replaced reference is kept in its history. Return status 200 on success, 400 if the repository is not local, 404 if Service version or reference is not found, or 423 if the tenant is locked or frozen and the lock is not overridden.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/repo_ref/resolve",
+responses(
+(status = 200, description = "repository reference resolved successfully"),
+(status = 400, description = "Repository is not local"),
+(status = 404, description = "Service version or reference not found"),
+(status = 423, description = "Tenant locked or frozen")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+ResolveParams,
+)
+)]
+pub async fn service_version_resolve_post(Path((name, application, tenant, version)): Path<(String, String, String, String)>,
+                                          Query(params): Query<ResolveParams>) -> impl IntoResponse {
+    let req = RepoReferenceResolveRequest::build_get_request(version, name, application, tenant, params.pin)
+        .with_lock_override(params.override_lock, params.justification, params.overridden_by);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ResolveRepoReference { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// List the versions of a Service
+///
+/// List the versions of the Service a page at a time, filtered by version prefix and ordered by creation. Pass the `next` cursor of a page as `after` to get the following page, `next` is missing on the last page. Return status 200 on success, 400 if the request is invalid or 404 if Service is not found.
+#[utoipa::path(
+get,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version",
+responses(
+(status = 200, description = "List service versions done successfully", body = ServiceVersionPage),
+(status = 400, description = "Invalid list request"),
+(status = 404, description = "Service not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+ListParams,
+)
+)]
+pub async fn versions_get(Path((service, application, tenant)): Path<(String, String, String)>,
+                          Query(params): Query<ListParams>) -> impl IntoResponse {
+    let req = ListRequest::from(params).with_scope(Some(tenant), Some(application), Some(service));
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListServiceVersions { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Delete Service version item by version, service name, application name and tenant name
+///
+/// Delete a version of the Service. Without cascade the version is only deleted when no build, artifact, repo reference history, deployment, release or deployment slot references it, otherwise the response lists them. With cascade the version is deleted with all of them in one transaction. Return status 200 on success, 404 if Service version is not found, 409 if the version is referenced or 410 if the version can not be deleted.
+#[utoipa::path(
+delete,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}",
+responses(
+(status = 200, description = "Delete service version done successfully"),
+(status = 404, description = "Service version not found"),
+(status = 409, description = "Service version referenced by dependents, listed in the response body"),
+(status = 410, description = "Service version not deleted")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+CascadeParams,
+)
+)]
+pub async fn service_version_delete(Path((service, application, tenant, version)): Path<(String, String, String, String)>,
+                                    Query(params): Query<CascadeParams>) -> impl IntoResponse {
+    let req = ServiceVersionRequest::build_get_request(version, service, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteServiceVersion { request: Box::new(req), cascade: params.cascade() }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Archive a Service version by version, service name, application name and tenant name
+///
+/// Archive a version of the Service, recording who archived it. An archived version is hidden from the Service versions and lists unless archived items are included. Return status 200 on success, 400 if the request is invalid, the version is already archived, deployed or pending approval, or 404 if Service version is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/archive",
+request_body = ArchiveRequest,
+responses(
+(status = 200, description = "Archive service version done successfully", body = ServiceVersion),
+(status = 400, description = "Invalid request or service version already archived"),
+(status = 404, description = "Service version not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+)
+)]
+pub async fn service_version_archive_post(Path((service, application, tenant, version)): Path<(String, String, String, String)>,
+                                          Json(payload): Json<ArchiveRequest>) -> impl IntoResponse {
+    let req = ServiceVersionRequest::build_get_request(version, service, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ArchiveServiceVersion { request: Box::new(req), archive: payload }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Restore an archived Service version by version, service name, application name and tenant name
+///
+/// Restore an archived version of the Service, making it visible again. Return status 200 on success, 400 if the version is not archived, or 404 if Service version is not found.
+#[utoipa::path(
+post,
+path = "/service/{service}/app/{app}/tenant/{tenant}/version/{version}/restore",
+responses(
+(status = 200, description = "Restore service version done successfully", body = ServiceVersion),
+(status = 400, description = "Service version not archived"),
+(status = 404, description = "Service version not found")
+),
+params(
+("service" = String, Path, description = "Service name"),
+("app" = String, Path, description = "Service application name"),
+("tenant" = String, Path, description = "Service tenant name"),
+("version" = String, Path, description = "Service version"),
+)
+)]
+pub async fn service_version_restore_post(Path((service, application, tenant, version)): Path<(String, String, String, String)>) -> impl IntoResponse {
+    let req = ServiceVersionRequest::build_get_request(version, service, application, tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::RestoreServiceVersion { request: Box::new(req) }));
+    message_into_response(msg, StatusCode::OK).await
+}
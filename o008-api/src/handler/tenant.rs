@@ -0,0 +1,93 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::response::IntoResponse;
+use o008_common::{AppCommand, DispatchCommand, ListRequest, TenantRequest};
+use o008_message_bus::RequestMessage;
+use crate::handler::{CascadeParams, ListParams, message_into_response};
+
+/// Create a tenant
+///
+/// Create a tenant by name, a coexisting tenant runs several versions of its Services side by side in blue/green slots. Return status 201 on success or 400 if the request is invalid or the tenant already exists.
+#[utoipa::path(
+post,
+path = "/tenant/{tenant}",
+request_body = TenantRequest,
+responses(
+(status = 201, description = "create tenant done successfully", body = Tenant),
+(status = 400, description = "Invalid or duplicated tenant")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn tenant_post(Path(tenant): Path<String>,
+                         Json(payload): Json<TenantRequest>) -> impl IntoResponse {
+    let req = payload.with_name(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::CreateTenant { request: req }));
+    message_into_response(msg, StatusCode::CREATED).await
+}
+
+/// Get a tenant by name
+///
+/// Get a tenant by name. Return status 200 on success or 404 if tenant is not found.
+#[utoipa::path(
+get,
+path = "/tenant/{tenant}",
+responses(
+(status = 200, description = "Get tenant done successfully", body = Tenant),
+(status = 404, description = "Tenant not found")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+)
+)]
+pub async fn tenant_get(Path(tenant): Path<String>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::GetTenant { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// List the tenants
+///
+/// List the tenants a page at a time, filtered by name prefix and ordered by creation. Pass the `next` cursor of a page as `after` to get the following page, `next` is missing on the last page. Return status 200 on success or 400 if the request is invalid.
+#[utoipa::path(
+get,
+path = "/tenant",
+responses(
+(status = 200, description = "List tenants done successfully", body = TenantPage),
+(status = 400, description = "Invalid list request")
+),
+params(
+ListParams,
+)
+)]
+pub async fn tenants_get(Query(params): Query<ListParams>) -> impl IntoResponse {
+    let req = ListRequest::from(params);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::ListTenants { request: req }));
+    message_into_response(msg, StatusCode::OK).await
+}
+
+/// Delete a tenant by name
+///
+/// Delete a tenant by name. Without cascade the tenant is only deleted when no Application, environment, deployment lock or freeze window references it, otherwise the response lists them. With cascade the tenant is deleted with its whole subtree in one transaction. Return status 200 on success, 404 if tenant is not found, 409 if the tenant is referenced or 410 if the tenant can not be deleted.
+#[utoipa::path(
+delete,
+path = "/tenant/{tenant}",
+responses(
+(status = 200, description = "Delete tenant done successfully"),
+(status = 404, description = "Tenant not found"),
+(status = 409, description = "Tenant referenced by dependents, listed in the response body"),
+(status = 410, description = "Tenant not deleted")
+),
+params(
+("tenant" = String, Path, description = "Tenant name"),
+CascadeParams,
+)
+)]
+pub async fn tenant_delete(Path(tenant): Path<String>,
+                           Query(params): Query<CascadeParams>) -> impl IntoResponse {
+    let req = TenantRequest::build_get_request(tenant);
+    let msg = RequestMessage::new(DispatchCommand::from(AppCommand::DeleteTenant { request: req, cascade: params.cascade() }));
+    message_into_response(msg, StatusCode::OK).await
+}
@@ -1,4 +1,5 @@
 use tracing::info;
+use o008_business::executor::BuildWorker;
 use o008_setting::{app_args, app_config, AppLogLevel, initialize_tracing};
 use crate::router::router_o008_v1;
 
@@ -12,6 +13,7 @@ async fn main() {
     tracing::subscriber::set_global_default(initialize_tracing()).expect("could not initialize tracing");
     info!("tracing level: {:?}", app_args().log.unwrap_or(AppLogLevel::Off));
 
+    BuildWorker::launch();
     let app = router_o008_v1();
     let listener = tokio::net::TcpListener::bind(app_config().deployment_api().address()).await.unwrap();
     info!("listening on: {}", listener.local_addr().unwrap());
@@ -1,5 +1,5 @@
 use axum::Router;
-use axum::routing::{get, put};
+use axum::routing::{delete, get, post, put};
 use crate::handler;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -8,10 +8,63 @@ use utoipa_swagger_ui::SwaggerUi;
 #[derive(OpenApi)]
 #[openapi(
     paths(
+        handler::tenant_post,
+        handler::tenant_get,
+        handler::tenants_get,
+        handler::tenant_delete,
+        handler::application_post,
+        handler::application_get,
+        handler::applications_get,
+        handler::application_delete,
+        handler::application_archive_post,
+        handler::application_restore_post,
+        handler::builder_post,
+        handler::builder_get,
+        handler::builder_delete,
+        handler::builders_get,
         handler::service_get,
         handler::service_put,
+        handler::services_get,
+        handler::service_delete,
+        handler::service_archive_post,
+        handler::service_restore_post,
         handler::service_version_put,
+        handler::service_version_resolve_post,
+        handler::versions_get,
+        handler::service_version_delete,
+        handler::service_version_archive_post,
+        handler::service_version_restore_post,
+        handler::repo_reference_delete,
         handler::service_versions_get,
+        handler::build_post,
+        handler::build_get,
+        handler::build_cancel_post,
+        handler::build_retry_post,
+        handler::build_stage_rerun_post,
+        handler::build_stage_logs_get,
+        handler::build_events_get,
+        handler::artifacts_get,
+        handler::artifact_get,
+        handler::deployment_post,
+        handler::deployments_get,
+        handler::deployment_rollback_post,
+        handler::deployment_slots_get,
+        handler::deployment_switch_post,
+        handler::deployment_slot_retire_post,
+        handler::promotion_post,
+        handler::release_approve_post,
+        handler::release_reject_post,
+        handler::environment_post,
+        handler::environment_get,
+        handler::environments_get,
+        handler::inventory_get,
+        handler::approval_policy_put,
+        handler::approval_policy_get,
+        handler::locks_post,
+        handler::locks_get,
+        handler::locks_delete,
+        handler::freeze_window_post,
+        handler::freeze_window_delete,
     ),
     components(
         schemas(
@@ -20,15 +73,56 @@ use utoipa_swagger_ui::SwaggerUi;
             o008_entity::Service,
             o008_entity::ServiceVersion,
             o008_entity::ServiceVersionItem,
+            o008_entity::ServiceVersionBuild,
+            o008_entity::ServiceVersionBuildStage,
+            o008_entity::ServiceVersionRepoRefHistory,
+            o008_entity::ServiceVersionArtifact,
             o008_entity::RepoReference,
             o008_entity::Tenant,
+            o008_entity::Environment,
+            o008_entity::Deployment,
+            o008_entity::DeploymentSlot,
+            o008_entity::DeploymentLock,
+            o008_entity::FreezeWindow,
+            o008_entity::LockOverride,
+            o008_entity::Release,
+            o008_entity::ReleaseApproval,
+            o008_entity::TenantPage,
+            o008_entity::ApplicationPage,
+            o008_entity::BuilderPage,
+            o008_entity::ServicePage,
+            o008_entity::ServiceVersionPage,
+            o008_entity::Dependent,
             o008_common::BuilderRequest,
+            o008_common::BuilderStage,
             o008_common::TenantRequest,
             o008_common::RepoReferenceKind,
             o008_common::ApplicationRequest,
+            o008_common::EnvironmentRequest,
             o008_common::RepoReferenceRequest,
+            o008_common::RepoReferenceResolveRequest,
             o008_common::ServiceRequest,
             o008_common::ServiceVersionRequest,
+            o008_common::BuildStatus,
+            o008_common::BuildRequest,
+            o008_common::BuildTriggerRequest,
+            o008_common::DeploymentStatus,
+            o008_common::DeploymentRequest,
+            o008_common::DeploymentHistoryRequest,
+            o008_common::DeploymentRollbackRequest,
+            o008_common::DeploymentSlotRequest,
+            o008_common::PromotionRequest,
+            o008_common::ReleaseStatus,
+            o008_common::ApprovalRequest,
+            o008_common::ApprovalPolicyRequest,
+            o008_common::ApprovalDecision,
+            o008_common::DeploymentLockRequest,
+            o008_common::FreezeWindowRequest,
+            o008_common::BuildStageRequest,
+            o008_common::ArtifactRequest,
+            o008_common::ArchiveRequest,
+            o008_common::ListRequest,
+            o008_common::SortOrder,
         ),
     )
 )]
@@ -36,9 +130,62 @@ struct ApiDocV1;
 
 pub fn router_o008_v1() -> Router {
     Router::new()
+        .route("/tenant", get(handler::tenants_get))
+        .route("/tenant/:tenant", post(handler::tenant_post))
+        .route("/tenant/:tenant", get(handler::tenant_get))
+        .route("/tenant/:tenant", delete(handler::tenant_delete))
+        .route("/tenant/:tenant/app/:app", post(handler::application_post))
+        .route("/tenant/:tenant/app/:app", get(handler::application_get))
+        .route("/tenant/:tenant/app/:app", delete(handler::application_delete))
+        .route("/tenant/:tenant/app/:app/archive", post(handler::application_archive_post))
+        .route("/tenant/:tenant/app/:app/restore", post(handler::application_restore_post))
+        .route("/tenant/:tenant/app", get(handler::applications_get))
+        .route("/tenant/:tenant/app/:app/service", get(handler::services_get))
+        .route("/builder", get(handler::builders_get))
+        .route("/builder/:builder", post(handler::builder_post))
+        .route("/builder/:builder", get(handler::builder_get))
+        .route("/builder/:builder", delete(handler::builder_delete))
         .route("/service/:service/app/:app/tenant/:tenant", get(handler::service_get))
         .route("/service/:service/app/:app/tenant/:tenant", put(handler::service_put))
+        .route("/service/:service/app/:app/tenant/:tenant", delete(handler::service_delete))
+        .route("/service/:service/app/:app/tenant/:tenant/archive", post(handler::service_archive_post))
+        .route("/service/:service/app/:app/tenant/:tenant/restore", post(handler::service_restore_post))
         .route("/service/:service/app/:app/tenant/:tenant/version/:version", put(handler::service_version_put))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version", delete(handler::service_version_delete))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/archive", post(handler::service_version_archive_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/restore", post(handler::service_version_restore_post))
+        .route("/repo_ref/:repo_ref", delete(handler::repo_reference_delete))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/repo_ref/resolve", post(handler::service_version_resolve_post))
         .route("/service/:service/app/:app/tenant/:tenant/versions", get(handler::service_versions_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version", get(handler::versions_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/build", post(handler::build_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build", get(handler::build_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build/cancel", post(handler::build_cancel_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build/retry", post(handler::build_retry_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build/stages/:stage/rerun", post(handler::build_stage_rerun_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build/stages/:stage/logs", get(handler::build_stage_logs_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/builds/:build/events", get(handler::build_events_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/artifacts", get(handler::artifacts_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/artifacts/:artifact", get(handler::artifact_get))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/deploy/:environment", post(handler::deployment_post))
+        .route("/service/:service/app/:app/tenant/:tenant/deployments", get(handler::deployments_get))
+        .route("/service/:service/app/:app/tenant/:tenant/deployments/:environment/rollback", post(handler::deployment_rollback_post))
+        .route("/service/:service/app/:app/tenant/:tenant/deployments/:environment/slots", get(handler::deployment_slots_get))
+        .route("/service/:service/app/:app/tenant/:tenant/deployments/:environment/switch", post(handler::deployment_switch_post))
+        .route("/service/:service/app/:app/tenant/:tenant/deployments/:environment/slots/:slot/retire", post(handler::deployment_slot_retire_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/promote", post(handler::promotion_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/releases/:environment/approve", post(handler::release_approve_post))
+        .route("/service/:service/app/:app/tenant/:tenant/version/:version/releases/:environment/reject", post(handler::release_reject_post))
+        .route("/tenant/:tenant/environment", post(handler::environment_post))
+        .route("/tenant/:tenant/environment", get(handler::environments_get))
+        .route("/tenant/:tenant/environment/:environment", get(handler::environment_get))
+        .route("/tenant/:tenant/inventory", get(handler::inventory_get))
+        .route("/tenant/:tenant/approval_policy", put(handler::approval_policy_put))
+        .route("/tenant/:tenant/approval_policy", get(handler::approval_policy_get))
+        .route("/tenant/:tenant/locks", post(handler::locks_post))
+        .route("/tenant/:tenant/locks", get(handler::locks_get))
+        .route("/tenant/:tenant/locks", delete(handler::locks_delete))
+        .route("/tenant/:tenant/freeze_windows", post(handler::freeze_window_post))
+        .route("/tenant/:tenant/freeze_windows/:name", delete(handler::freeze_window_delete))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDocV1::openapi()))
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
 use serde_json::{to_value, Value};
 use tracing::info;
 
-use o008_common::{ApplicationRequest, RequestValidator, DispatchResult};
-use o008_entity::{Application, persist_json, QueryEntity, Tenant};
+use o008_common::{ApplicationRequest, ArchiveRequest, ListRequest, RequestValidator, DispatchResult};
+use o008_entity::{Application, DalFilter, destroy_checked, persist_json, QueryEntity, Tenant};
+use crate::action::destroy_result;
 
-use o008_common::error::AppCommandError::{Create, InvalidRequest, InvalidResponse, NotFound};
+use o008_common::error::AppCommandError::{Create, InvalidRequest, InvalidResponse, NotFound, Update};
 use o008_common::error::DispatcherError;
 
 pub async fn create(arq: ApplicationRequest) -> DispatchResult<Value> {
@@ -40,3 +41,64 @@ pub async fn get(arq: ApplicationRequest) -> DispatchResult<Value> {
         Err(e) => Err(DispatcherError::AppCommand(InvalidRequest(e.to_string())))
     }
 }
+
+pub async fn list(lrq: ListRequest) -> DispatchResult<Value> {
+    info!("list applications {:?}", lrq);
+    match (lrq.is_valid_get(), lrq.tenant()) {
+        (Ok(()), Some(trq)) => {
+            let tenant = Tenant::read(to_value(trq).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+            match Application::list(DalFilter::new(Some(tenant.id()), lrq.name().map(|n| n.as_str())).with_archived(lrq.include_archived()), &lrq).await {
+                Ok(page) => Ok(to_value(page).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+            }
+        },
+        (Ok(()), None) => Err(DispatcherError::from(InvalidRequest(String::from("list action: tenant is mandatory")))),
+        (Err(e), _) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn delete(arq: ApplicationRequest, cascade: bool) -> DispatchResult<Value> {
+    info!("delete application {:?} cascade {}", arq, cascade);
+    match arq.is_valid_get() {
+        Ok(()) => match Application::read(to_value(&arq).unwrap()).await {
+            Ok(app) => destroy_result(destroy_checked(app.as_ref(), cascade).await, format!("application '{}'", app.name())),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("delete action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
+    }
+}
+
+pub async fn archive(arq: ApplicationRequest, ar: ArchiveRequest) -> DispatchResult<Value> {
+    info!("archive application {:?} by {:?}", arq, ar);
+    match (arq.is_valid_get(), ar.valid_archived_by()) {
+        (Ok(()), Ok(archived_by)) => match Application::read(to_value(arq.with_include_archived(true)).unwrap()).await {
+            Ok(app) if app.is_archived() =>
+                Err(DispatcherError::from(Update(format!("archive action: application '{}' is already archived", app.name())))),
+            Ok(mut app) => {
+                app.archive(archived_by);
+                let r = persist_json(app.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("archive action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("archive action: {}", e))))
+        },
+        (Err(e), _) | (_, Err(e)) => Err(DispatcherError::from(InvalidRequest(format!("archive action: {}", e))))
+    }
+}
+
+pub async fn restore(arq: ApplicationRequest) -> DispatchResult<Value> {
+    info!("restore application {:?}", arq);
+    match arq.is_valid_get() {
+        Ok(()) => match Application::read(to_value(arq.with_include_archived(true)).unwrap()).await {
+            Ok(app) if !app.is_archived() =>
+                Err(DispatcherError::from(Update(format!("restore action: application '{}' is not archived", app.name())))),
+            Ok(mut app) => {
+                app.restore();
+                let r = persist_json(app.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("restore action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("restore action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("restore action: {}", e))))
+    }
+}
@@ -0,0 +1,100 @@
+use serde_json::{json, to_value, Value};
+use tracing::info;
+use o008_common::{ApprovalDecision, ApprovalPolicyRequest, ApprovalRequest, DispatcherError, DispatchResult, ReleaseStatus, RequestValidator, TenantRequest};
+use o008_common::AppCommandError::{Create, InvalidRequest, NotFound, Update};
+use o008_entity::{Environment, PersistEntity, QueryEntity, Release, ReleaseApproval, ServiceVersion, Tenant};
+use crate::action::lock::{ensure_unlocked, record_override};
+
+pub async fn approve(arq: ApprovalRequest) -> DispatchResult<Value> {
+    info!("approve release {:?}", arq);
+    decide(arq, ApprovalDecision::Approved, "approve").await
+}
+
+pub async fn reject(arq: ApprovalRequest) -> DispatchResult<Value> {
+    info!("reject release {:?}", arq);
+    decide(arq, ApprovalDecision::Rejected, "reject").await
+}
+
+pub async fn set_policy(apr: ApprovalPolicyRequest) -> DispatchResult<Value> {
+    info!("set approval policy {:?}", apr);
+    match apr.is_valid_create() {
+        Ok(()) => {
+            let tenant = Tenant::read(to_value(apr.tenant().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("set policy action: {}", e))))?;
+            let environments = Environment::tenant_environments(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("set policy action: {}", e))))?;
+            let required = apr.environments().unwrap();
+            if let Some(unknown) = required.iter().find(|name| !environments.iter().any(|e| e.name() == name.as_str())) {
+                return Err(DispatcherError::from(NotFound(format!("set policy action: environment {} not found", unknown))))
+            }
+            // nothing is promoted to the first environment, so a release there is never pending an approval
+            if let Some(first) = environments.first().filter(|e| required.contains(&String::from(e.name()))) {
+                return Err(DispatcherError::from(InvalidRequest(format!("set policy action: the first environment {} can not require approval", first.name()))))
+            }
+            for mut environment in environments {
+                let require_approval = required.contains(&String::from(environment.name()));
+                if environment.require_approval() != require_approval {
+                    environment.set_require_approval(require_approval);
+                    environment.persist().await
+                        .map_err(|e| DispatcherError::from(Update(format!("set policy action: {}", e))))?;
+                }
+            }
+            approval_policy(&tenant, "set policy").await
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("set policy action: {}", e))))
+    }
+}
+
+pub async fn get_policy(trq: TenantRequest) -> DispatchResult<Value> {
+    info!("get approval policy {:?}", trq);
+    match trq.is_valid_get() {
+        Ok(()) => match Tenant::read(to_value(trq).unwrap()).await {
+            Ok(tenant) => approval_policy(&tenant, "get policy").await,
+            Err(e) => Err(DispatcherError::from(NotFound(format!("get policy action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get policy action: {}", e))))
+    }
+}
+
+async fn approval_policy(tenant: &Tenant, action: &str) -> DispatchResult<Value> {
+    let environments: Vec<String> = Environment::tenant_environments(tenant.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?
+        .into_iter()
+        .filter(|e| e.require_approval())
+        .map(|e| String::from(e.name()))
+        .collect();
+    Ok(json!({"tenant": tenant, "environments": environments}))
+}
+
+async fn decide(arq: ApprovalRequest, decision: ApprovalDecision, action: &str) -> DispatchResult<Value> {
+    match arq.is_valid_create() {
+        Ok(()) => {
+            let sv = ServiceVersion::read(to_value(arq.service_version().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+            let tenant = sv.service().application().tenant();
+            let environment = Environment::read(json!({"name": arq.environment().unwrap(), "tenant": {"id": tenant.id()}})).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+            let mut release = Release::read(json!({"service_version": sv.id(), "environment": environment.id()})).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+            if release.status() != ReleaseStatus::PendingApproval {
+                return Err(DispatcherError::from(InvalidRequest(format!("{} action: release of version {} to {} is {}, not pending approval", action, sv.version(), environment.name(), release.status()))))
+            }
+            if release.promoted_by() == arq.approver().unwrap() {
+                return Err(DispatcherError::from(InvalidRequest(format!("{} action: release of version {} to {} can not be decided by {} who promoted it", action, sv.version(), environment.name(), release.promoted_by()))))
+            }
+            // an approved release can be deployed, so it is a change of the environment
+            let lock_override = if decision == ApprovalDecision::Approved {
+                ensure_unlocked(&tenant, Some(&environment), arq.lock_override(), action).await?
+            } else {
+                None
+            };
+            release.set_status(if decision == ApprovalDecision::Approved { ReleaseStatus::Released } else { ReleaseStatus::Rejected });
+            let approval = ReleaseApproval::new(*release, decision, arq.approver().unwrap(), arq.comment().map(|c| c.as_str()));
+            match approval.persist_deciding().await {
+                Ok(a) => record_override(to_value(*a).unwrap(), lock_override, action).await,
+                Err(e) => Err(DispatcherError::from(Create(format!("{} action: {}", action, e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("{} action: {}", action, e))))
+    }
+}
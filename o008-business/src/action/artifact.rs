@@ -0,0 +1,40 @@
+use serde_json::{to_value, Value};
+use tracing::info;
+use o008_common::{ArtifactRequest, DispatcherError, DispatchResult, RequestValidator, ServiceVersionRequest};
+use o008_common::AppCommandError::{InvalidRequest, NotFound};
+use o008_entity::{QueryEntity, ServiceVersion, ServiceVersionArtifact, ServiceVersionArtifactKey};
+
+pub async fn list(svr: ServiceVersionRequest) -> DispatchResult<Value> {
+    info!("get artifacts {:?}", svr);
+    match svr.is_valid_get() {
+        Ok(()) => match ServiceVersion::read(to_value(&svr).unwrap()).await {
+            Ok(sv) => match ServiceVersionArtifact::service_version_artifacts(sv.id()).await {
+                Ok(artifacts) => Ok(to_value(artifacts).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn get(arq: ArtifactRequest) -> DispatchResult<Value> {
+    info!("get artifact {:?}", arq);
+    match arq.is_valid_get() {
+        Ok(()) => {
+            let id = arq.id().unwrap();
+            match ServiceVersionArtifact::read(ServiceVersionArtifactKey::Id(id)).await {
+                Ok(artifact) => match arq.service_version() {
+                    Some(svr) => match ServiceVersion::read(to_value(svr).unwrap()).await {
+                        Ok(sv) if sv.id() == artifact.service_version() => Ok(to_value(*artifact).unwrap()),
+                        Ok(sv) => Err(DispatcherError::from(NotFound(format!("artifact {} does not belong to service version {}", id, sv.version())))),
+                        Err(e) => Err(DispatcherError::from(NotFound(format!("get action: {}", e))))
+                    },
+                    None => Ok(to_value(*artifact).unwrap())
+                },
+                Err(e) => Err(DispatcherError::from(NotFound(format!("get action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get action: {}", e))))
+    }
+}
@@ -0,0 +1,199 @@
+use serde_json::{to_value, Value};
+use tracing::{info, warn};
+use o008_common::{BuildRequest, BuildStageRequest, BuildStatus, BuildTriggerRequest, DispatcherError, DispatchResult, RepoReferenceKind, RequestValidator};
+use o008_common::AppCommandError::{Create, InvalidRequest, NotFound, Update};
+use o008_entity::{PersistEntity, QueryEntity, ServiceVersion, ServiceVersionArtifact, ServiceVersionBuild, ServiceVersionBuildKey, ServiceVersionBuildStage, ServiceVersionBuildStageKey};
+use o008_message_bus::BuildEvent;
+use o008_message_bus::helper::send_build_event;
+use crate::executor::{BUILD_CANCELLED, builder_digest, render_commands};
+use crate::git::{is_local_repo, resolve_reference};
+
+pub async fn trigger(btr: BuildTriggerRequest) -> DispatchResult<Value> {
+    info!("trigger build {:?}", btr);
+    match btr.is_valid_create() {
+        Ok(()) => match ServiceVersion::read(to_value(btr.service_version()).unwrap()).await {
+            Ok(sv) => {
+                if !btr.force() {
+                    if let Some(hit) = cache_hit(&sv).await {
+                        return complete_from_cache(*sv, &hit).await
+                    }
+                }
+                queue_build(ServiceVersionBuild::new(*sv), "trigger").await
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("trigger action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("trigger action: {}", e))))
+    }
+}
+
+pub async fn get(brq: BuildRequest) -> DispatchResult<Value> {
+    info!("get build {:?}", brq);
+    match brq.is_valid_get() {
+        Ok(()) => {
+            let mut build = read_build(brq).await?;
+            if let Ok(stages) = ServiceVersionBuildStage::build_stages(build.id()).await {
+                build.set_stages(stages)
+            }
+            if let Err(e) = build.load_queue_position().await {
+                warn!("get action: {}", e)
+            }
+            Ok(to_value(*build).unwrap())
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get action: {}", e))))
+    }
+}
+
+pub async fn get_stage(bsr: BuildStageRequest) -> DispatchResult<Value> {
+    info!("get build stage {:?}", bsr);
+    match bsr.is_valid_get() {
+        Ok(()) => {
+            let build = read_build(bsr.build().unwrap()).await?;
+            match ServiceVersionBuildStage::read(ServiceVersionBuildStageKey::Natural { build: build.id(), stage: bsr.stage().unwrap() }).await {
+                Ok(stage) => Ok(to_value(*stage).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("get stage action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get stage action: {}", e))))
+    }
+}
+
+pub async fn cancel(brq: BuildRequest) -> DispatchResult<Value> {
+    info!("cancel build {:?}", brq);
+    match brq.is_valid_get() {
+        Ok(()) => {
+            let build = read_build(brq).await?;
+            if build.completed() {
+                return Err(DispatcherError::from(InvalidRequest(format!("cancel action: build {} is already completed", build.id()))))
+            }
+            match build.cancel_queued(BUILD_CANCELLED).await {
+                Ok(Some(b)) => {
+                    send_build_event(BuildEvent::status(b.id(), None, b.status()));
+                    info!("queued build {} cancelled", b.id());
+                    Ok(to_value(*b).unwrap())
+                },
+                Ok(None) => match build.request_cancel().await {
+                    Ok(()) => match ServiceVersionBuild::read(ServiceVersionBuildKey::Id(build.id())).await {
+                        Ok(b) => {
+                            info!("running build {} cancel requested", b.id());
+                            Ok(to_value(*b).unwrap())
+                        },
+                        Err(e) => Err(DispatcherError::from(NotFound(format!("cancel action: {}", e))))
+                    },
+                    Err(e) => Err(DispatcherError::from(Update(format!("cancel action: {}", e))))
+                },
+                Err(e) => Err(DispatcherError::from(Update(format!("cancel action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("cancel action: {}", e))))
+    }
+}
+
+pub async fn retry(brq: BuildRequest) -> DispatchResult<Value> {
+    info!("retry build {:?}", brq);
+    match brq.is_valid_get() {
+        Ok(()) => {
+            let build = read_build(brq).await?;
+            if !build.completed() {
+                return Err(DispatcherError::from(InvalidRequest(format!("retry action: build {} is not completed", build.id()))))
+            }
+            queue_build(build.retry(), "retry").await
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("retry action: {}", e))))
+    }
+}
+
+pub async fn rerun_from_stage(bsr: BuildStageRequest) -> DispatchResult<Value> {
+    info!("rerun build from stage {:?}", bsr);
+    match bsr.is_valid_get() {
+        Ok(()) => {
+            let build = read_build(bsr.build().unwrap()).await?;
+            let from_stage = bsr.stage().unwrap();
+            if !build.completed() {
+                return Err(DispatcherError::from(InvalidRequest(format!("rerun action: build {} is not completed", build.id()))))
+            }
+            let stages = ServiceVersionBuildStage::build_stages(build.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("rerun action: {}", e))))?;
+            if !stages.iter().any(|s| s.stage() == from_stage) {
+                return Err(DispatcherError::from(NotFound(format!("rerun action: build {} has no stage {}", build.id(), from_stage))))
+            }
+            // every stage before the rerun one is reused, so it has to be a success
+            if let Some(s) = stages.iter().find(|s| s.stage() < from_stage && s.status() != BuildStatus::Succeeded) {
+                return Err(DispatcherError::from(InvalidRequest(format!("rerun action: stage {} of build {} can not be reused, its status is {}", s.stage(), build.id(), s.status()))))
+            }
+            queue_build(build.rerun(from_stage), "rerun").await
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("rerun action: {}", e))))
+    }
+}
+
+async fn queue_build(build: ServiceVersionBuild, action: &str) -> DispatchResult<Value> {
+    let builder = build.service_version().builder();
+    if !builder.active() {
+        return Err(DispatcherError::from(InvalidRequest(format!("{} action: builder {} is not active", action, builder.name()))))
+    }
+    match build.persist().await {
+        Ok(mut b) => {
+            if let Err(e) = b.load_queue_position().await {
+                warn!("{} action: {}", action, e)
+            }
+            info!("build {} queued", b.id());
+            Ok(to_value(*b).unwrap())
+        },
+        Err(e) => Err(DispatcherError::from(Create(format!("{} action: {}", action, e))))
+    }
+}
+
+// the last successful build of the commit the service version points to, with the same builder definition
+async fn cache_hit(sv: &ServiceVersion) -> Option<Box<ServiceVersionBuild>> {
+    let repo_ref = sv.repo_ref();
+    // only a local repository can be resolved without a checkout
+    if repo_ref.kind() != RepoReferenceKind::Commit && !is_local_repo(sv.repo()) {
+        return None
+    }
+    let commit = match resolve_reference(sv.repo(), repo_ref.kind(), repo_ref.reference()).await {
+        Ok(commit) => commit,
+        Err(e) => {
+            warn!("trigger action: no build cache for service version {}: {}", sv.id(), e);
+            return None
+        }
+    };
+    let commands = render_commands(sv.builder(), sv).ok()?;
+    match ServiceVersionBuild::cache_hit(commit.as_str(), builder_digest(sv.builder(), &commands).as_str()).await {
+        Ok(hit) => hit,
+        Err(e) => {
+            warn!("trigger action: {}", e);
+            None
+        }
+    }
+}
+
+async fn complete_from_cache(sv: ServiceVersion, from: &ServiceVersionBuild) -> DispatchResult<Value> {
+    let artifacts = ServiceVersionArtifact::build_artifacts(from.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("trigger action: {}", e))))?;
+    let build = ServiceVersionBuild::cached(sv, from).persist().await
+        .map_err(|e| DispatcherError::from(Create(format!("trigger action: {}", e))))?;
+    // the stored files are shared, only the artifact records are copied
+    for artifact in artifacts {
+        ServiceVersionArtifact::new(build.service_version().id(), build.id(), artifact.name(), artifact.size(), artifact.sha256())
+            .persist().await
+            .map_err(|e| DispatcherError::from(Create(format!("trigger action: {}", e))))?;
+    }
+    send_build_event(BuildEvent::status(build.id(), None, build.status()));
+    info!("build {} cached from build {}", build.id(), from.id());
+    Ok(to_value(*build).unwrap())
+}
+
+async fn read_build(brq: BuildRequest) -> DispatchResult<Box<ServiceVersionBuild>> {
+    let id = brq.id().unwrap();
+    match ServiceVersionBuild::read(ServiceVersionBuildKey::Id(id)).await {
+        Ok(build) => match brq.service_version() {
+            Some(svr) => match ServiceVersion::read(to_value(svr).unwrap()).await {
+                Ok(sv) if sv.id() == build.service_version().id() => Ok(build),
+                Ok(sv) => Err(DispatcherError::from(NotFound(format!("build {} does not belong to service version {}", id, sv.version())))),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("read build: {}", e))))
+            },
+            None => Ok(build)
+        },
+        Err(e) => Err(DispatcherError::from(NotFound(format!("read build: {}", e))))
+    }
+}
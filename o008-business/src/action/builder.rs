@@ -1,10 +1,12 @@
-use serde_json::{to_value, Value};
+use serde_json::{json, to_value, Value};
 use tracing::info;
 
-use o008_common::{BuilderRequest, RequestValidator, DispatchResult};
-use o008_entity::{Builder, DestroyEntity, persist_json, QueryEntity};
+use o008_common::{BuilderRequest, ListRequest, RequestValidator, DispatchResult, ServiceVersionRequest};
+use o008_entity::{Builder, DalFilter, destroy_checked, persist_json, QueryEntity, ServiceVersion};
+use crate::action::destroy_result;
+use crate::executor::render_commands;
 
-use o008_common::error::AppCommandError::{Create, Destroy, InvalidRequest, NotFound};
+use o008_common::error::AppCommandError::{Create, InvalidRequest, NotFound};
 use o008_common::error::DispatcherError;
 
 pub async fn create(brq: BuilderRequest) -> DispatchResult<Value> {
@@ -34,12 +36,60 @@ pub async fn delete(brq: BuilderRequest) -> DispatchResult<serde_json::Value> {
     info!("update builder {:?}", &brq);
     match brq.is_valid_get() {
         Ok(()) => match Builder::read(to_value(&brq).unwrap()).await {
-            Ok(b) => match b.destroy().await {
-                Ok(_) => Ok(serde_json::Value::Null),
-                Err(e) => Err(DispatcherError::from(Destroy(format!("delete action: {}", e))))
-            },
+            Ok(b) => destroy_result(destroy_checked(b.as_ref(), false).await, format!("builder '{}'", b.name())),
             Err(_) => Err(DispatcherError::from(NotFound(format!("builder '{:?}'", &brq))))
         },
         Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
     }
 }
+
+pub async fn render(svr: ServiceVersionRequest) -> DispatchResult<Value> {
+    info!("render builder command {:?}", svr);
+    match svr.is_valid_get() {
+        Ok(()) => match ServiceVersion::read(to_value(&svr).unwrap()).await {
+            Ok(sv) => {
+                let builder = preview_builder(svr.builder(), &sv).await?;
+                match render_commands(&builder, &sv) {
+                    Ok(commands) => {
+                        let stages: Vec<Value> = builder.stages().iter().zip(commands).enumerate()
+                            .map(|(i, (stage, command))| json!({"stage": i + 1, "name": stage.name(), "command": command}))
+                            .collect();
+                        Ok(json!({"builder": builder.name(), "stages": stages}))
+                    },
+                    Err(e) => Err(DispatcherError::from(InvalidRequest(format!("render action: {}", e))))
+                }
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("render action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("render action: {}", e))))
+    }
+}
+
+// the builder to preview is either an unsaved definition, a stored builder or the service version one
+async fn preview_builder(brq: Option<BuilderRequest>, sv: &ServiceVersion) -> DispatchResult<Builder> {
+    match brq {
+        None => Ok(sv.builder().clone()),
+        Some(brq) if brq.build_command.is_some() || brq.stages.is_some() => match brq.is_valid_stages() {
+            Ok(()) => Ok(Builder::new(brq.name.as_deref().unwrap_or(sv.builder().name()), true, brq.stages(), brq.timeout(), brq.artifacts())),
+            Err(e) => Err(DispatcherError::from(InvalidRequest(format!("render action: {}", e))))
+        },
+        Some(brq) => match brq.is_valid_get() {
+            Ok(()) => match Builder::read(to_value(&brq).unwrap()).await {
+                Ok(b) => Ok(*b),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("render action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(InvalidRequest(format!("render action: {}", e))))
+        }
+    }
+}
+
+pub async fn list(lrq: ListRequest) -> DispatchResult<Value> {
+    info!("list builders {:?}", lrq);
+    match lrq.is_valid_get() {
+        Ok(()) => match Builder::list(DalFilter::new(None, lrq.name().map(|n| n.as_str())).with_active(lrq.active()), &lrq).await {
+            Ok(page) => Ok(to_value(page).unwrap()),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
@@ -0,0 +1,240 @@
+use serde_json::{json, to_value, Value};
+use tracing::info;
+use o008_common::{DeploymentHistoryRequest, DeploymentRequest, DeploymentRollbackRequest, DeploymentSlotRequest, DeploymentStatus, DispatcherError, DispatchResult, ReleaseStatus, RequestValidator};
+use o008_common::AppCommandError::{Create, Destroy, InvalidRequest, NotFound, Update};
+use o008_entity::{Deployment, DeploymentSlot, DestroyEntity, Environment, PersistEntity, QueryEntity, Release, Service, ServiceVersion};
+use o008_setting::app_config;
+use crate::action::lock::{ensure_unlocked, record_override};
+use crate::deployer;
+
+pub async fn deploy(drq: DeploymentRequest) -> DispatchResult<Value> {
+    info!("deploy service version {:?}", drq);
+    match drq.is_valid_create() {
+        Ok(()) => {
+            let sv = ServiceVersion::read(to_value(drq.service_version().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("deploy action: {}", e))))?;
+            let tenant = sv.service().application().tenant();
+            let environment = Environment::read(json!({"name": drq.environment().unwrap(), "tenant": {"id": tenant.id()}})).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("deploy action: {}", e))))?;
+            let lock_override = ensure_unlocked(&tenant, Some(&environment), drq.lock_override(), "deploy").await?;
+            // an environment requiring approvals only runs the versions whose release has been approved
+            if environment.require_approval() {
+                let approved = Release::read(json!({"service_version": sv.id(), "environment": environment.id()})).await
+                    .is_ok_and(|r| r.status() == ReleaseStatus::Released);
+                if !approved {
+                    return Err(DispatcherError::from(InvalidRequest(format!("deploy action: version {} is not approved for {}", sv.version(), environment.name()))))
+                }
+            }
+            let previous = Deployment::current(sv.service().id(), environment.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("deploy action: {}", e))))?;
+            let mut deployment = Deployment::new(*sv, *environment, drq.deployed_by().unwrap(), previous.map(|d| d.service_version().id()));
+            if tenant.coexisting() {
+                set_slot(&mut deployment, "deploy").await?
+            }
+            let deployed = run_deployment(deployment, "deploy").await?;
+            record_override(deployed, lock_override, "deploy").await
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("deploy action: {}", e))))
+    }
+}
+
+pub async fn rollback(drr: DeploymentRollbackRequest) -> DispatchResult<Value> {
+    info!("rollback deployment {:?}", drr);
+    match drr.is_valid_create() {
+        Ok(()) => {
+            let service = Service::read(to_value(drr.service().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("rollback action: {}", e))))?;
+            let tenant = service.application().tenant();
+            let environment = Environment::read(json!({"name": drr.environment().unwrap(), "tenant": {"id": tenant.id()}})).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("rollback action: {}", e))))?;
+            let lock_override = ensure_unlocked(&tenant, Some(&environment), drr.lock_override(), "rollback").await?;
+            match Deployment::rollback_target(service.id(), environment.id()).await {
+                Ok(Some((current, target))) => {
+                    info!("rollback deployment {} to the version of deployment {}", current.id(), target.id());
+                    let mut deployment = Deployment::rollback(&current, &target, drr.deployed_by().unwrap());
+                    if !tenant.coexisting() {
+                        let deployed = run_deployment(deployment, "rollback").await?;
+                        return record_override(deployed, lock_override, "rollback").await
+                    }
+                    // the target version goes back to the slot it ran on, which then gets the traffic from the active slot
+                    match target.slot() {
+                        Some(name) => deployment.set_slot(name),
+                        None => set_slot(&mut deployment, "rollback").await?
+                    }
+                    let deployment = execute_deployment(deployment, "rollback").await?;
+                    if deployment.status() == DeploymentStatus::Succeeded {
+                        if let Some(name) = deployment.slot() {
+                            activate_slot(&service, &environment, Some(name), "rollback").await?;
+                        }
+                    }
+                    record_override(to_value(*deployment).unwrap(), lock_override, "rollback").await
+                },
+                Ok(None) => Err(DispatcherError::from(NotFound(format!("rollback action: no previous version of service {} to roll back to in {}", service.name(), environment.name())))),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("rollback action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("rollback action: {}", e))))
+    }
+}
+
+pub async fn list(dhr: DeploymentHistoryRequest) -> DispatchResult<Value> {
+    info!("get deployments {:?}", dhr);
+    match dhr.is_valid_get() {
+        Ok(()) => {
+            let service = Service::read(to_value(dhr.service().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+            let tenant = service.application().tenant();
+            let environments = match dhr.environment() {
+                Some(name) => Environment::read(json!({"name": name, "tenant": {"id": tenant.id()}})).await
+                    .map(|e| vec![*e]),
+                None => Environment::tenant_environments(tenant.id()).await
+            }.map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+            let mut history = Vec::new();
+            for environment in environments {
+                let deployments = Deployment::service_deployments(service.id(), environment.id()).await
+                    .map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+                history.push(json!({"environment": environment, "deployments": deployments}))
+            }
+            Ok(Value::Array(history))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn list_slots(dsr: DeploymentSlotRequest) -> DispatchResult<Value> {
+    info!("get deployment slots {:?}", dsr);
+    match dsr.is_valid_get() {
+        Ok(()) => {
+            let (service, environment) = slot_target(&dsr, "list slots").await?;
+            match DeploymentSlot::service_slots(service.id(), environment.id()).await {
+                Ok(slots) => Ok(to_value(slots).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list slots action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list slots action: {}", e))))
+    }
+}
+
+pub async fn switch_traffic(dsr: DeploymentSlotRequest) -> DispatchResult<Value> {
+    info!("switch traffic {:?}", dsr);
+    match dsr.is_valid_update() {
+        Ok(()) => {
+            let (service, environment) = slot_target(&dsr, "switch").await?;
+            let lock_override = ensure_unlocked(environment.tenant(), Some(&environment), dsr.lock_override(), "switch").await?;
+            let switched = activate_slot(&service, &environment, dsr.slot().map(|s| s.as_str()), "switch").await?;
+            record_override(json!({"slots": switched}), lock_override, "switch").await
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("switch action: {}", e))))
+    }
+}
+
+pub async fn retire_slot(dsr: DeploymentSlotRequest) -> DispatchResult<Value> {
+    info!("retire slot {:?}", dsr);
+    match dsr.is_valid_update() {
+        Ok(()) => {
+            let (service, environment) = slot_target(&dsr, "retire").await?;
+            let lock_override = ensure_unlocked(environment.tenant(), Some(&environment), dsr.lock_override(), "retire").await?;
+            let slot = DeploymentSlot::service_slots(service.id(), environment.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("retire action: {}", e))))?
+                .into_iter()
+                .find(|s| dsr.slot().map_or(!s.active(), |name| name == s.name()))
+                .ok_or_else(|| DispatcherError::from(NotFound(format!("retire action: no slot {} of service {} in {}", dsr.slot().map_or("idle", |s| s.as_str()), service.name(), environment.name()))))?;
+            if slot.active() {
+                return Err(DispatcherError::from(InvalidRequest(format!("retire action: slot {} is serving the traffic, switch it first", slot.name()))))
+            }
+            deployer::run_slot_command(app_config().deploy().retire_command(), &slot, "retire").await
+                .map_err(|e| DispatcherError::from(Destroy(format!("retire action: {}", e))))?;
+            match slot.destroy().await {
+                Ok(()) => record_override(to_value(slot).unwrap(), lock_override, "retire").await,
+                Err(e) => Err(DispatcherError::from(Destroy(format!("retire action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("retire action: {}", e))))
+    }
+}
+
+// the idle slot, the named one if given, gets the traffic and the other slots turn idle
+async fn activate_slot(service: &Service, environment: &Environment, name: Option<&str>, action: &str) -> DispatchResult<Vec<DeploymentSlot>> {
+    let slots = DeploymentSlot::service_slots(service.id(), environment.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    let idle = slots.iter()
+        .find(|s| !s.active() && name.is_none_or(|name| name == s.name()))
+        .ok_or_else(|| DispatcherError::from(InvalidRequest(format!("{} action: no idle slot of service {} to switch to in {}", action, service.name(), environment.name()))))?;
+    deployer::run_slot_command(app_config().deploy().switch_command(), idle, "switch").await
+        .map_err(|e| DispatcherError::from(Update(format!("{} action: {}", action, e))))?;
+    let idle = idle.id();
+    let mut switched = Vec::new();
+    for mut slot in slots {
+        slot.set_active(slot.id() == idle);
+        let slot = slot.persist().await
+            .map_err(|e| DispatcherError::from(Update(format!("{} action: {}", action, e))))?;
+        switched.push(*slot)
+    }
+    Ok(switched)
+}
+
+// the service and environment of a slot request, only the tenants with coexisting versions have slots
+async fn slot_target(dsr: &DeploymentSlotRequest, action: &str) -> DispatchResult<(Box<Service>, Box<Environment>)> {
+    let service = Service::read(to_value(dsr.service().unwrap()).unwrap()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    let tenant = service.application().tenant();
+    if !tenant.coexisting() {
+        return Err(DispatcherError::from(InvalidRequest(format!("{} action: tenant {} deploys in place, it has no slots", action, tenant.name()))))
+    }
+    let environment = Environment::read(json!({"name": dsr.environment().unwrap(), "tenant": {"id": tenant.id()}})).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    Ok((service, environment))
+}
+
+// coexisting versions are deployed to the slot already running the version or to the idle one, keeping the active slot untouched
+async fn set_slot(deployment: &mut Deployment, action: &str) -> DispatchResult<()> {
+    let slots = DeploymentSlot::service_slots(deployment.service_version().service().id(), deployment.environment().id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    match DeploymentSlot::target(&slots, deployment.service_version().id()) {
+        Some(name) => {
+            deployment.set_slot(name.as_str());
+            Ok(())
+        },
+        None => Err(DispatcherError::from(InvalidRequest(format!("{} action: no slot available", action))))
+    }
+}
+
+// a successful deployment takes its slot, the first slot of the environment gets the traffic
+async fn assign_slot(deployment: &Deployment, action: &str) -> DispatchResult<()> {
+    if let Some(name) = deployment.slot() {
+        let slots = DeploymentSlot::service_slots(deployment.service_version().service().id(), deployment.environment().id()).await
+            .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+        let active = slots.iter().any(|s| s.active());
+        let slot = match slots.into_iter().find(|s| s.name() == name) {
+            Some(mut slot) => {
+                slot.assign(deployment);
+                slot
+            },
+            None => DeploymentSlot::new(name, deployment, !active)
+        };
+        slot.persist().await
+            .map_err(|e| DispatcherError::from(Update(format!("{} action: {}", action, e))))?;
+    }
+    Ok(())
+}
+
+async fn run_deployment(deployment: Deployment, action: &str) -> DispatchResult<Value> {
+    execute_deployment(deployment, action).await
+        .map(|d| to_value(*d).unwrap())
+}
+
+async fn execute_deployment(deployment: Deployment, action: &str) -> DispatchResult<Box<Deployment>> {
+    let mut deployment = deployment;
+    let command = deployer::render_deploy_command(&deployment)
+        .map_err(|e| DispatcherError::from(InvalidRequest(format!("{} action: {}", action, e))))?;
+    deployment.set_command(command.as_str());
+    match deployer::deploy(deployment).await {
+        Ok(d) => {
+            if d.status() == DeploymentStatus::Succeeded {
+                assign_slot(&d, action).await?
+            }
+            Ok(d)
+        },
+        Err(e) => Err(DispatcherError::from(Create(format!("{} action: {}", action, e))))
+    }
+}
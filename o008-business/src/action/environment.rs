@@ -0,0 +1,60 @@
+use serde_json::{to_value, Value};
+use tracing::info;
+use o008_common::{DispatchResult, EnvironmentRequest, RequestValidator, TenantRequest};
+use o008_common::AppCommandError::{Create, InvalidRequest, NotFound};
+use o008_common::DispatcherError;
+use o008_entity::{Environment, PersistEntity, QueryEntity, Tenant};
+
+pub async fn create(erq: EnvironmentRequest) -> DispatchResult<Value> {
+    info!("create environment {:?}", erq);
+    match erq.is_valid_create() {
+        Ok(()) => match Tenant::read(to_value(erq.tenant().unwrap()).unwrap()).await {
+            Ok(tenant) => {
+                let environments = Environment::tenant_environments(tenant.id()).await
+                    .map_err(|e| DispatcherError::from(NotFound(format!("create action: {}", e))))?;
+                // without an explicit position the environment comes after the last one of the tenant
+                let position = match erq.position() {
+                    Some(p) => p,
+                    None => environments.last().map_or(0, |e| e.position() + 1)
+                };
+                // nothing is promoted to the first environment, so a release there is never pending an approval
+                if erq.require_approval() && environments.first().is_none_or(|e| position < e.position()) {
+                    return Err(DispatcherError::from(InvalidRequest(format!("create action: the first environment {} can not require approval", erq.name().unwrap()))))
+                }
+                let mut environment = Environment::new(erq.name().unwrap(), *tenant, position);
+                environment.set_rules(erq.soak_minutes(), erq.require_build(), erq.require_approval());
+                match environment.persist().await {
+                    Ok(environment) => Ok(to_value(*environment).unwrap()),
+                    Err(e) => Err(DispatcherError::from(Create(format!("create action: {}", e))))
+                }
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("create action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("create action: {}", e))))
+    }
+}
+
+pub async fn get(erq: EnvironmentRequest) -> DispatchResult<Value> {
+    info!("get environment {:?}", erq);
+    match erq.is_valid_get() {
+        Ok(()) => match Environment::read(to_value(erq).unwrap()).await {
+            Ok(environment) => Ok(to_value(*environment).unwrap()),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("get action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get action: {}", e))))
+    }
+}
+
+pub async fn list(trq: TenantRequest) -> DispatchResult<Value> {
+    info!("list environments {:?}", trq);
+    match trq.is_valid_get() {
+        Ok(()) => match Tenant::read(to_value(trq).unwrap()).await {
+            Ok(tenant) => match Environment::tenant_environments(tenant.id()).await {
+                Ok(environments) => Ok(to_value(environments).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
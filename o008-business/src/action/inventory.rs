@@ -0,0 +1,113 @@
+use serde_json::{json, to_value, Value};
+use tracing::info;
+use o008_common::{DispatcherError, DispatchResult, RequestValidator, TenantRequest};
+use o008_common::AppCommandError::{InvalidRequest, NotFound};
+use o008_entity::{Deployment, Environment, QueryEntity, Release, Service, Tenant};
+
+pub async fn get(trq: TenantRequest) -> DispatchResult<Value> {
+    info!("get inventory {:?}", trq);
+    match trq.is_valid_get() {
+        Ok(()) => {
+            let tenant = Tenant::read(to_value(trq).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("inventory action: {}", e))))?;
+            let environments = Environment::tenant_environments(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("inventory action: {}", e))))?;
+            let services = Service::tenant_services(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("inventory action: {}", e))))?;
+            let running = Deployment::running(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("inventory action: {}", e))))?;
+            let released = Release::latest_released(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("inventory action: {}", e))))?;
+            let mut applications: Vec<Value> = Vec::new();
+            let mut drifts = 0;
+            for service in services {
+                let mut deployed = Vec::new();
+                let mut deployments = Vec::new();
+                let mut releases = Vec::new();
+                for environment in environments.iter() {
+                    let deployment = running.iter()
+                        .find(|d| d.service_version().service().id() == service.id() && d.environment().id() == environment.id());
+                    deployed.push(json!({
+                        "environment": environment.name(),
+                        "version": deployment.map(|d| d.service_version().version()),
+                        "deployment": deployment.map(|d| d.id()),
+                        "slot": deployment.and_then(|d| d.slot()),
+                        "deployed_on": deployment.and_then(|d| d.finished_on()),
+                    }));
+                    deployments.push(deployment);
+                    releases.push(released.iter()
+                        .find(|r| r.service_version().service().id() == service.id() && r.environment().id() == environment.id()));
+                }
+                let versions: Vec<Option<&str>> = deployments.iter().map(|d| d.map(|d| d.service_version().version())).collect();
+                let promoted: Vec<Option<&str>> = releases.iter().map(|r| r.map(|r| r.service_version().version())).collect();
+                let drift: Vec<Value> = drifting(&versions, &promoted).into_iter()
+                    .map(|i| json!({
+                        "environment": environments[i].name(),
+                        "running": versions[i],
+                        "previous": environments[i - 1].name(),
+                        "previous_running": versions[i - 1],
+                        "released": promoted[i],
+                        "promoted_on": releases[i].map(|r| r.promoted_on()),
+                    }))
+                    .collect();
+                drifts += drift.len();
+                let entry = json!({"service": service.name(), "environments": deployed, "drift": drift});
+                let application = service.application();
+                match applications.iter_mut().find(|a| a["application"] == application.name()) {
+                    Some(a) => a["services"].as_array_mut().unwrap().push(entry),
+                    None => applications.push(json!({"application": application.name(), "services": [entry]}))
+                }
+            }
+            Ok(json!({
+                "tenant": tenant.name(),
+                "environments": environments.iter().map(|e| e.name()).collect::<Vec<&str>>(),
+                "applications": applications,
+                "drift": drifts,
+            }))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("inventory action: {}", e))))
+    }
+}
+
+// the environments running another version than the environment before them in the promotion chain, unless the one before is only
+// ahead with a version not promoted yet while the environment runs the version last released to it, an environment running nothing
+// does not drift
+fn drifting(running: &[Option<&str>], released: &[Option<&str>]) -> Vec<usize> {
+    (1..running.len())
+        .filter(|&i| match (running[i - 1], running[i]) {
+            (previous, Some(current)) if previous != Some(current) => released[i] != Some(current) || released[i] == previous,
+            (_, _) => false
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::drifting;
+
+    #[test]
+    fn matching_environments_do_not_drift() {
+        assert!(drifting(&[Some("1.0.0"), Some("1.0.0"), Some("1.0.0")], &[None, Some("1.0.0"), Some("1.0.0")]).is_empty());
+    }
+
+    #[test]
+    fn promoted_release_without_deployment_does_not_drift() {
+        assert!(drifting(&[Some("2.0.0"), Some("2.0.0"), None], &[None, Some("2.0.0"), Some("2.0.0")]).is_empty());
+    }
+
+    #[test]
+    fn environment_ahead_of_its_promotions_does_not_drift() {
+        assert!(drifting(&[Some("3.0.0"), Some("2.0.0"), Some("1.0.0")], &[None, Some("2.0.0"), Some("1.0.0")]).is_empty());
+    }
+
+    #[test]
+    fn promoted_version_not_running_drifts() {
+        assert_eq!(drifting(&[Some("2.0.0"), Some("1.0.0")], &[None, Some("2.0.0")]), vec![1]);
+    }
+
+    #[test]
+    fn version_deployed_outside_the_promotion_chain_drifts() {
+        assert_eq!(drifting(&[Some("1.0.0"), Some("1.0.0"), Some("1.0.1")], &[None, Some("1.0.0"), Some("1.0.0")]), vec![2]);
+        assert_eq!(drifting(&[Some("1.0.0"), Some("2.0.0")], &[None, None]), vec![1]);
+    }
+}
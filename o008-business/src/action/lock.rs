@@ -0,0 +1,141 @@
+use serde_json::{json, to_value, Value};
+use tracing::{info, warn};
+use o008_common::{DeploymentLockRequest, DispatcherError, DispatchResult, FreezeWindowRequest, RequestValidator, TenantRequest};
+use o008_common::AppCommandError::{Create, Destroy, InvalidRequest, Locked, NotFound};
+use o008_entity::{DeploymentLock, DestroyEntity, Environment, FreezeWindow, LockOverride, PersistEntity, QueryEntity, Tenant};
+
+pub async fn lock(dlr: DeploymentLockRequest) -> DispatchResult<Value> {
+    info!("lock deployments {:?}", dlr);
+    match dlr.is_valid_create() {
+        Ok(()) => {
+            let (tenant, environment) = lock_scope(dlr.tenant().unwrap(), dlr.environment(), "lock").await?;
+            let lock = DeploymentLock::new(tenant, environment, dlr.locked_by().unwrap(), dlr.reason().unwrap(), dlr.expires_on());
+            match lock.persist().await {
+                Ok(l) => Ok(to_value(*l).unwrap()),
+                Err(e) => Err(DispatcherError::from(Create(format!("lock action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("lock action: {}", e))))
+    }
+}
+
+pub async fn unlock(dlr: DeploymentLockRequest) -> DispatchResult<Value> {
+    info!("unlock deployments {:?}", dlr);
+    match dlr.is_valid_get() {
+        Ok(()) => {
+            let (tenant, environment) = lock_scope(dlr.tenant().unwrap(), dlr.environment(), "unlock").await?;
+            let locks: Vec<DeploymentLock> = DeploymentLock::tenant_locks(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("unlock action: {}", e))))?
+                .into_iter()
+                .filter(|l| l.environment().map(|e| e.id()) == environment.as_ref().map(|e| e.id()))
+                .collect();
+            if locks.is_empty() {
+                return Err(DispatcherError::from(NotFound(format!("unlock action: no lock of tenant {} on {}", tenant.name(), environment.as_ref().map_or("every environment", |e| e.name())))))
+            }
+            for lock in &locks {
+                lock.destroy().await
+                    .map_err(|e| DispatcherError::from(Destroy(format!("unlock action: {}", e))))?;
+            }
+            Ok(to_value(locks).unwrap())
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("unlock action: {}", e))))
+    }
+}
+
+pub async fn list(trq: TenantRequest) -> DispatchResult<Value> {
+    info!("get deployment locks {:?}", trq);
+    match trq.is_valid_get() {
+        Ok(()) => {
+            let tenant = Tenant::read(to_value(trq).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list locks action: {}", e))))?;
+            let locks = DeploymentLock::tenant_locks(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list locks action: {}", e))))?;
+            let windows = FreezeWindow::tenant_windows(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list locks action: {}", e))))?;
+            let overrides = LockOverride::tenant_overrides(tenant.id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list locks action: {}", e))))?;
+            Ok(json!({"tenant": tenant, "locks": locks, "freeze_windows": windows, "overrides": overrides}))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list locks action: {}", e))))
+    }
+}
+
+pub async fn create_window(fwr: FreezeWindowRequest) -> DispatchResult<Value> {
+    info!("create freeze window {:?}", fwr);
+    match fwr.is_valid_create() {
+        Ok(()) => {
+            let (tenant, environment) = lock_scope(fwr.tenant().unwrap(), fwr.environment(), "create window").await?;
+            let window = FreezeWindow::new(fwr.name().unwrap(), tenant, environment, &fwr.days(), fwr.starts_at().unwrap(), fwr.ends_at().unwrap(),
+                                           fwr.reason().map(|r| r.as_str()));
+            match window.persist().await {
+                Ok(w) => Ok(to_value(*w).unwrap()),
+                Err(e) => Err(DispatcherError::from(Create(format!("create window action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("create window action: {}", e))))
+    }
+}
+
+pub async fn remove_window(fwr: FreezeWindowRequest) -> DispatchResult<Value> {
+    info!("remove freeze window {:?}", fwr);
+    match fwr.is_valid_get() {
+        Ok(()) => {
+            let window = FreezeWindow::read(json!({"name": fwr.name().unwrap(), "tenant": fwr.tenant().unwrap()})).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("remove window action: {}", e))))?;
+            match window.destroy().await {
+                Ok(()) => Ok(to_value(*window).unwrap()),
+                Err(e) => Err(DispatcherError::from(Destroy(format!("remove window action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("remove window action: {}", e))))
+    }
+}
+
+// refuse the change while a tenant wide or environment lock or freeze window is active, unless someone overrides it with a justification,
+// the override is then returned to be recorded once the change is done
+pub(crate) async fn ensure_unlocked(tenant: &Tenant, environment: Option<&Environment>, lock_override: Option<(&String, &String)>, action: &str) -> DispatchResult<Option<LockOverride>> {
+    let in_scope = |locked: Option<&Environment>| locked.is_none_or(|l| environment.is_some_and(|e| e.id() == l.id()));
+    let locks = DeploymentLock::tenant_locks(tenant.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    let windows = FreezeWindow::tenant_windows(tenant.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    let reason = if let Some(lock) = locks.iter().find(|l| l.is_active() && in_scope(l.environment())) {
+        format!("locked by {}: {}", lock.locked_by(), lock.reason())
+    } else if let Some(window) = windows.iter().find(|w| w.is_active() && in_scope(w.environment())) {
+        format!("frozen by window {}{}", window.name(), window.reason().map(|r| format!(": {}", r)).unwrap_or_default())
+    } else {
+        return Ok(None)
+    };
+    let target = environment.map_or(String::from(tenant.name()), |e| format!("{} {}", tenant.name(), e.name()));
+    match lock_override {
+        Some((overridden_by, justification)) => Ok(Some(LockOverride::new(tenant.clone(), environment.cloned(), action, &reason, overridden_by, justification))),
+        None => Err(DispatcherError::from(Locked(format!("{} action: {} is {}", action, target, reason))))
+    }
+}
+
+// record the override of a change that went through and return it along with the changed item
+pub(crate) async fn record_override(changed: Value, lock_override: Option<LockOverride>, action: &str) -> DispatchResult<Value> {
+    match (changed, lock_override) {
+        (Value::Object(mut item), Some(lock_override)) => {
+            let lock_override = lock_override.persist().await
+                .map_err(|e| DispatcherError::from(Create(format!("{} action: {}", action, e))))?;
+            warn!("{} overrode the lock of tenant {}, {}, by {}, justification: {}", action, lock_override.tenant().name(), lock_override.locked(),
+                  lock_override.overridden_by(), lock_override.justification());
+            item.insert(String::from("lock_override"), to_value(*lock_override).unwrap());
+            Ok(Value::Object(item))
+        },
+        (changed, _) => Ok(changed)
+    }
+}
+
+// the tenant and the optional environment a lock or a freeze window applies to
+async fn lock_scope(trq: &TenantRequest, environment: Option<&String>, action: &str) -> DispatchResult<(Tenant, Option<Environment>)> {
+    let tenant = Tenant::read(to_value(trq).unwrap()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    let environment = match environment {
+        Some(name) => Some(*Environment::read(json!({"name": name, "tenant": {"id": tenant.id()}})).await
+            .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?),
+        None => None
+    };
+    Ok((*tenant, environment))
+}
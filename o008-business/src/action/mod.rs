@@ -1,5 +1,32 @@
 pub mod application;
+pub mod approval;
+pub mod artifact;
+pub mod build;
 pub mod builder;
+pub mod deployment;
+pub mod environment;
+pub mod inventory;
+pub mod lock;
+pub mod promotion;
+pub mod repo_reference;
 pub mod service;
 pub mod service_version;
-pub mod tenant;
\ No newline at end of file
+pub mod tenant;
+
+use serde_json::{json, Value};
+use o008_common::DispatchResult;
+use o008_common::error::AppCommandError::{Conflict, Destroy};
+use o008_common::error::DispatcherError;
+use o008_entity::EntityError;
+
+// a delete refused because of its dependents answers with the list of them
+pub(crate) fn destroy_result(result: Result<(), EntityError>, target: String) -> DispatchResult<Value> {
+    match result {
+        Ok(_) => Ok(Value::Null),
+        Err(EntityError::Dependents(dependents)) => Err(DispatcherError::from(Conflict(json!({
+            "message": format!("delete action: {} is referenced by {} dependents", target, dependents.len()),
+            "dependents": dependents,
+        })))),
+        Err(e) => Err(DispatcherError::from(Destroy(format!("delete action: {}", e))))
+    }
+}
@@ -0,0 +1,134 @@
+use serde_json::{json, to_value, Value};
+use tracing::info;
+use o008_common::{BuildStatus, DispatcherError, DispatchResult, PromotionRequest, ReleaseStatus, RequestValidator};
+use o008_common::AppCommandError::{Create, InvalidRequest, NotFound};
+use o008_entity::{Deployment, EntityError, Environment, PersistEntity, QueryEntity, Release, ServiceVersion, ServiceVersionBuild};
+use crate::action::lock::{ensure_unlocked, record_override};
+
+pub async fn promote(prq: PromotionRequest) -> DispatchResult<Value> {
+    info!("promote service version {:?}", prq);
+    match prq.is_valid_create() {
+        Ok(()) => {
+            let sv = ServiceVersion::read(to_value(prq.service_version().unwrap()).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("promote action: {}", e))))?;
+            let service = sv.service().id();
+            let environments = Environment::tenant_environments(sv.service().application().tenant().id()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("promote action: {}", e))))?;
+            let mut deployed = Vec::new();
+            for environment in environments.iter() {
+                let current = Deployment::current(service, environment.id()).await
+                    .map_err(|e| DispatcherError::from(NotFound(format!("promote action: {}", e))))?;
+                deployed.push(current.filter(|d| d.service_version().id() == sv.id()))
+            }
+            // the target is the requested environment or the one following the last environment running the version
+            let target = match prq.environment() {
+                Some(name) => environments.iter().position(|e| e.name() == name)
+                    .ok_or_else(|| DispatcherError::from(NotFound(format!("promote action: environment {} not found", name))))?,
+                None => deployed.iter().rposition(|d| d.is_some()).map_or(0, |p| p + 1)
+            };
+            if target == 0 || target >= environments.len() {
+                return Err(DispatcherError::from(InvalidRequest(format!("promote action: no environment to promote version {} to", sv.version()))))
+            }
+            let (previous, environment) = (&environments[target - 1], &environments[target]);
+            let lock_override = ensure_unlocked(&sv.service().application().tenant(), Some(environment), prq.lock_override(), "promote").await?;
+            let deployment = deployed[target - 1].as_ref()
+                .ok_or_else(|| DispatcherError::from(InvalidRequest(format!("promote action: version {} is not currently deployed in {}", sv.version(), previous.name()))))?;
+            let rejected = match Release::read(json!({"service_version": sv.id(), "environment": environment.id()})).await {
+                Ok(release) if release.status() == ReleaseStatus::Rejected => Some(release),
+                Ok(_) => return Err(DispatcherError::from(InvalidRequest(format!("promote action: version {} is already promoted to {}", sv.version(), environment.name())))),
+                Err(EntityError::NotFound(_)) => None,
+                Err(e) => return Err(DispatcherError::from(Create(format!("promote action: {}", e))))
+            };
+            let builds = if environment.require_build() {
+                ServiceVersionBuild::service_version_builds(sv.id()).await
+                    .map_err(|e| DispatcherError::from(NotFound(format!("promote action: {}", e))))?
+                    .iter()
+                    .map(|b| b.status())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let status = apply_rules(environment, previous, |minutes| deployment.soaked(minutes), &builds)
+                .map_err(|violations| DispatcherError::from(InvalidRequest(format!("promote action: promotion to {} refused: {}", environment.name(), violations.join(", ")))))?;
+            let release = match rejected {
+                Some(mut release) => {
+                    release.promote_again(prq.promoted_by().unwrap(), deployment.id(), status);
+                    *release
+                },
+                None => Release::new(*sv, environment.clone(), prq.promoted_by().unwrap(), deployment.id(), status)
+            };
+            match release.persist().await {
+                Ok(r) => record_override(to_value(*r).unwrap(), lock_override, "promote").await,
+                Err(e) => Err(DispatcherError::from(Create(format!("promote action: {}", e))))
+            }
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("promote action: {}", e))))
+    }
+}
+
+// the status of the release of a version meeting the promotion rules of the environment, or the rules it breaks
+fn apply_rules(environment: &Environment, previous: &Environment, soaked: impl Fn(i32) -> bool, builds: &[BuildStatus]) -> Result<ReleaseStatus, Vec<String>> {
+    let mut violations = Vec::new();
+    if let Some(minutes) = environment.soak_minutes() {
+        if !soaked(minutes) {
+            violations.push(format!("version must soak {} minutes in {}", minutes, previous.name()))
+        }
+    }
+    if environment.require_build() && !builds.iter().any(|b| *b == BuildStatus::Succeeded || *b == BuildStatus::Cached) {
+        violations.push(String::from("version has no successful build"))
+    }
+    match (violations.is_empty(), environment.require_approval()) {
+        (true, true) => Ok(ReleaseStatus::PendingApproval),
+        (true, false) => Ok(ReleaseStatus::Released),
+        (false, _) => Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use o008_entity::Tenant;
+    use super::*;
+
+    fn environments(soak_minutes: Option<i32>, require_build: bool, require_approval: bool) -> (Environment, Environment) {
+        let tenant = Tenant::new("acme", false);
+        let mut prod = Environment::new("prod", tenant.clone(), 1);
+        prod.set_rules(soak_minutes, require_build, require_approval);
+        (Environment::new("staging", tenant, 0), prod)
+    }
+
+    #[test]
+    fn environment_without_rules_releases() {
+        let (staging, prod) = environments(None, false, false);
+        assert_eq!(apply_rules(&prod, &staging, |_| false, &[]), Ok(ReleaseStatus::Released));
+    }
+
+    #[test]
+    fn version_must_soak_in_the_previous_environment() {
+        let (staging, prod) = environments(Some(30), false, false);
+        assert_eq!(apply_rules(&prod, &staging, |minutes| minutes <= 10, &[]), Err(vec![String::from("version must soak 30 minutes in staging")]));
+        assert_eq!(apply_rules(&prod, &staging, |minutes| minutes <= 60, &[]), Ok(ReleaseStatus::Released));
+    }
+
+    #[test]
+    fn version_must_have_a_successful_build() {
+        let (staging, prod) = environments(None, true, false);
+        let refused = Err(vec![String::from("version has no successful build")]);
+        assert_eq!(apply_rules(&prod, &staging, |_| true, &[]), refused);
+        assert_eq!(apply_rules(&prod, &staging, |_| true, &[BuildStatus::Failed, BuildStatus::Running]), refused);
+        assert_eq!(apply_rules(&prod, &staging, |_| true, &[BuildStatus::Failed, BuildStatus::Succeeded]), Ok(ReleaseStatus::Released));
+        assert_eq!(apply_rules(&prod, &staging, |_| true, &[BuildStatus::Cached]), Ok(ReleaseStatus::Released));
+    }
+
+    #[test]
+    fn environment_requiring_approval_holds_the_release() {
+        let (staging, prod) = environments(None, false, true);
+        assert_eq!(apply_rules(&prod, &staging, |_| true, &[]), Ok(ReleaseStatus::PendingApproval));
+    }
+
+    #[test]
+    fn every_broken_rule_is_reported() {
+        let (staging, prod) = environments(Some(15), true, true);
+        assert_eq!(apply_rules(&prod, &staging, |_| false, &[BuildStatus::Failed]),
+                   Err(vec![String::from("version must soak 15 minutes in staging"), String::from("version has no successful build")]));
+    }
+}
@@ -0,0 +1,17 @@
+use serde_json::{to_value, Value};
+use tracing::info;
+use o008_common::{DispatcherError, DispatchResult, RepoReferenceRequest, RequestValidator};
+use o008_common::AppCommandError::{InvalidRequest, NotFound};
+use o008_entity::{destroy_checked, QueryEntity, RepoReference};
+use crate::action::destroy_result;
+
+pub async fn delete(rrq: RepoReferenceRequest, cascade: bool) -> DispatchResult<Value> {
+    info!("delete repo reference {:?} cascade {}", rrq, cascade);
+    match rrq.is_valid_get() {
+        Ok(()) => match RepoReference::read(to_value(&rrq).unwrap()).await {
+            Ok(rr) => destroy_result(destroy_checked(rr.as_ref(), cascade).await, format!("repo reference '{}'", rr.reference())),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("delete action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
+    }
+}
@@ -1,14 +1,21 @@
-use serde_json::{json, to_value, Value};
+use serde_json::{to_value, Value};
 use tracing::info;
 
-use o008_common::{RequestValidator, ServiceRequest, DispatchResult};
-use o008_entity::{Application, persist_json, QueryEntity, Service, ServiceVersion};
+use o008_common::{ArchiveRequest, ListRequest, RequestValidator, ServiceRequest, DispatchResult};
+use o008_entity::{Application, DalFilter, destroy_checked, EntityError, persist_json, QueryEntity, Service, ServiceVersion};
+use crate::action::destroy_result;
+use crate::action::service_version::ensure_retired;
 
 use o008_common::error::AppCommandError::{Create, InvalidRequest, NotFound, Update};
 use o008_common::error::DispatcherError;
 
 pub async fn persist(src: ServiceRequest, req: ServiceRequest) -> DispatchResult<Value> {
-    if Service::persisted(to_value(src.clone()).unwrap()).await {
+    let persisted = Service::persisted(to_value(src.clone()).unwrap()).await
+        .map_err(|e| match e {
+            EntityError::WrongQuery(_) => DispatcherError::from(InvalidRequest(format!("persist action: {}", e))),
+            _ => DispatcherError::from(NotFound(format!("persist action: {}", e)))
+        })?;
+    if persisted {
         update(src, req).await
     } else {
         let create_req = ServiceRequest::new(
@@ -33,11 +40,12 @@ pub async fn get(srq: ServiceRequest) -> DispatchResult<Value> {
 
 pub async fn get_with_versions(srq: ServiceRequest) -> DispatchResult<Value> {
     info!("get service versions {:?}", srq);
+    let include_archived = srq.include_archived();
     match srq.is_valid_get() {
         Ok(()) => match Service::read(to_value(srq).unwrap()).await {
             Ok(srv) => {
                let mut vsrv = srv.clone();
-                if let Ok(versions) = ServiceVersion::service_versions(json!({"service": srv.id()})).await {
+                if let Ok(versions) = ServiceVersion::service_versions(srv.id(), include_archived).await {
                     vsrv.set_versions(versions)
                 }
                 Ok(to_value(vsrv).unwrap())
@@ -88,3 +96,65 @@ async fn update(src: ServiceRequest, req: ServiceRequest) -> DispatchResult<Valu
         (_, Err(e)) => Err(DispatcherError::from(InvalidRequest(format!("update action: {}", e)))),
     }
 }
+
+pub async fn list(lrq: ListRequest) -> DispatchResult<Value> {
+    info!("list services {:?}", lrq);
+    match (lrq.is_valid_get(), lrq.application()) {
+        (Ok(()), Some(arq)) => {
+            let application = Application::read(to_value(arq).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+            match Service::list(DalFilter::new(Some(application.id()), lrq.name().map(|n| n.as_str())).with_archived(lrq.include_archived()), &lrq).await {
+                Ok(page) => Ok(to_value(page).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+            }
+        },
+        (Ok(()), None) => Err(DispatcherError::from(InvalidRequest(String::from("list action: application and tenant are mandatory")))),
+        (Err(e), _) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn delete(srq: ServiceRequest, cascade: bool) -> DispatchResult<Value> {
+    info!("delete service {:?} cascade {}", srq, cascade);
+    match srq.is_valid_get() {
+        Ok(()) => match Service::read(to_value(&srq).unwrap()).await {
+            Ok(srv) => destroy_result(destroy_checked(srv.as_ref(), cascade).await, format!("service '{}'", srv.name())),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("delete action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
+    }
+}
+
+pub async fn archive(srq: ServiceRequest, ar: ArchiveRequest) -> DispatchResult<Value> {
+    info!("archive service {:?} by {:?}", srq, ar);
+    match (srq.is_valid_get(), ar.valid_archived_by()) {
+        (Ok(()), Ok(archived_by)) => match Service::read(to_value(srq.with_include_archived(true)).unwrap()).await {
+            Ok(srv) if srv.is_archived() =>
+                Err(DispatcherError::from(Update(format!("archive action: service '{}' is already archived", srv.name())))),
+            Ok(mut srv) => {
+                ensure_retired(&srv, None, "archive").await?;
+                srv.archive(archived_by);
+                let r = persist_json(srv.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("archive action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("archive action: {}", e))))
+        },
+        (Err(e), _) | (_, Err(e)) => Err(DispatcherError::from(InvalidRequest(format!("archive action: {}", e))))
+    }
+}
+
+pub async fn restore(srq: ServiceRequest) -> DispatchResult<Value> {
+    info!("restore service {:?}", srq);
+    match srq.is_valid_get() {
+        Ok(()) => match Service::read(to_value(srq.with_include_archived(true)).unwrap()).await {
+            Ok(srv) if !srv.is_archived() =>
+                Err(DispatcherError::from(Update(format!("restore action: service '{}' is not archived", srv.name())))),
+            Ok(mut srv) => {
+                srv.restore();
+                let r = persist_json(srv.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("restore action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("restore action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("restore action: {}", e))))
+    }
+}
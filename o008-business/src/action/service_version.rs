@@ -1,14 +1,28 @@
-use serde_json::{to_value, Value};
+use serde_json::{json, to_value, Value};
 use tracing::info;
-use o008_common::{DispatcherError, DispatchResult, RequestValidator, ServiceVersionRequest};
+use uuid::Uuid;
+use o008_common::{ArchiveRequest, DispatcherError, DispatchResult, ListRequest, ReleaseStatus, RepoReferenceKind, RepoReferenceRequest, RepoReferenceResolveRequest, RequestValidator, ServiceVersionRequest};
 use o008_common::AppCommandError::{Create, InvalidRequest, NotFound, Update};
-use o008_entity::{Builder, EntityError, persist_json, PersistEntity, QueryEntity, Service, ServiceVersion};
+use o008_entity::{Builder, DalFilter, Deployment, destroy_checked, EntityError, Environment, persist_json, PersistEntity, QueryEntity, Release, Service, ServiceVersion, ServiceVersionRepoRefHistory};
 use o008_entity::pg::RepoReference;
+use crate::action::destroy_result;
+use crate::action::lock::{ensure_unlocked, record_override};
+use crate::git::{is_local_repo, resolve_reference};
 
 
 pub async fn persist(src: ServiceVersionRequest, req: ServiceVersionRequest) -> DispatchResult<Value> {
-    if ServiceVersion::persisted(to_value(&src).unwrap()).await {
-        update(src, req).await
+    // a missing service is reported by the create or update action
+    let lock_override = match Service::read(to_value(src.service()).unwrap()).await {
+        Ok(service) => ensure_unlocked(&service.application().tenant(), None, req.lock_override(), "persist").await?,
+        Err(_) => None
+    };
+    let persisted = ServiceVersion::persisted(to_value(&src).unwrap()).await
+        .map_err(|e| match e {
+            EntityError::WrongQuery(_) => DispatcherError::from(InvalidRequest(format!("persist action: {}", e))),
+            _ => DispatcherError::from(NotFound(format!("persist action: {}", e)))
+        })?;
+    let changed = if persisted {
+        update(src, req).await?
     } else {
         let create_req = ServiceVersionRequest::new(
             src.version(),
@@ -16,10 +30,93 @@ pub async fn persist(src: ServiceVersionRequest, req: ServiceVersionRequest) ->
             req.repo_ref(),
             req.builder()
         );
-        create(create_req).await
+        create(create_req).await?
+    };
+    record_override(changed, lock_override, "persist").await
+}
+
+pub async fn list(lrq: ListRequest) -> DispatchResult<Value> {
+    info!("list service versions {:?}", lrq);
+    match (lrq.is_valid_get(), lrq.service()) {
+        (Ok(()), Some(srq)) => {
+            let service = Service::read(to_value(srq).unwrap()).await
+                .map_err(|e| DispatcherError::from(NotFound(format!("list action: {}", e))))?;
+            match ServiceVersion::list(DalFilter::new(Some(service.id()), lrq.name().map(|n| n.as_str())).with_archived(lrq.include_archived()), &lrq).await {
+                Ok(page) => Ok(to_value(page).unwrap()),
+                Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+            }
+        },
+        (Ok(()), None) => Err(DispatcherError::from(InvalidRequest(String::from("list action: service, application and tenant are mandatory")))),
+        (Err(e), _) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn resolve(req: RepoReferenceResolveRequest) -> DispatchResult<Value> {
+    info!("resolve service version repo reference {:?}", req);
+    match req.is_valid_get() {
+        Ok(()) => match ServiceVersion::read(to_value(req.service_version()).unwrap()).await {
+            Ok(sv) => {
+                let lock_override = if req.pin() && sv.repo_ref().kind() != RepoReferenceKind::Commit {
+                    ensure_unlocked(&sv.service().application().tenant(), None, req.lock_override(), "resolve").await?
+                } else {
+                    None
+                };
+                let resolved = resolve_service_version(*sv, req.pin()).await?;
+                record_override(resolved, lock_override, "resolve").await
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("resolve action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("resolve action: {}", e))))
     }
 }
 
+async fn resolve_service_version(sv: ServiceVersion, pin: bool) -> DispatchResult<Value> {
+    let repo_ref = sv.repo_ref().clone();
+    let repo = sv.repo();
+    if !is_local_repo(repo) {
+        return Err(DispatcherError::from(InvalidRequest(format!("resolve action: repository {} is not a local repository", repo))))
+    }
+    let commit = resolve_reference(repo, repo_ref.kind(), repo_ref.reference()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("resolve action: {}", e))))?;
+
+    let pinned = pin && repo_ref.kind() != RepoReferenceKind::Commit;
+    let sv = if pinned {
+        pin_service_version(sv, commit.as_str()).await?
+    } else {
+        Box::new(sv)
+    };
+    let history = ServiceVersionRepoRefHistory::service_version_history(sv.id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("resolve action: {}", e))))?;
+    Ok(json!({
+        "repo_ref": repo_ref,
+        "commit": commit,
+        "pinned": pinned,
+        "service_version": sv,
+        "history": history,
+    }))
+}
+
+// point the service version to the commit, the replaced reference is kept in the history
+async fn pin_service_version(sv: ServiceVersion, commit: &str) -> DispatchResult<Box<ServiceVersion>> {
+    let original = sv.repo_ref().clone();
+    let rrq = RepoReferenceRequest::build_get_request(original.repo(), RepoReferenceKind::Commit, commit);
+    let pinned_ref = match RepoReference::read(to_value(&rrq).unwrap()).await {
+        Ok(rr) => rr,
+        Err(EntityError::NotFound(_)) => RepoReference::from(rrq).persist().await
+            .map_err(|e| DispatcherError::from(Create(format!("resolve action: {}", e))))?,
+        Err(e) => return Err(DispatcherError::from(Create(format!("resolve action: {}", e))))
+    };
+
+    let mut sv = sv;
+    sv.set_repo_ref(*pinned_ref.clone());
+    let sv = sv.persist().await
+        .map_err(|e| DispatcherError::from(Update(format!("resolve action: {}", e))))?;
+    ServiceVersionRepoRefHistory::new(sv.id(), original, *pinned_ref).persist().await
+        .map_err(|e| DispatcherError::from(Create(format!("resolve action: {}", e))))?;
+    info!("service version {} pinned to commit {}", sv.id(), commit);
+    Ok(sv)
+}
+
 async fn create(svr: ServiceVersionRequest) -> DispatchResult<Value> {
     info!("create service version {:?}", svr);
     match svr.is_valid_create() {
@@ -135,4 +232,79 @@ async fn build_and_persist_service_version(version: &str, service: Service, rr:
         builder);
     let r = persist_json(&service_version).await;
     r.map_err(|e| DispatcherError::from(Create(format!("create action: {}", e))))
-}
\ No newline at end of file
+}
+
+pub async fn delete(svr: ServiceVersionRequest, cascade: bool) -> DispatchResult<Value> {
+    info!("delete service version {:?} cascade {}", svr, cascade);
+    match svr.is_valid_get() {
+        Ok(()) => match ServiceVersion::read(to_value(&svr).unwrap()).await {
+            Ok(sv) => destroy_result(destroy_checked(sv.as_ref(), cascade).await, format!("service version '{}'", sv.version())),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("delete action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
+    }
+}
+
+pub async fn archive(svr: ServiceVersionRequest, ar: ArchiveRequest) -> DispatchResult<Value> {
+    info!("archive service version {:?} by {:?}", svr, ar);
+    match (svr.is_valid_get(), ar.valid_archived_by()) {
+        (Ok(()), Ok(archived_by)) => match ServiceVersion::read(to_value(svr.with_include_archived(true)).unwrap()).await {
+            Ok(sv) if sv.is_archived() =>
+                Err(DispatcherError::from(Update(format!("archive action: service version '{}' is already archived", sv.version())))),
+            Ok(mut sv) => {
+                ensure_retired(sv.service(), Some(sv.id()), "archive").await?;
+                sv.archive(archived_by);
+                let r = persist_json(sv.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("archive action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("archive action: {}", e))))
+        },
+        (Err(e), _) | (_, Err(e)) => Err(DispatcherError::from(InvalidRequest(format!("archive action: {}", e))))
+    }
+}
+
+// an archived version is hidden from the reads, so it can only be archived once it runs nowhere and waits for no approval
+pub(crate) async fn ensure_retired(service: &Service, version: Option<Uuid>, action: &str) -> DispatchResult<()> {
+    let environments = Environment::tenant_environments(service.application().tenant().id()).await
+        .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+    for environment in environments.iter() {
+        let current = Deployment::current(service.id(), environment.id()).await
+            .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+        if let Some(deployment) = current.filter(|d| version.is_none_or(|v| d.service_version().id() == v)) {
+            return Err(DispatcherError::from(Update(format!("{} action: version {} is deployed in {}", action, deployment.service_version().version(), environment.name()))))
+        }
+    }
+    let versions = match version {
+        Some(v) => vec![v],
+        None => ServiceVersion::service_versions(service.id(), false).await
+            .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?
+            .iter()
+            .map(|v| v.id())
+            .collect()
+    };
+    for v in versions {
+        let releases = Release::service_version_releases(v).await
+            .map_err(|e| DispatcherError::from(NotFound(format!("{} action: {}", action, e))))?;
+        if let Some(release) = releases.iter().find(|r| r.status() == ReleaseStatus::PendingApproval) {
+            return Err(DispatcherError::from(Update(format!("{} action: version {} is pending approval for {}", action, release.service_version().version(), release.environment().name()))))
+        }
+    }
+    Ok(())
+}
+
+pub async fn restore(svr: ServiceVersionRequest) -> DispatchResult<Value> {
+    info!("restore service version {:?}", svr);
+    match svr.is_valid_get() {
+        Ok(()) => match ServiceVersion::read(to_value(svr.with_include_archived(true)).unwrap()).await {
+            Ok(sv) if !sv.is_archived() =>
+                Err(DispatcherError::from(Update(format!("restore action: service version '{}' is not archived", sv.version())))),
+            Ok(mut sv) => {
+                sv.restore();
+                let r = persist_json(sv.as_ref()).await;
+                r.map_err(|e| DispatcherError::from(Update(format!("restore action: {}", e))))
+            },
+            Err(e) => Err(DispatcherError::from(NotFound(format!("restore action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("restore action: {}", e))))
+    }
+}
@@ -1,7 +1,8 @@
 use serde_json::{to_value, Value};
 use tracing::info;
-use o008_common::{RequestValidator, TenantRequest, DispatchResult};
-use o008_entity::{persist_json, QueryEntity, Tenant};
+use o008_common::{ListRequest, RequestValidator, TenantRequest, DispatchResult};
+use o008_entity::{DalFilter, destroy_checked, persist_json, QueryEntity, Tenant};
+use crate::action::destroy_result;
 use o008_common::error::AppCommandError::{Create, InvalidRequest, InvalidResponse, NotFound};
 use o008_common::error::DispatcherError;
 
@@ -32,3 +33,25 @@ pub async fn get(trq: TenantRequest) -> DispatchResult<Value> {
         Err(e) => Err(DispatcherError::from(InvalidRequest(format!("get action: {}", e))))
     }
 }
+
+pub async fn list(lrq: ListRequest) -> DispatchResult<Value> {
+    info!("list tenants {:?}", lrq);
+    match lrq.is_valid_get() {
+        Ok(()) => match Tenant::list(DalFilter::new(None, lrq.name().map(|n| n.as_str())), &lrq).await {
+            Ok(page) => Ok(to_value(page).unwrap()),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("list action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("list action: {}", e))))
+    }
+}
+
+pub async fn delete(trq: TenantRequest, cascade: bool) -> DispatchResult<Value> {
+    info!("delete tenant {:?} cascade {}", trq, cascade);
+    match trq.is_valid_get() {
+        Ok(()) => match Tenant::read(to_value(&trq).unwrap()).await {
+            Ok(t) => destroy_result(destroy_checked(t.as_ref(), cascade).await, format!("tenant '{}'", trq.name())),
+            Err(e) => Err(DispatcherError::from(NotFound(format!("delete action: {}", e))))
+        },
+        Err(e) => Err(DispatcherError::from(InvalidRequest(format!("delete action: {}", e))))
+    }
+}
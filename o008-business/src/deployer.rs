@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{info, warn};
+use uuid::Uuid;
+use o008_common::template::render_command;
+use o008_entity::{Deployment, DeploymentSlot, Environment, EntityError, PersistEntity, ServiceVersion};
+use o008_setting::app_config;
+use crate::executor::kill_process_group;
+
+pub const DEPLOY_TIMEOUT: &str = "timeout";
+
+// the configured deploy command with the service version variables, the target environment and slot
+pub fn render_deploy_command(deployment: &Deployment) -> Result<String, String> {
+    let variables = deploy_variables(deployment.service_version(), deployment.environment(), deployment.slot());
+    render_command(app_config().deploy().command(), &variables)
+        .map_err(|e| format!("deploy command: {}", e))
+}
+
+// run a switch or retire command of the slot, the command is optional in the deploy settings
+pub async fn run_slot_command(command: Option<&str>, slot: &DeploymentSlot, action: &str) -> Result<(), String> {
+    match command {
+        Some(command) => {
+            let command = render_slot_command(command, slot, action)?;
+            let log_path = app_config().storage().logs_dir()
+                .join("deployments")
+                .join("slots")
+                .join(format!("{}-{}.log", slot.id(), action));
+            info!("{} slot {} of service version {} in {}", action, slot.name(), slot.service_version().id(), slot.environment().name());
+            run_command(command.as_str(), &log_path).await
+        },
+        None => Ok(())
+    }
+}
+
+fn render_slot_command(command: &str, slot: &DeploymentSlot, action: &str) -> Result<String, String> {
+    let variables = deploy_variables(slot.service_version(), slot.environment(), Some(slot.name()));
+    render_command(command, &variables)
+        .map_err(|e| format!("{} command: {}", action, e))
+}
+
+pub async fn deploy(deployment: Deployment) -> Result<Box<Deployment>, EntityError> {
+    let mut deployment = deployment.persist().await?;
+    info!("deployment {} of service version {} to {} started", deployment.id(), deployment.service_version().id(), deployment.environment().name());
+    let log_path = log_path(deployment.id());
+    deployment.set_logs_link(log_path.to_string_lossy().as_ref());
+    let command = String::from(deployment.command().unwrap_or_default());
+    let r = run_command(command.as_str(), &log_path).await;
+    deployment.finish(r.err().as_deref());
+    let deployment = deployment.persist().await?;
+    info!("deployment {} finished with status {}", deployment.id(), deployment.status());
+    Ok(deployment)
+}
+
+fn deploy_variables(service_version: &ServiceVersion, environment: &Environment, slot: Option<&str>) -> HashMap<&'static str, String> {
+    let mut variables = service_version.command_variables();
+    variables.insert("environment.name", String::from(environment.name()));
+    if let Some(slot) = slot {
+        variables.insert("slot", String::from(slot));
+    }
+    variables
+}
+
+fn log_path(deployment: Uuid) -> PathBuf {
+    app_config().storage().logs_dir()
+        .join("deployments")
+        .join(format!("{}.log", deployment))
+}
+
+async fn run_command(command: &str, log_path: &Path) -> Result<(), String> {
+    if let Some(dir) = log_path.parent() {
+        tokio::fs::create_dir_all(dir).await
+            .map_err(|e| format!("could not create log directory {}: {}", dir.display(), e))?;
+    }
+    let stdout = std::fs::File::create(log_path)
+        .map_err(|e| format!("could not create log file {}: {}", log_path.display(), e))?;
+    let stderr = stdout.try_clone()
+        .map_err(|e| format!("could not create log file {}: {}", log_path.display(), e))?;
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(stdout))
+        .stderr(Stdio::from(stderr))
+        .process_group(0)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("could not launch command '{}': {}", command, e))?;
+    let pid = child.id();
+    let status = match app_config().deploy().timeout().map(Duration::from_secs) {
+        Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => status,
+            Err(_) => {
+                warn!("deploy command '{}' timed out", command);
+                kill_process_group(pid);
+                let _ = child.wait().await;
+                return Err(String::from(DEPLOY_TIMEOUT))
+            }
+        },
+        None => child.wait().await
+    };
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(format!("command '{}' {}", command, s)),
+        Err(e) => Err(format!("could not wait for command '{}': {}", command, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use super::*;
+
+    const HOSTILE: &str = "cart'; touch /tmp/o008-owned; `id` $(id) | &";
+
+    fn service_version(name: &str) -> Value {
+        let tenant = json!({"id": Uuid::nil(), "name": "acme", "coexisting": true});
+        let application = json!({"id": Uuid::nil(), "name": "shop", "tenant": tenant, "class_unit": "cu", "functional_group": "fg"});
+        json!({
+            "id": Uuid::nil(),
+            "version": "1.0.0",
+            "service": {"id": Uuid::nil(), "name": name, "original_name": name, "application": application, "default_repo": ""},
+            "repo_ref": {"id": Uuid::nil(), "repo": "file:///tmp/repo", "kind": "Branch", "reference": "main"},
+            "builder": {"id": Uuid::nil(), "name": "sh", "active": true, "stages": [], "artifacts": []},
+        })
+    }
+
+    fn environment(name: &str) -> Value {
+        json!({
+            "id": Uuid::nil(),
+            "name": name,
+            "tenant": {"id": Uuid::nil(), "name": "acme", "coexisting": true},
+            "position": 0,
+            "require_build": false,
+            "require_approval": false,
+        })
+    }
+
+    fn run(command: &str) -> String {
+        let output = std::process::Command::new("sh").arg("-c").arg(command).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    #[test]
+    fn deploy_command_quotes_shell_metacharacters() {
+        let service_version: ServiceVersion = serde_json::from_value(service_version(HOSTILE)).unwrap();
+        let environment: Environment = serde_json::from_value(environment("$(id)")).unwrap();
+        let variables = deploy_variables(&service_version, &environment, None);
+        let command = render_command("printf '%s|%s' {{service.name}} {{environment.name}}", &variables).unwrap();
+        assert_eq!(run(&command), format!("{}|$(id)", HOSTILE));
+    }
+
+    #[test]
+    fn slot_command_quotes_shell_metacharacters() {
+        let slot: DeploymentSlot = serde_json::from_value(json!({
+            "id": Uuid::nil(),
+            "name": "blue;id",
+            "service_version": service_version(HOSTILE),
+            "environment": environment("prod"),
+            "deployment": Uuid::nil(),
+            "active": true,
+        })).unwrap();
+        let command = render_slot_command("printf '%s|%s' {{service.name}} {{slot}}", &slot, "switch").unwrap();
+        assert_eq!(run(&command), format!("{}|blue;id", HOSTILE));
+    }
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 use o008_common::{AppCommand, CommandDispatcher, DispatchCommand, InternalCommand, ResultDispatcher};
 use o008_message_bus::{handler, RequestMessage};
-use crate::action::{application, builder, service, service_version, tenant};
+use crate::action::{application, approval, artifact, build, builder, deployment, environment, inventory, lock, promotion, repo_reference, service, service_version, tenant};
 
 pub struct RequestMessageCommand(RequestMessage<DispatchCommand>);
 
@@ -17,22 +17,114 @@ impl RequestMessageCommand {
                 handler::request(from, request, builder::get).await,
             AppCommand::DeleteBuilder { request } =>
                 handler::request(from, request, builder::delete).await,
+            AppCommand::ListBuilders { request } =>
+                handler::request(from, request, builder::list).await,
+            AppCommand::RenderBuilderCommand { request } =>
+                handler::request(from, *request, builder::render).await,
             AppCommand::CreateTenant { request } =>
                 handler::request(from, request, tenant::create).await,
             AppCommand::GetTenant { request } =>
                 handler::request(from, request, tenant::get).await,
+            AppCommand::DeleteTenant { request, cascade } =>
+                handler::request(from, request, |r| tenant::delete(r, cascade)).await,
+            AppCommand::ListTenants { request } =>
+                handler::request(from, request, tenant::list).await,
             AppCommand::CreateApplication { request } =>
                 handler::request(from, request, application::create).await,
             AppCommand::GetApplication { request } =>
                 handler::request(from, request, application::get).await,
+            AppCommand::DeleteApplication { request, cascade } =>
+                handler::request(from, request, |r| application::delete(r, cascade)).await,
+            AppCommand::ListApplications { request } =>
+                handler::request(from, request, application::list).await,
+            AppCommand::ArchiveApplication { request, archive } =>
+                handler::request(from, request, |r| application::archive(r, archive)).await,
+            AppCommand::RestoreApplication { request } =>
+                handler::request(from, request, application::restore).await,
+            AppCommand::CreateEnvironment { request } =>
+                handler::request(from, request, environment::create).await,
+            AppCommand::GetEnvironment { request } =>
+                handler::request(from, request, environment::get).await,
+            AppCommand::ListEnvironments { request } =>
+                handler::request(from, request, environment::list).await,
+            AppCommand::GetInventory { request } =>
+                handler::request(from, request, inventory::get).await,
             AppCommand::PersistService { source, request } =>
                 handler::request_with_source(from, source, request, service::persist).await,
             AppCommand::GetService { request } =>
                 handler::request(from, request, service::get).await,
+            AppCommand::DeleteService { request, cascade } =>
+                handler::request(from, request, |r| service::delete(r, cascade)).await,
+            AppCommand::ListServices { request } =>
+                handler::request(from, request, service::list).await,
+            AppCommand::ArchiveService { request, archive } =>
+                handler::request(from, request, |r| service::archive(r, archive)).await,
+            AppCommand::RestoreService { request } =>
+                handler::request(from, request, service::restore).await,
             AppCommand::GetServiceVersions { request } =>
                 handler::request(from, request, service::get_with_versions).await,
+            AppCommand::ListServiceVersions { request } =>
+                handler::request(from, request, service_version::list).await,
+            AppCommand::DeleteServiceVersion { request, cascade } =>
+                handler::request(from, *request, |r| service_version::delete(r, cascade)).await,
+            AppCommand::ArchiveServiceVersion { request, archive } =>
+                handler::request(from, *request, |r| service_version::archive(r, archive)).await,
+            AppCommand::RestoreServiceVersion { request } =>
+                handler::request(from, *request, service_version::restore).await,
             AppCommand::PersistServiceVersion { source, request } =>
-                handler::request_with_source(from, source, request, service_version::persist).await,
+                handler::request_with_source(from, *source, *request, service_version::persist).await,
+            AppCommand::ResolveRepoReference { request } =>
+                handler::request(from, *request, service_version::resolve).await,
+            AppCommand::DeleteRepoReference { request, cascade } =>
+                handler::request(from, request, |r| repo_reference::delete(r, cascade)).await,
+            AppCommand::TriggerBuild { request } =>
+                handler::request(from, *request, build::trigger).await,
+            AppCommand::GetBuild { request } =>
+                handler::request(from, *request, build::get).await,
+            AppCommand::GetBuildStage { request } =>
+                handler::request(from, *request, build::get_stage).await,
+            AppCommand::CancelBuild { request } =>
+                handler::request(from, *request, build::cancel).await,
+            AppCommand::RetryBuild { request } =>
+                handler::request(from, *request, build::retry).await,
+            AppCommand::RerunFromStage { request } =>
+                handler::request(from, *request, build::rerun_from_stage).await,
+            AppCommand::GetArtifacts { request } =>
+                handler::request(from, *request, artifact::list).await,
+            AppCommand::GetArtifact { request } =>
+                handler::request(from, *request, artifact::get).await,
+            AppCommand::DeployServiceVersion { request } =>
+                handler::request(from, *request, deployment::deploy).await,
+            AppCommand::GetDeployments { request } =>
+                handler::request(from, request, deployment::list).await,
+            AppCommand::RollbackDeployment { request } =>
+                handler::request(from, request, deployment::rollback).await,
+            AppCommand::GetDeploymentSlots { request } =>
+                handler::request(from, request, deployment::list_slots).await,
+            AppCommand::SwitchTraffic { request } =>
+                handler::request(from, request, deployment::switch_traffic).await,
+            AppCommand::RetireSlot { request } =>
+                handler::request(from, request, deployment::retire_slot).await,
+            AppCommand::PromoteServiceVersion { request } =>
+                handler::request(from, *request, promotion::promote).await,
+            AppCommand::ApproveDeployment { request } =>
+                handler::request(from, *request, approval::approve).await,
+            AppCommand::RejectDeployment { request } =>
+                handler::request(from, *request, approval::reject).await,
+            AppCommand::SetApprovalPolicy { request } =>
+                handler::request(from, request, approval::set_policy).await,
+            AppCommand::GetApprovalPolicy { request } =>
+                handler::request(from, request, approval::get_policy).await,
+            AppCommand::LockDeployments { request } =>
+                handler::request(from, request, lock::lock).await,
+            AppCommand::UnlockDeployments { request } =>
+                handler::request(from, request, lock::unlock).await,
+            AppCommand::ListDeploymentLocks { request } =>
+                handler::request(from, request, lock::list).await,
+            AppCommand::CreateFreezeWindow { request } =>
+                handler::request(from, request, lock::create_window).await,
+            AppCommand::RemoveFreezeWindow { request } =>
+                handler::request(from, request, lock::remove_window).await,
         };
         ResultDispatcher::Done(r)
     }
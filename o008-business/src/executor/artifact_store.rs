@@ -0,0 +1,42 @@
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use o008_setting::app_config;
+
+pub struct ArtifactStore;
+
+impl ArtifactStore {
+    pub fn path(sha256: &str) -> PathBuf {
+        app_config().storage().artifacts_dir()
+            .join(&sha256[..2])
+            .join(sha256)
+    }
+
+    // copy a file into the store under its content hash, returning the hash and the file size
+    pub async fn store(source: &Path) -> io::Result<(String, u64)> {
+        let source = source.to_path_buf();
+        tokio::task::spawn_blocking(move || store_file(&source)).await?
+    }
+}
+
+fn store_file(source: &Path) -> io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut File::open(source)?, &mut hasher)?;
+    let sha256 = format!("{:x}", hasher.finalize());
+    let target = ArtifactStore::path(&sha256);
+    // same content is already stored, by this build or another one
+    if target.exists() {
+        return Ok((sha256, size))
+    }
+    let dir = target.parent().unwrap();
+    std::fs::create_dir_all(dir)?;
+    // write aside then rename, so a stored file is never seen half written
+    let partial = dir.join(format!(".{}.{}", sha256, Uuid::new_v4()));
+    if let Err(e) = std::fs::copy(source, &partial).and_then(|_| std::fs::rename(&partial, &target)) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e)
+    }
+    Ok((sha256, size))
+}
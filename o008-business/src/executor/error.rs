@@ -0,0 +1,23 @@
+use std::fmt::{Display, Formatter};
+use o008_entity::EntityError;
+
+#[derive(Debug)]
+pub enum ExecutorError {
+    Persist(EntityError),
+}
+
+impl Display for ExecutorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutorError::Persist(e) => write!(f, "could not persist build state: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+impl From<EntityError> for ExecutorError {
+    fn from(value: EntityError) -> Self {
+        ExecutorError::Persist(value)
+    }
+}
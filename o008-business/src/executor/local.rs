@@ -0,0 +1,385 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::time::{Instant, interval, sleep_until};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use o008_common::BuildStatus;
+use o008_common::template::render_command;
+use o008_entity::{Builder, PersistEntity, ServiceVersion, ServiceVersionArtifact, ServiceVersionBuild, ServiceVersionBuildStage};
+use o008_message_bus::BuildEvent;
+use o008_message_bus::helper::send_build_event;
+use o008_setting::app_config;
+use crate::executor::{ArtifactStore, BUILD_CANCELLED, BUILD_TIMEOUT, ExecutorError};
+use crate::executor::stage_log::StageLog;
+use crate::git::{checkout_command, head_commit};
+
+const CHECKOUT_STAGE: i32 = 1;
+const CHECKOUT_STAGE_NAME: &str = "checkout";
+
+pub struct LocalExecutor;
+
+impl LocalExecutor {
+    pub async fn execute(build: ServiceVersionBuild) -> Result<Box<ServiceVersionBuild>, ExecutorError> {
+        let mut build = build;
+        send_build_event(BuildEvent::status(build.id(), None, build.status()));
+        info!("build {} started", build.id());
+
+        let builder = build.service_version().builder().clone();
+        let deadline = build_timeout(builder.timeout()).map(|t| Instant::now() + t);
+        let workspace = workspace_path(build.id());
+        let mut stages = Vec::new();
+        let (error_reason, digest) = match (prepare_workspace(&build, &workspace).await, render_commands(&builder, build.service_version())) {
+            (Ok(()), Ok(commands)) => {
+                let digest = builder_digest(&builder, &commands);
+                let failure = match run_stages(&build, &builder, commands, &workspace, deadline, &mut stages).await? {
+                    None => collect_artifacts(&build, &builder, &workspace).await?,
+                    failure => failure
+                };
+                (failure, Some(digest))
+            },
+            (Err(e), _) | (_, Err(e)) => (Some(e), None)
+        };
+        if let Some(digest) = digest {
+            build.set_cache_key(checkout_commit(&stages).as_deref(), digest.as_str());
+        }
+        build.finish(error_reason.as_deref());
+        build.set_stages(stages);
+
+        let build = build.persist().await?;
+        send_build_event(BuildEvent::status(build.id(), None, build.status()));
+        info!("build {} finished with status {}", build.id(), build.status());
+        Ok(build)
+    }
+}
+
+// run the checkout and the builder stages in order, returning why the build stopped if it did
+async fn run_stages(build: &ServiceVersionBuild, builder: &Builder, commands: Vec<String>, workspace: &Path, deadline: Option<Instant>,
+                    stages: &mut Vec<ServiceVersionBuildStage>) -> Result<Option<String>, ExecutorError> {
+    if ServiceVersionBuild::is_cancel_requested(build.id()).await? {
+        return Ok(Some(String::from(BUILD_CANCELLED)))
+    }
+    let from_stage = build.rerun_from().unwrap_or(CHECKOUT_STAGE);
+    let failure = if from_stage > CHECKOUT_STAGE {
+        reuse_stages(build, from_stage, stages).await?
+    } else {
+        let stage = checkout(build, workspace, deadline).await?;
+        let failure = stage_failure(&stage, false);
+        stages.push(*stage);
+        failure
+    };
+    if failure.is_some() {
+        return Ok(failure)
+    }
+
+    for (i, (definition, command)) in builder.stages().iter().zip(commands).enumerate() {
+        let number = CHECKOUT_STAGE + 1 + i as i32;
+        if number < from_stage {
+            continue
+        }
+        if ServiceVersionBuild::is_cancel_requested(build.id()).await? {
+            return Ok(Some(String::from(BUILD_CANCELLED)))
+        }
+        let mut stage = ServiceVersionBuildStage::new(build.id(), number, definition.name(), definition.description());
+        if let Some(meta) = definition.stage_meta() {
+            stage.set_stage_meta(meta.clone())
+        }
+        let stage = run_stage(stage, command.as_str(), workspace, deadline).await?;
+        let failure = stage_failure(&stage, definition.continue_on_error());
+        stages.push(*stage);
+        if failure.is_some() {
+            return Ok(failure)
+        }
+    }
+    Ok(None)
+}
+
+// the reason the build has to stop after this stage, if any
+fn stage_failure(stage: &ServiceVersionBuildStage, continue_on_error: bool) -> Option<String> {
+    match stage.error_reason() {
+        Some(e) if e == BUILD_CANCELLED || e == BUILD_TIMEOUT => Some(String::from(e)),
+        Some(_) if continue_on_error => {
+            info!("build {} stage {} failed, continue on error", stage.build(), stage.name());
+            None
+        },
+        Some(e) => Some(format!("stage {} failed: {}", stage.name(), e)),
+        None => None
+    }
+}
+
+// keep the declared outputs of a successful build in the artifact store
+async fn collect_artifacts(build: &ServiceVersionBuild, builder: &Builder, workspace: &Path) -> Result<Option<String>, ExecutorError> {
+    for name in builder.artifacts() {
+        let source = workspace.join(name);
+        if !tokio::fs::metadata(&source).await.map(|m| m.is_file()).unwrap_or(false) {
+            return Ok(Some(format!("artifact {} not found", name)))
+        }
+        match ArtifactStore::store(&source).await {
+            Ok((sha256, size)) => {
+                let artifact = ServiceVersionArtifact::new(build.service_version().id(), build.id(), name, size as i64, sha256.as_str());
+                artifact.persist().await?;
+                info!("build {} artifact {} stored as {}", build.id(), name, sha256);
+            },
+            Err(e) => return Ok(Some(format!("artifact {} could not be stored: {}", name, e)))
+        }
+    }
+    Ok(None)
+}
+
+fn workspace_path(build: Uuid) -> PathBuf {
+    app_config().storage().workspaces_dir().join(build.to_string())
+}
+
+// the stages before the rerun one are taken from the build it reruns, they all have to be a success
+async fn reuse_stages(build: &ServiceVersionBuild, from_stage: i32, stages: &mut Vec<ServiceVersionBuildStage>) -> Result<Option<String>, ExecutorError> {
+    let previous = match build.retry_of() {
+        Some(previous) => previous,
+        None => return Ok(Some(format!("build {} reruns from stage {} without a previous build", build.id(), from_stage)))
+    };
+    let previous_stages = ServiceVersionBuildStage::build_stages(previous).await?;
+    for number in CHECKOUT_STAGE..from_stage {
+        match previous_stages.iter().find(|s| s.stage() == number) {
+            Some(s) if s.status() == BuildStatus::Succeeded => {
+                let stage = s.reuse(build.id()).persist().await?;
+                send_build_event(BuildEvent::status(stage.build(), Some(stage.stage()), stage.status()));
+                info!("build {} stage {} reused from build {}", build.id(), stage.stage(), previous);
+                stages.push(*stage);
+            },
+            _ => return Ok(Some(format!("stage {} of build {} can not be reused", number, previous)))
+        }
+    }
+    Ok(None)
+}
+
+// a requeued build starts again from a clean workspace, a rerun one from a copy of the workspace of the build it reruns
+async fn prepare_workspace(build: &ServiceVersionBuild, workspace: &Path) -> Result<(), String> {
+    if tokio::fs::try_exists(workspace).await.unwrap_or(false) {
+        tokio::fs::remove_dir_all(workspace).await
+            .map_err(|e| format!("could not clean workspace {}: {}", workspace.display(), e))?;
+    }
+    tokio::fs::create_dir_all(workspace).await
+        .map_err(|e| format!("could not create workspace {}: {}", workspace.display(), e))?;
+    match (build.retry_of(), build.rerun_from()) {
+        (Some(previous), Some(from_stage)) if from_stage > CHECKOUT_STAGE => {
+            let (source, target) = (workspace_path(previous), workspace.to_path_buf());
+            tokio::task::spawn_blocking(move || copy_dir(&source, &target)).await
+                .map_err(|e| format!("could not copy workspace of build {}: {}", previous, e))?
+                .map_err(|e| format!("could not copy workspace of build {}: {}", previous, e))
+        },
+        _ => Ok(())
+    }
+}
+
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let destination = target.join(entry.file_name());
+        if file_type.is_dir() {
+            std::fs::create_dir(&destination)?;
+            copy_dir(&entry.path(), &destination)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &destination)?;
+        } else {
+            std::fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
+
+async fn checkout(build: &ServiceVersionBuild, workspace: &Path, deadline: Option<Instant>) -> Result<Box<ServiceVersionBuildStage>, ExecutorError> {
+    let service_version = build.service_version();
+    let repo_ref = service_version.repo_ref();
+    let repo = service_version.repo();
+    let mut meta = json!({"repo": repo, "kind": repo_ref.kind(), "reference": repo_ref.reference()});
+    let mut stage = ServiceVersionBuildStage::new(build.id(), CHECKOUT_STAGE, CHECKOUT_STAGE_NAME, Some(repo));
+    stage.set_stage_meta(meta.clone());
+    let command = checkout_command(repo, repo_ref.kind(), repo_ref.reference());
+    let mut stage = run_stage(stage, command.as_str(), workspace, deadline).await?;
+    if !stage.in_error() {
+        match head_commit(workspace).await {
+            Ok(commit) => {
+                meta["commit"] = json!(commit);
+                stage.set_stage_meta(meta);
+                stage = stage.persist().await?;
+            },
+            Err(e) => warn!("build {} could not resolve checked out commit: {}", build.id(), e)
+        }
+    }
+    Ok(stage)
+}
+
+// the commit checked out by the build, a reused checkout stage keeps it in its meta too
+fn checkout_commit(stages: &[ServiceVersionBuildStage]) -> Option<String> {
+    stages.iter()
+        .find(|s| s.stage() == CHECKOUT_STAGE)
+        .and_then(|s| s.stage_meta())
+        .and_then(|meta| meta["commit"].as_str())
+        .map(String::from)
+}
+
+// identify what a builder runs for a service version, two builds with the same digest on the same commit produce the same outputs
+pub fn builder_digest(builder: &Builder, commands: &[String]) -> String {
+    let stages: Vec<_> = builder.stages().iter().zip(commands)
+        .map(|(stage, command)| json!({"name": stage.name(), "command": command, "continue_on_error": stage.continue_on_error()}))
+        .collect();
+    let definition = json!({"stages": stages, "artifacts": builder.artifacts()});
+    format!("{:x}", Sha256::digest(definition.to_string().as_bytes()))
+}
+
+pub fn render_commands(builder: &Builder, service_version: &ServiceVersion) -> Result<Vec<String>, String> {
+    let variables = service_version.command_variables();
+    builder.stages().iter()
+        .map(|stage| render_command(stage.command(), &variables)
+            .map_err(|e| format!("stage {} command: {}", stage.name(), e)))
+        .collect()
+}
+
+// the builder timeout can only narrow the global one
+fn build_timeout(builder_timeout: Option<i32>) -> Option<Duration> {
+    let builder_timeout = builder_timeout.map(|t| Duration::from_secs(t as u64));
+    let global_timeout = app_config().build().timeout().map(Duration::from_secs);
+    match (builder_timeout, global_timeout) {
+        (Some(b), Some(g)) => Some(b.min(g)),
+        (b, g) => b.or(g)
+    }
+}
+
+async fn run_stage(stage: ServiceVersionBuildStage, command: &str, workdir: &Path, deadline: Option<Instant>) -> Result<Box<ServiceVersionBuildStage>, ExecutorError> {
+    let mut stage = stage;
+    let log_path = StageLog::path(stage.build(), stage.stage());
+    stage.set_logs_link(log_path.to_string_lossy().as_ref());
+    stage.start();
+    let mut stage = stage.persist().await?;
+    send_build_event(BuildEvent::status(stage.build(), Some(stage.stage()), stage.status()));
+
+    let r = run_command(&stage, command, &log_path, workdir, deadline).await;
+    stage.finish(r.err().as_deref());
+    let stage = stage.persist().await?;
+    send_build_event(BuildEvent::status(stage.build(), Some(stage.stage()), stage.status()));
+    Ok(stage)
+}
+
+async fn run_command(stage: &ServiceVersionBuildStage, command: &str, log_path: &Path, workdir: &Path, deadline: Option<Instant>) -> Result<(), String> {
+    let mut log = StageLog::create(stage.build(), stage.stage(), log_path).await
+        .map_err(|e| format!("could not create log file {}: {}", log_path.display(), e))?;
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("could not launch command '{}': {}", command, e))?;
+    let pid = child.id();
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+    let expiry = sleep_until(deadline.unwrap_or_else(Instant::now));
+    tokio::pin!(expiry);
+    let mut cancel_check = interval(Duration::from_millis(app_config().build().poll_interval()));
+    let r = loop {
+        tokio::select! {
+            line = stdout.next_line(), if stdout_open => match line {
+                Ok(Some(l)) => log.write_line(&l).await,
+                _ => stdout_open = false,
+            },
+            line = stderr.next_line(), if stderr_open => match line {
+                Ok(Some(l)) => log.write_line(&l).await,
+                _ => stderr_open = false,
+            },
+            status = child.wait(), if !stdout_open && !stderr_open => break match status {
+                Ok(s) if s.success() => Ok(()),
+                Ok(s) => Err(format!("command '{}' {}", command, s)),
+                Err(e) => Err(format!("could not wait for command '{}': {}", command, e)),
+            },
+            _ = &mut expiry, if deadline.is_some() => {
+                warn!("build {} stage {} timed out", stage.build(), stage.stage());
+                kill_process_group(pid);
+                break Err(String::from(BUILD_TIMEOUT))
+            },
+            _ = cancel_check.tick() => match ServiceVersionBuild::is_cancel_requested(stage.build()).await {
+                Ok(true) => {
+                    warn!("build {} stage {} cancelled", stage.build(), stage.stage());
+                    kill_process_group(pid);
+                    break Err(String::from(BUILD_CANCELLED))
+                },
+                Ok(false) => (),
+                // the stage keeps running, the cancellation is checked again on the next tick
+                Err(e) => error!("build {} stage {} could not check its cancellation: {}", stage.build(), stage.stage(), e),
+            },
+        }
+    };
+    log.close().await;
+    // reap the killed command, a no-op when it already exited
+    let _ = child.wait().await;
+    r
+}
+
+pub(crate) fn kill_process_group(pid: Option<u32>) {
+    if let Some(pid) = pid {
+        // the command leads its own process group, so the whole tree gets the signal
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use o008_common::BuilderStage;
+    use super::*;
+
+    fn builder(name: &str, stages: &[(&str, bool)], artifacts: &[&str]) -> Builder {
+        let stages = stages.iter()
+            .enumerate()
+            .map(|(i, (command, continue_on_error))| BuilderStage::new(&format!("stage{}", i), None, command, *continue_on_error, None))
+            .collect();
+        Builder::new(name, true, stages, None, artifacts.iter().map(|a| String::from(*a)).collect())
+    }
+
+    fn commands(builder: &Builder) -> Vec<String> {
+        builder.stages().iter().map(|s| String::from(s.command())).collect()
+    }
+
+    fn digest(builder: &Builder) -> String {
+        builder_digest(builder, &commands(builder))
+    }
+
+    #[test]
+    fn digest_is_stable() {
+        let b = builder("sh", &[("make", false), ("make test", true)], &["target/app"]);
+        assert_eq!(digest(&b), digest(&b.clone()));
+        assert_eq!(digest(&b).len(), 64);
+    }
+
+    #[test]
+    fn digest_ignores_the_builder_identity() {
+        let b = builder("sh", &[("make", false)], &["target/app"]);
+        let renamed = builder("make", &[("make", false)], &["target/app"]);
+        assert_eq!(digest(&b), digest(&renamed));
+    }
+
+    #[test]
+    fn digest_changes_with_the_definition() {
+        let b = digest(&builder("sh", &[("make", false), ("make test", false)], &["target/app"]));
+        assert_ne!(b, digest(&builder("sh", &[("make all", false), ("make test", false)], &["target/app"])));
+        assert_ne!(b, digest(&builder("sh", &[("make test", false), ("make", false)], &["target/app"])));
+        assert_ne!(b, digest(&builder("sh", &[("make", false), ("make test", true)], &["target/app"])));
+        assert_ne!(b, digest(&builder("sh", &[("make", false), ("make test", false)], &["target/app", "target/lib"])));
+    }
+
+    #[test]
+    fn digest_uses_the_rendered_commands() {
+        let b = builder("sh", &[("make {{version}}", false)], &[]);
+        assert_ne!(builder_digest(&b, &[String::from("make '1.0.0'")]), builder_digest(&b, &[String::from("make '2.0.0'")]));
+    }
+}
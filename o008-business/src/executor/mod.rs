@@ -0,0 +1,14 @@
+mod artifact_store;
+mod error;
+mod local;
+mod stage_log;
+mod worker;
+
+pub use artifact_store::ArtifactStore;
+pub use error::ExecutorError;
+pub use local::{builder_digest, LocalExecutor, render_commands};
+pub(crate) use local::kill_process_group;
+pub use worker::BuildWorker;
+
+pub const BUILD_CANCELLED: &str = "cancelled";
+pub const BUILD_TIMEOUT: &str = "timeout";
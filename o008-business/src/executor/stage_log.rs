@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+use uuid::Uuid;
+use o008_message_bus::BuildEvent;
+use o008_message_bus::helper::send_build_event;
+use o008_setting::app_config;
+
+pub struct StageLog {
+    build: Uuid,
+    stage: i32,
+    file: File,
+}
+
+impl StageLog {
+    pub fn path(build: Uuid, stage: i32) -> PathBuf {
+        app_config().storage().logs_dir()
+            .join(build.to_string())
+            .join(format!("{}.log", stage))
+    }
+
+    pub async fn create(build: Uuid, stage: i32, path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        Ok(Self {
+            build,
+            stage,
+            file: File::create(path).await?,
+        })
+    }
+
+    pub async fn write_line(&mut self, line: &str) {
+        if let Err(e) = self.file.write_all(format!("{}\n", line).as_bytes()).await {
+            warn!("build {} stage {} could not write log line: {}", self.build, self.stage, e)
+        }
+        send_build_event(BuildEvent::log(self.build, self.stage, line));
+    }
+
+    pub async fn close(mut self) {
+        if let Err(e) = self.file.flush().await {
+            warn!("build {} stage {} could not flush log: {}", self.build, self.stage, e)
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use o008_entity::{DestroyEntity, PersistEntity, QueryEntity, ServiceVersionArtifact, ServiceVersionBuild, ServiceVersionBuildKey, ServiceVersionBuildStage};
+use o008_message_bus::BuildEvent;
+use o008_message_bus::helper::send_build_event;
+use o008_setting::app_config;
+use crate::executor::LocalExecutor;
+
+pub struct BuildWorker;
+
+impl BuildWorker {
+    pub fn launch() -> JoinHandle<()> {
+        let cfg = app_config().build();
+        let slots = Arc::new(Semaphore::new(cfg.max_concurrency()));
+        let poll_interval = Duration::from_millis(cfg.poll_interval());
+        let lease = Duration::from_secs(cfg.lease());
+        let worker = Uuid::new_v4();
+        let running: Arc<Mutex<HashSet<Uuid>>> = Arc::default();
+        tokio::spawn(keep_leases(worker, lease, Arc::clone(&running)));
+        tokio::spawn(async move {
+            info!("build worker {} started with max concurrency {}", worker, cfg.max_concurrency());
+            loop {
+                let slot = Arc::clone(&slots).acquire_owned().await.unwrap();
+                match ServiceVersionBuild::claim_next(worker, lease).await {
+                    Ok(Some(build)) => {
+                        info!("build {} claimed", build.id());
+                        let running = RunningBuild::new(build.id(), Arc::clone(&running));
+                        tokio::spawn(async move {
+                            let id = build.id();
+                            if let Err(e) = LocalExecutor::execute(*build).await {
+                                error!("build {} could not be executed: {}", id, e);
+                                fail(id, e.to_string().as_str()).await
+                            }
+                            drop(running);
+                            drop(slot)
+                        });
+                    },
+                    Ok(None) => {
+                        drop(slot);
+                        sleep(poll_interval).await
+                    },
+                    Err(e) => {
+                        error!("could not claim next build: {}", e);
+                        drop(slot);
+                        sleep(poll_interval).await
+                    }
+                }
+            }
+        })
+    }
+}
+
+// a build this worker renews the lease of, until its task ends even when the executor panics,
+// then the lease expires and the build is requeued
+struct RunningBuild {
+    id: Uuid,
+    running: Arc<Mutex<HashSet<Uuid>>>,
+}
+
+impl RunningBuild {
+    fn new(id: Uuid, running: Arc<Mutex<HashSet<Uuid>>>) -> Self {
+        running.lock().unwrap().insert(id);
+        Self {
+            id,
+            running,
+        }
+    }
+}
+
+impl Drop for RunningBuild {
+    fn drop(&mut self) {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner).remove(&self.id);
+    }
+}
+
+// renew the lease of the builds this worker runs, and recover the running builds of workers
+// that stopped renewing theirs, a live worker never loses its builds to another one
+async fn keep_leases(worker: Uuid, lease: Duration, running: Arc<Mutex<HashSet<Uuid>>>) {
+    let mut ticks = interval(lease / 3);
+    loop {
+        ticks.tick().await;
+        let builds: Vec<Uuid> = running.lock().unwrap().iter().copied().collect();
+        if !builds.is_empty() {
+            if let Err(e) = ServiceVersionBuild::renew_leases(worker, &builds, lease).await {
+                error!("build worker {} could not renew its leases: {}", worker, e)
+            }
+        }
+        requeue_expired(worker, lease).await
+    }
+}
+
+// builds with an expired lease were interrupted with their worker,
+// so their partial stages and artifacts are discarded and they go back to the queue
+async fn requeue_expired(worker: Uuid, lease: Duration) {
+    match ServiceVersionBuild::take_expired(worker, lease).await {
+        Ok(builds) => for mut build in builds {
+            warn!("requeue interrupted build {}", build.id());
+            if let Ok(stages) = ServiceVersionBuildStage::build_stages(build.id()).await {
+                for stage in stages {
+                    if let Err(e) = stage.destroy().await {
+                        error!("build {} could not discard stage {}: {}", build.id(), stage.stage(), e)
+                    }
+                }
+            }
+            if let Ok(artifacts) = ServiceVersionArtifact::build_artifacts(build.id()).await {
+                for artifact in artifacts {
+                    if let Err(e) = artifact.destroy().await {
+                        error!("build {} could not discard artifact {}: {}", build.id(), artifact.name(), e)
+                    }
+                }
+            }
+            build.requeue();
+            if let Err(e) = build.persist().await {
+                error!("could not requeue build {}: {}", build.id(), e)
+            }
+        },
+        Err(e) => error!("could not read interrupted builds: {}", e)
+    }
+}
+
+// a build the executor gave up on is failed, otherwise it would stay running
+async fn fail(id: Uuid, error_reason: &str) {
+    match ServiceVersionBuild::read(ServiceVersionBuildKey::Id(id)).await {
+        Ok(mut build) if !build.completed() => {
+            build.finish(Some(error_reason));
+            match build.persist().await {
+                Ok(build) => {
+                    send_build_event(BuildEvent::status(build.id(), None, build.status()));
+                },
+                Err(e) => error!("build {} could not be marked as failed, it is requeued when its lease expires: {}", id, e)
+            }
+        },
+        Ok(_) => (),
+        Err(e) => error!("build {} could not be marked as failed, it is requeued when its lease expires: {}", id, e)
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+use tokio::process::Command;
+use o008_common::RepoReferenceKind;
+use o008_common::template::shell_quote;
+
+pub fn checkout_command(repo: &str, kind: RepoReferenceKind, reference: &str) -> String {
+    let revision = match kind {
+        RepoReferenceKind::Branch => format!("refs/remotes/origin/{}", reference),
+        RepoReferenceKind::Tag => format!("refs/tags/{}", reference),
+        RepoReferenceKind::Commit => String::from(reference),
+    };
+    format!("git clone --quiet --no-checkout -- {} . && git checkout --quiet --detach {} -- && git log -1 --format='%H %s'",
+            shell_quote(repo), shell_quote(revision.as_str()))
+}
+
+pub async fn head_commit(workspace: &Path) -> Result<String, String> {
+    git(Some(workspace), &["rev-parse", "HEAD"]).await
+}
+
+// only repositories reachable on this host can be resolved without a checkout
+pub fn is_local_repo(repo: &str) -> bool {
+    repo.starts_with("file://") || (!repo.is_empty() && !repo.contains(':'))
+}
+
+pub async fn resolve_reference(repo: &str, kind: RepoReferenceKind, reference: &str) -> Result<String, String> {
+    let (pattern, peeled) = match kind {
+        RepoReferenceKind::Branch => (format!("refs/heads/{}", reference), None),
+        // an annotated tag points to a tag object, its peeled entry is the commit
+        RepoReferenceKind::Tag => (format!("refs/tags/{}", reference), Some(format!("refs/tags/{}^{{}}", reference))),
+        RepoReferenceKind::Commit => return resolve_commit(repo, reference).await,
+    };
+    let mut args = vec!["ls-remote", "--", repo, pattern.as_str()];
+    if let Some(p) = peeled.as_deref() {
+        args.push(p)
+    }
+    let refs = git(None, &args).await?;
+    let commits: Vec<(&str, &str)> = refs.lines()
+        .filter_map(|l| l.split_once('\t'))
+        .collect();
+    peeled.as_deref()
+        .and_then(|p| commits.iter().find(|(_, r)| *r == p))
+        .or_else(|| commits.iter().find(|(_, r)| *r == pattern))
+        .map(|(commit, _)| String::from(*commit))
+        .ok_or_else(|| format!("{} {} not found in {}", kind, reference, repo))
+}
+
+// a full commit SHA is used as is, an abbreviated one is expanded by a local repository
+async fn resolve_commit(repo: &str, reference: &str) -> Result<String, String> {
+    if reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(reference.to_ascii_lowercase())
+    }
+    if !is_local_repo(repo) {
+        return Err(format!("commit {} can only be expanded in a local repository, not in {}", reference, repo))
+    }
+    let path = repo.strip_prefix("file://").unwrap_or(repo);
+    let revision = format!("{}^{{commit}}", reference);
+    git(Some(Path::new(path)), &["rev-parse", "--verify", "--quiet", "--end-of-options", revision.as_str()]).await
+        .map_err(|_| format!("commit {} not found in {}", reference, repo))
+}
+
+async fn git(workdir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.args(args);
+    if let Some(w) = workdir {
+        command.current_dir(w);
+    }
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("could not launch git {}: {}", args.join(" "), e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!("git {} {}: {}", args.join(" "), output.status, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use super::*;
+
+    async fn repo() -> (PathBuf, String) {
+        let path = std::env::temp_dir().join(format!("o008-git-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        git(Some(&path), &["init", "--quiet"]).await.unwrap();
+        git(Some(&path), &["-c", "user.name=o008", "-c", "user.email=o008@localhost", "commit", "--quiet", "--allow-empty", "-m", "init"]).await.unwrap();
+        let commit = head_commit(&path).await.unwrap();
+        (path, commit)
+    }
+
+    #[tokio::test]
+    async fn short_commit_resolves_to_the_full_sha() {
+        let (path, commit) = repo().await;
+        let repo = path.to_str().unwrap();
+        assert_eq!(resolve_reference(repo, RepoReferenceKind::Commit, &commit[..7]).await, Ok(commit.clone()));
+        assert_eq!(resolve_reference(&format!("file://{}", repo), RepoReferenceKind::Commit, &commit[..12]).await, Ok(commit.clone()));
+        assert_eq!(resolve_reference(repo, RepoReferenceKind::Commit, &commit.to_uppercase()).await, Ok(commit.clone()));
+        assert!(resolve_reference(repo, RepoReferenceKind::Commit, "0000000").await.is_err());
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn short_commit_of_a_remote_repository_is_not_resolved() {
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(resolve_reference("https://example.com/repo.git", RepoReferenceKind::Commit, commit).await, Ok(String::from(commit)));
+        assert!(resolve_reference("https://example.com/repo.git", RepoReferenceKind::Commit, &commit[..7]).await.is_err());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod action;
 pub mod dispatcher;
+pub mod executor;
+mod deployer;
+mod git;
 
 
@@ -1,5 +1,6 @@
+use std::str::FromStr;
 use clap::Subcommand;
-use crate::{ApplicationRequest, BuilderRequest, ServiceRequest, TenantRequest};
+use crate::{ApplicationRequest, ApprovalPolicyRequest, ApprovalRequest, ArchiveRequest, ArtifactRequest, BuilderRequest, BuildRequest, BuildStageRequest, BuildTriggerRequest, DeploymentHistoryRequest, DeploymentLockRequest, DeploymentRequest, DeploymentRollbackRequest, DeploymentSlotRequest, EnvironmentRequest, FreezeWindowRequest, ListRequest, PromotionRequest, RepoReferenceRequest, RepoReferenceResolveRequest, ServiceRequest, TenantRequest};
 use crate::request::service_version::ServiceVersionRequest;
 
 #[derive(Subcommand, Debug, Clone)]
@@ -16,6 +17,14 @@ pub enum AppCommand {
         #[arg(short, long)]
         request: BuilderRequest,
     },
+    ListBuilders {
+        #[arg(short, long)]
+        request: ListRequest,
+    },
+    RenderBuilderCommand {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+    },
     CreateTenant {
         #[arg(short, long)]
         request: TenantRequest,
@@ -24,6 +33,16 @@ pub enum AppCommand {
         #[arg(short, long)]
         request: TenantRequest,
     },
+    DeleteTenant {
+        #[arg(short, long)]
+        request: TenantRequest,
+        #[arg(long)]
+        cascade: bool,
+    },
+    ListTenants {
+        #[arg(short, long)]
+        request: ListRequest,
+    },
     CreateApplication {
         #[arg(short, long)]
         request: ApplicationRequest,
@@ -32,6 +51,42 @@ pub enum AppCommand {
         #[arg(short, long)]
         request: ApplicationRequest,
     },
+    DeleteApplication {
+        #[arg(short, long)]
+        request: ApplicationRequest,
+        #[arg(long)]
+        cascade: bool,
+    },
+    ListApplications {
+        #[arg(short, long)]
+        request: ListRequest,
+    },
+    ArchiveApplication {
+        #[arg(short, long)]
+        request: ApplicationRequest,
+        #[arg(short, long)]
+        archive: ArchiveRequest,
+    },
+    RestoreApplication {
+        #[arg(short, long)]
+        request: ApplicationRequest,
+    },
+    CreateEnvironment {
+        #[arg(short, long)]
+        request: EnvironmentRequest,
+    },
+    GetEnvironment {
+        #[arg(short, long)]
+        request: EnvironmentRequest,
+    },
+    ListEnvironments {
+        #[arg(short, long)]
+        request: TenantRequest,
+    },
+    GetInventory {
+        #[arg(short, long)]
+        request: TenantRequest,
+    },
     PersistService {
         #[arg(short, long)]
         source: ServiceRequest,
@@ -41,13 +96,165 @@ pub enum AppCommand {
         #[arg(short, long)]
         request: ServiceRequest,
     },
+    DeleteService {
+        #[arg(short, long)]
+        request: ServiceRequest,
+        #[arg(long)]
+        cascade: bool,
+    },
+    ListServices {
+        #[arg(short, long)]
+        request: ListRequest,
+    },
+    ArchiveService {
+        #[arg(short, long)]
+        request: ServiceRequest,
+        #[arg(short, long)]
+        archive: ArchiveRequest,
+    },
+    RestoreService {
+        #[arg(short, long)]
+        request: ServiceRequest,
+    },
     GetServiceVersions {
         #[arg(short, long)]
         request: ServiceRequest,
     },
+    ListServiceVersions {
+        #[arg(short, long)]
+        request: ListRequest,
+    },
+    DeleteServiceVersion {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+        #[arg(long)]
+        cascade: bool,
+    },
+    ArchiveServiceVersion {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+        #[arg(short, long)]
+        archive: ArchiveRequest,
+    },
+    RestoreServiceVersion {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+    },
     PersistServiceVersion {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        source: Box<ServiceVersionRequest>,
+        #[arg(value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+    },
+    ResolveRepoReference {
+        #[arg(short, long, value_parser = boxed::<RepoReferenceResolveRequest>)]
+        request: Box<RepoReferenceResolveRequest>,
+    },
+    DeleteRepoReference {
         #[arg(short, long)]
-        source: ServiceVersionRequest,
-        request: ServiceVersionRequest,
+        request: RepoReferenceRequest,
+        #[arg(long)]
+        cascade: bool,
+    },
+    TriggerBuild {
+        #[arg(short, long, value_parser = boxed::<BuildTriggerRequest>)]
+        request: Box<BuildTriggerRequest>,
+    },
+    GetBuild {
+        #[arg(short, long, value_parser = boxed::<BuildRequest>)]
+        request: Box<BuildRequest>,
+    },
+    GetBuildStage {
+        #[arg(short, long, value_parser = boxed::<BuildStageRequest>)]
+        request: Box<BuildStageRequest>,
+    },
+    CancelBuild {
+        #[arg(short, long, value_parser = boxed::<BuildRequest>)]
+        request: Box<BuildRequest>,
+    },
+    RetryBuild {
+        #[arg(short, long, value_parser = boxed::<BuildRequest>)]
+        request: Box<BuildRequest>,
+    },
+    RerunFromStage {
+        #[arg(short, long, value_parser = boxed::<BuildStageRequest>)]
+        request: Box<BuildStageRequest>,
+    },
+    GetArtifacts {
+        #[arg(short, long, value_parser = boxed::<ServiceVersionRequest>)]
+        request: Box<ServiceVersionRequest>,
+    },
+    GetArtifact {
+        #[arg(short, long, value_parser = boxed::<ArtifactRequest>)]
+        request: Box<ArtifactRequest>,
+    },
+    DeployServiceVersion {
+        #[arg(short, long, value_parser = boxed::<DeploymentRequest>)]
+        request: Box<DeploymentRequest>,
+    },
+    GetDeployments {
+        #[arg(short, long)]
+        request: DeploymentHistoryRequest,
+    },
+    RollbackDeployment {
+        #[arg(short, long)]
+        request: DeploymentRollbackRequest,
+    },
+    GetDeploymentSlots {
+        #[arg(short, long)]
+        request: DeploymentSlotRequest,
+    },
+    SwitchTraffic {
+        #[arg(short, long)]
+        request: DeploymentSlotRequest,
+    },
+    RetireSlot {
+        #[arg(short, long)]
+        request: DeploymentSlotRequest,
+    },
+    PromoteServiceVersion {
+        #[arg(short, long, value_parser = boxed::<PromotionRequest>)]
+        request: Box<PromotionRequest>,
+    },
+    ApproveDeployment {
+        #[arg(short, long, value_parser = boxed::<ApprovalRequest>)]
+        request: Box<ApprovalRequest>,
+    },
+    RejectDeployment {
+        #[arg(short, long, value_parser = boxed::<ApprovalRequest>)]
+        request: Box<ApprovalRequest>,
+    },
+    SetApprovalPolicy {
+        #[arg(short, long)]
+        request: ApprovalPolicyRequest,
+    },
+    GetApprovalPolicy {
+        #[arg(short, long)]
+        request: TenantRequest,
+    },
+    LockDeployments {
+        #[arg(short, long)]
+        request: DeploymentLockRequest,
+    },
+    UnlockDeployments {
+        #[arg(short, long)]
+        request: DeploymentLockRequest,
+    },
+    ListDeploymentLocks {
+        #[arg(short, long)]
+        request: TenantRequest,
+    },
+    CreateFreezeWindow {
+        #[arg(short, long)]
+        request: FreezeWindowRequest,
+    },
+    RemoveFreezeWindow {
+        #[arg(short, long)]
+        request: FreezeWindowRequest,
     }
 }
+
+// the large requests are boxed to keep the commands small, clap parses them with their own parser
+fn boxed<T: FromStr<Err = String>>(s: &str) -> Result<Box<T>, String> {
+    s.parse().map(Box::new)
+}
@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use serde_json::Value;
 
 
 #[derive(Debug, Clone)]
@@ -8,7 +9,9 @@ pub enum AppCommandError {
     NotFound(String),
     Destroy(String),
     InvalidRequest(String),
-    InvalidResponse(String)
+    InvalidResponse(String),
+    Locked(String),
+    Conflict(Value)
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +34,8 @@ impl Display for AppCommandError {
             AppCommandError::Destroy(s) => write!(f, "destroy: {}", s),
             AppCommandError::InvalidRequest(s) => write!(f, "invalid request: {}", s),
             AppCommandError::InvalidResponse(s) => write!(f, "invalid response: {}", s),
+            AppCommandError::Locked(s) => write!(f, "locked: {}", s),
+            AppCommandError::Conflict(v) => write!(f, "conflict: {}", v),
         }
     }
 }
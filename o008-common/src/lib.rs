@@ -1,20 +1,39 @@
 use std::error::Error as StdError;
 use async_trait::async_trait;
-use sqlx::types::Uuid;
+use uuid::Uuid;
 
 mod macros;
 mod command;
 mod request;
 pub mod error;
+pub mod template;
 pub use command::{AppCommand, InternalCommand, DispatchCommand, DispatchResponse};
 pub use macros::ScopeCall;
 pub use request::application::ApplicationRequest;
+pub use request::environment::EnvironmentRequest;
 pub use request::builder::BuilderRequest;
+pub use request::builder_stage::BuilderStage;
 pub use request::repo_reference::RepoReferenceRequest;
 pub use request::repo_reference_kind::RepoReferenceKind;
+pub use request::repo_reference_resolve::RepoReferenceResolveRequest;
 pub use request::service::ServiceRequest;
 pub use request::service_version::ServiceVersionRequest;
 pub use request::tenant::TenantRequest;
+pub use request::build_status::BuildStatus;
+pub use request::deployment::{DeploymentHistoryRequest, DeploymentRequest, DeploymentRollbackRequest, DeploymentSlotRequest};
+pub use request::deployment_status::DeploymentStatus;
+pub use request::deployment_lock::{DeploymentLockRequest, FreezeWindowRequest};
+pub use request::promotion::PromotionRequest;
+pub use request::release_status::ReleaseStatus;
+pub use request::build::BuildRequest;
+pub use request::build_trigger::BuildTriggerRequest;
+pub use request::build_stage::BuildStageRequest;
+pub use request::artifact::ArtifactRequest;
+pub use request::approval::{ApprovalPolicyRequest, ApprovalRequest};
+pub use request::approval_decision::ApprovalDecision;
+pub use request::archive::ArchiveRequest;
+pub use request::list::ListRequest;
+pub use request::sort_order::SortOrder;
 pub use request::RequestValidator;
 pub use error::{AppCommandError, DispatcherError, InternalCommandError};
 
@@ -25,6 +44,14 @@ pub trait AsyncFrom<T>  where T: Send + Unpin + Sized {
     async fn from(value: T) -> Self;
 }
 
+// a conversion reading what the value references, so it fails with the reads
+#[async_trait]
+pub trait TryAsyncFrom<T>: Sized where T: Send + Unpin + Sized {
+    type Error;
+
+    async fn try_from(value: T) -> Result<Self, Self::Error>;
+}
+
 pub trait TypeInfo {
     fn type_name() -> &'static str;
     fn type_of(&self) -> &'static str;
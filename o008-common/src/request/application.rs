@@ -11,6 +11,7 @@ pub struct ApplicationRequest {
     tenant: Option<TenantRequest>,
     class_unit: Option<String>,
     functional_group: Option<String>,
+    include_archived: Option<bool>,
 }
 
 impl ApplicationRequest {
@@ -20,6 +21,7 @@ impl ApplicationRequest {
             tenant: t,
             class_unit: cu,
             functional_group: fg,
+            include_archived: None,
         }
     }
 
@@ -45,8 +47,30 @@ impl ApplicationRequest {
             tenant: Some(TenantRequest::build_get_request(tenant)),
             class_unit: None,
             functional_group: None,
+            include_archived: None,
         }
     }
+
+    // the name and the tenant come from the route, the body carries the application attributes
+    pub fn with_target(&self, name: String, tenant: String) -> Self {
+        Self {
+            name: Some(name),
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            ..self.clone()
+        }
+    }
+
+    // the archived applications are only read when included
+    pub fn with_include_archived(&self, include: bool) -> Self {
+        Self {
+            include_archived: Some(include).filter(|i| *i),
+            ..self.clone()
+        }
+    }
+
+    pub fn include_archived(&self) -> bool {
+        self.include_archived.unwrap_or(false)
+    }
 }
 
 impl RequestValidator for ApplicationRequest {
@@ -0,0 +1,187 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, ServiceVersionRequest, TenantRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult, validate_lock_override};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct ApprovalRequest {
+    service_version: Option<ServiceVersionRequest>,
+    environment: Option<String>,
+    approver: Option<String>,
+    comment: Option<String>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+}
+
+impl ApprovalRequest {
+    pub fn new(service_version: Option<ServiceVersionRequest>, environment: Option<String>, approver: Option<String>, comment: Option<String>) -> Self {
+        Self {
+            service_version,
+            environment,
+            approver,
+            comment,
+            override_lock: None,
+            justification: None,
+        }
+    }
+
+    pub fn service_version(&self) -> Option<&ServiceVersionRequest> {
+        self.service_version.as_ref()
+    }
+
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    pub fn approver(&self) -> Option<&String> {
+        self.approver.as_ref()
+    }
+
+    pub fn comment(&self) -> Option<&String> {
+        self.comment.as_ref()
+    }
+
+    // who approves while the environment is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.approver.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    // the release comes from the route, the body only says who decides and why
+    pub fn with_target(&self, service_version: ServiceVersionRequest, environment: String) -> Self {
+        Self {
+            service_version: Some(service_version),
+            environment: Some(environment),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for ApprovalRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.service_version.as_ref(),
+            self.environment.as_ref(),
+            self.approver.as_ref()
+        ) {
+            (Some(svr), Some(_), Some(_)) => svr.is_valid_get()
+                .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.approver.as_ref())),
+            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service version, environment and approver are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.service_version.as_ref(),
+            self.environment.as_ref()
+        ) {
+            (Some(svr), Some(_)) => svr.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service version and environment are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const APPROVAL_REQUEST_TYPE_INFO: &str = "ApprovalRequest";
+
+impl TypeInfo for ApprovalRequest {
+    fn type_name() -> &'static str {
+        APPROVAL_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        APPROVAL_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for ApprovalRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: ApprovalRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing approval request: {}", e))?;
+        Ok(res)
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct ApprovalPolicyRequest {
+    tenant: Option<TenantRequest>,
+    environments: Option<Vec<String>>,
+}
+
+impl ApprovalPolicyRequest {
+    pub fn new(tenant: Option<TenantRequest>, environments: Option<Vec<String>>) -> Self {
+        Self {
+            tenant,
+            environments,
+        }
+    }
+
+    pub fn tenant(&self) -> Option<&TenantRequest> {
+        self.tenant.as_ref()
+    }
+
+    // the environments of the tenant whose releases have to be approved, the others are released on promotion
+    pub fn environments(&self) -> Option<&Vec<String>> {
+        self.environments.as_ref()
+    }
+
+    // the tenant comes from the route, the body only carries the environments
+    pub fn with_tenant(&self, tenant: String) -> Self {
+        Self {
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            environments: self.environments.clone(),
+        }
+    }
+}
+
+impl RequestValidator for ApprovalPolicyRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.tenant.as_ref(),
+            self.environments.as_ref()
+        ) {
+            (Some(tenant), Some(_)) => tenant.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} tenant and environments are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.tenant.as_ref() {
+            Some(tenant) => tenant.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} tenant is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const APPROVAL_POLICY_REQUEST_TYPE_INFO: &str = "ApprovalPolicyRequest";
+
+impl TypeInfo for ApprovalPolicyRequest {
+    fn type_name() -> &'static str {
+        APPROVAL_POLICY_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        APPROVAL_POLICY_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for ApprovalPolicyRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: ApprovalPolicyRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing approval policy request: {}", e))?;
+        Ok(res)
+    }
+}
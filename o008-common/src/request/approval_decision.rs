@@ -0,0 +1,30 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema, Clone, Copy)]
+pub enum ApprovalDecision {
+    Approved,
+    Rejected
+}
+
+impl Display for ApprovalDecision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApprovalDecision::Approved => write!(f, "Approved"),
+            ApprovalDecision::Rejected => write!(f, "Rejected"),
+        }
+    }
+}
+
+impl FromStr for ApprovalDecision {
+    type Err = ();
+    fn from_str(input: &str) -> Result<ApprovalDecision, Self::Err> {
+        match input {
+            "Approved"  => Ok(Self::Approved),
+            "Rejected"  => Ok(Self::Rejected),
+            _      => Err(()),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct ArchiveRequest {
+    archived_by: Option<String>,
+}
+
+impl ArchiveRequest {
+    pub fn new(archived_by: Option<String>) -> Self {
+        Self {
+            archived_by,
+        }
+    }
+
+    pub fn archived_by(&self) -> Option<&String> {
+        self.archived_by.as_ref()
+    }
+
+    // the archiving user once the request is valid
+    pub fn valid_archived_by(&self) -> Result<&str, RequestValidatorError> {
+        match self.archived_by.as_deref() {
+            Some(archived_by) if !archived_by.trim().is_empty() => Ok(archived_by),
+            _ => Err(RequestValidatorError::MissingAttribute(format!("{} archived by is mandatory", self.type_of())))
+        }
+    }
+}
+
+impl RequestValidator for ArchiveRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.valid_archived_by().map(|_| ())
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const ARCHIVE_REQUEST_TYPE_INFO: &str = "ArchiveRequest";
+
+impl TypeInfo for ArchiveRequest {
+    fn type_name() -> &'static str {
+        ARCHIVE_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        ARCHIVE_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for ArchiveRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: ArchiveRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing archive request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::{RequestValidator, ServiceVersionRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct BuildRequest {
+    id: Option<Uuid>,
+    service_version: Option<ServiceVersionRequest>,
+}
+
+impl BuildRequest {
+    pub fn new(id: Option<Uuid>, service_version: Option<ServiceVersionRequest>) -> Self {
+        Self {
+            id,
+            service_version,
+        }
+    }
+
+    pub fn build_get_request(id: Uuid, version: String, service: String, application: String, tenant: String) -> Self {
+        Self {
+            id: Some(id),
+            service_version: Some(ServiceVersionRequest::build_get_request(version, service, application, tenant)),
+        }
+    }
+
+    pub fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Option<ServiceVersionRequest> {
+        self.service_version.clone()
+    }
+}
+
+impl RequestValidator for BuildRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match self.service_version.as_ref() {
+            Some(svr) => svr.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} service version is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.id.as_ref(),
+            self.service_version.as_ref()
+        ) {
+            (Some(_), Some(svr)) => svr.is_valid_get(),
+            (Some(_), None) => Ok(()),
+            (None, _) => Err(RequestValidatorError::MissingAttribute(format!("{} id is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        match (
+            self.id.as_ref(),
+            self.service_version.as_ref()
+        ) {
+            (None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one attribute is mandatory", self.type_of()))),
+            (_, Some(svr)) => svr.is_valid_get(),
+            (_, None) => Ok(())
+        }
+    }
+}
+
+const BUILD_REQUEST_TYPE_INFO: &str = "BuildRequest";
+
+impl TypeInfo for BuildRequest {
+    fn type_name() -> &'static str {
+        BUILD_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        BUILD_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for BuildRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: BuildRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing build request: {}", e))?;
+        Ok(res)
+    }
+}
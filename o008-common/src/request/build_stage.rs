@@ -0,0 +1,86 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::{BuildRequest, RequestValidator, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct BuildStageRequest {
+    build: Option<BuildRequest>,
+    stage: Option<i32>,
+}
+
+impl BuildStageRequest {
+    pub fn new(build: Option<BuildRequest>, stage: Option<i32>) -> Self {
+        Self {
+            build,
+            stage,
+        }
+    }
+
+    pub fn build_get_request(stage: i32, build: Uuid, version: String, service: String, application: String, tenant: String) -> Self {
+        Self {
+            build: Some(BuildRequest::build_get_request(build, version, service, application, tenant)),
+            stage: Some(stage),
+        }
+    }
+
+    pub fn build(&self) -> Option<BuildRequest> {
+        self.build.clone()
+    }
+
+    pub fn stage(&self) -> Option<i32> {
+        self.stage
+    }
+}
+
+impl RequestValidator for BuildStageRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.build.as_ref(),
+            self.stage
+        ) {
+            (Some(build), Some(_)) => build.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} build and stage are mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        match (
+            self.build.as_ref(),
+            self.stage
+        ) {
+            (None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one attribute is mandatory", self.type_of()))),
+            (Some(build), _) => build.is_valid_get(),
+            (None, _) => Ok(())
+        }
+    }
+}
+
+const BUILD_STAGE_REQUEST_TYPE_INFO: &str = "BuildStageRequest";
+
+impl TypeInfo for BuildStageRequest {
+    fn type_name() -> &'static str {
+        BUILD_STAGE_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        BUILD_STAGE_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for BuildStageRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: BuildStageRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing build stage request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema, Clone, Copy)]
+pub enum BuildStatus {
+    Queued,
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cached
+}
+
+impl BuildStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(self, BuildStatus::Succeeded | BuildStatus::Failed | BuildStatus::Cached)
+    }
+}
+
+impl Display for BuildStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildStatus::Queued => write!(f, "Queued"),
+            BuildStatus::Pending => write!(f, "Pending"),
+            BuildStatus::Running => write!(f, "Running"),
+            BuildStatus::Succeeded => write!(f, "Succeeded"),
+            BuildStatus::Failed => write!(f, "Failed"),
+            BuildStatus::Cached => write!(f, "Cached"),
+        }
+    }
+}
+
+impl FromStr for BuildStatus {
+    type Err = ();
+    fn from_str(input: &str) -> Result<BuildStatus, Self::Err> {
+        match input {
+            "Queued"  => Ok(Self::Queued),
+            "Pending"  => Ok(Self::Pending),
+            "Running"  => Ok(Self::Running),
+            "Succeeded"  => Ok(Self::Succeeded),
+            "Failed"  => Ok(Self::Failed),
+            "Cached"  => Ok(Self::Cached),
+            _      => Err(()),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, ServiceVersionRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct BuildTriggerRequest {
+    service_version: Option<ServiceVersionRequest>,
+    force: Option<bool>,
+}
+
+impl BuildTriggerRequest {
+    pub fn new(service_version: Option<ServiceVersionRequest>, force: Option<bool>) -> Self {
+        Self {
+            service_version,
+            force,
+        }
+    }
+
+    pub fn build_get_request(version: String, service: String, application: String, tenant: String, force: Option<bool>) -> Self {
+        Self {
+            service_version: Some(ServiceVersionRequest::build_get_request(version, service, application, tenant)),
+            force,
+        }
+    }
+
+    pub fn service_version(&self) -> Option<ServiceVersionRequest> {
+        self.service_version.clone()
+    }
+
+    pub fn force(&self) -> bool {
+        self.force.unwrap_or(false)
+    }
+}
+
+impl RequestValidator for BuildTriggerRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.service_version.as_ref() {
+            Some(svr) => svr.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} service version is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+}
+
+const BUILD_TRIGGER_REQUEST_TYPE_INFO: &str = "BuildTriggerRequest";
+
+impl TypeInfo for BuildTriggerRequest {
+    fn type_name() -> &'static str {
+        BUILD_TRIGGER_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        BUILD_TRIGGER_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for BuildTriggerRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: BuildTriggerRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing build trigger request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -1,8 +1,10 @@
+use std::path::{Component, Path};
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use crate::request::{RequestValidator, RequestValidatorError, RequestValidatorResult};
 use utoipa::ToSchema;
-use crate::TypeInfo;
+use crate::{BuilderStage, TypeInfo};
+use crate::template::validate_command;
 
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
@@ -10,6 +12,9 @@ pub struct BuilderRequest {
     pub name: Option<String>,
     pub active: Option<bool>,
     pub build_command: Option<String>,
+    pub stages: Option<Vec<BuilderStage>>,
+    pub timeout: Option<i32>,
+    pub artifacts: Option<Vec<String>>,
 }
 
 impl BuilderRequest {
@@ -21,8 +26,77 @@ impl BuilderRequest {
         *self.active.as_ref().unwrap()
     }
 
-    pub fn build_command(&self) -> &str {
-        self.build_command.as_ref().unwrap().as_str()
+    pub fn build_command(&self) -> Option<&str> {
+        self.build_command.as_deref()
+    }
+
+    // a single build_command is shorthand for a one stage builder
+    pub fn stages(&self) -> Vec<BuilderStage> {
+        match (self.stages.as_ref(), self.build_command.as_ref()) {
+            (Some(stages), _) => stages.clone(),
+            (None, Some(command)) => vec![BuilderStage::single(command)],
+            (None, None) => Vec::new()
+        }
+    }
+
+    pub fn is_valid_stages(&self) -> RequestValidatorResult {
+        let stages = self.stages();
+        if stages.is_empty() {
+            return Err(RequestValidatorError::MissingAttribute(format!("{} at least one stage is mandatory", self.type_of())))
+        }
+        for (i, stage) in stages.iter().enumerate() {
+            if stage.name().trim().is_empty() || stage.command().trim().is_empty() {
+                return Err(RequestValidatorError::MissingAttribute(format!("{} stage {} name and command are mandatory", self.type_of(), i + 1)))
+            }
+            if stages[..i].iter().any(|s| s.name() == stage.name()) {
+                return Err(RequestValidatorError::InvalidFormat(format!("{} stage name '{}' is duplicated", self.type_of(), stage.name())))
+            }
+            if let Err(e) = validate_command(stage.command()) {
+                return Err(RequestValidatorError::InvalidFormat(format!("{} stage {} command: {}", self.type_of(), stage.name(), e)))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Option<i32> {
+        self.timeout
+    }
+
+    pub fn artifacts(&self) -> Vec<String> {
+        self.artifacts.clone().unwrap_or_default()
+    }
+
+    // artifacts are files of the build workspace, so their paths have to stay inside it
+    pub fn is_valid_artifacts(&self) -> RequestValidatorResult {
+        let artifacts = self.artifacts();
+        for (i, artifact) in artifacts.iter().enumerate() {
+            let path = Path::new(artifact);
+            if artifact.trim().is_empty() {
+                return Err(RequestValidatorError::MissingAttribute(format!("{} artifact {} path is mandatory", self.type_of(), i + 1)))
+            }
+            if path.is_absolute() || path.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+                return Err(RequestValidatorError::InvalidFormat(format!("{} artifact '{}' must be a path relative to the build workspace", self.type_of(), artifact)))
+            }
+            if artifacts[..i].contains(artifact) {
+                return Err(RequestValidatorError::InvalidFormat(format!("{} artifact '{}' is duplicated", self.type_of(), artifact)))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build_get_request(name: String) -> Self {
+        Self {
+            name: Some(name),
+            ..Default::default()
+        }
+    }
+
+    // the name comes from the route, the body carries the builder definition
+    pub fn with_name(&self, name: String) -> Self {
+        Self {
+            name: Some(name),
+            ..self.clone()
+        }
     }
 }
 
@@ -31,10 +105,15 @@ impl RequestValidator for BuilderRequest {
         match (
             self.name.as_ref(),
             self.active,
-            self.build_command.as_ref()
+            self.build_command.as_ref(),
+            self.stages.as_ref()
         ) {
-            (Some(_), Some(_), Some(_)) => Ok(()),
-            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} all attributes are mandatory", self.type_of()))),
+            (Some(_), Some(_), Some(_), Some(_)) => Err(RequestValidatorError::InvalidFormat(format!("{} build_command and stages are mutually exclusive", self.type_of()))),
+            (Some(_), Some(_), Some(_), None) | (Some(_), Some(_), None, Some(_)) => match self.timeout {
+                Some(t) if t <= 0 => Err(RequestValidatorError::InvalidFormat(format!("{} timeout must be a positive number of seconds", self.type_of()))),
+                _ => self.is_valid_stages().and_then(|_| self.is_valid_artifacts())
+            },
+            (_, _, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} name, active and build_command or stages attributes are mandatory", self.type_of()))),
         }
     }
 
@@ -50,10 +129,13 @@ impl RequestValidator for BuilderRequest {
         match (
             self.name.as_ref(),
             self.active,
-            self.build_command.as_ref()
+            self.build_command.as_ref(),
+            self.stages.as_ref(),
+            self.timeout,
+            self.artifacts.as_ref()
         ) {
-            (None, None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one attribute is mandatory", self.type_of()))),
-            (_, _, _) => Ok(())
+            (None, None, None, None, None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one attribute is mandatory", self.type_of()))),
+            (_, _, _, _, _, _) => Ok(())
         }
     }
 }
@@ -79,3 +161,79 @@ impl FromStr for BuilderRequest {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> BuilderRequest {
+        json.parse().unwrap()
+    }
+
+    fn stages(stages: &str) -> BuilderRequest {
+        request(&format!(r#"{{"name": "sh", "active": true, "stages": {}}}"#, stages))
+    }
+
+    #[test]
+    fn stages_accept_a_pipeline() {
+        assert!(stages(r#"[{"name": "build", "command": "make {{version}}"}, {"name": "test", "command": "make test"}]"#).is_valid_stages().is_ok());
+    }
+
+    #[test]
+    fn stages_from_a_build_command() {
+        let builder = request(r#"{"name": "sh", "active": true, "build_command": "make"}"#);
+        assert_eq!(builder.stages().len(), 1);
+        assert!(builder.is_valid_stages().is_ok());
+    }
+
+    #[test]
+    fn stages_are_mandatory() {
+        assert!(matches!(stages("[]").is_valid_stages(), Err(RequestValidatorError::MissingAttribute(_))));
+        assert!(matches!(request(r#"{"name": "sh", "active": true}"#).is_valid_stages(), Err(RequestValidatorError::MissingAttribute(_))));
+    }
+
+    #[test]
+    fn stages_need_a_name_and_a_command() {
+        assert!(matches!(stages(r#"[{"name": " ", "command": "make"}]"#).is_valid_stages(), Err(RequestValidatorError::MissingAttribute(_))));
+        assert!(matches!(stages(r#"[{"name": "build", "command": ""}]"#).is_valid_stages(), Err(RequestValidatorError::MissingAttribute(_))));
+    }
+
+    #[test]
+    fn stages_reject_duplicated_names() {
+        let builder = stages(r#"[{"name": "build", "command": "make"}, {"name": "build", "command": "make test"}]"#);
+        assert!(matches!(builder.is_valid_stages(), Err(RequestValidatorError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn stages_reject_unknown_variables() {
+        assert!(matches!(stages(r#"[{"name": "build", "command": "make {{cluster}}"}]"#).is_valid_stages(), Err(RequestValidatorError::InvalidFormat(_))));
+    }
+
+    fn artifacts(artifacts: &str) -> BuilderRequest {
+        request(&format!(r#"{{"name": "sh", "active": true, "build_command": "make", "artifacts": {}}}"#, artifacts))
+    }
+
+    #[test]
+    fn artifacts_accept_workspace_paths() {
+        assert!(artifacts(r#"["target/app", "./dist/app.tar.gz"]"#).is_valid_artifacts().is_ok());
+        assert!(request(r#"{"name": "sh", "active": true, "build_command": "make"}"#).is_valid_artifacts().is_ok());
+    }
+
+    #[test]
+    fn artifacts_need_a_path() {
+        assert!(matches!(artifacts(r#"["target/app", " "]"#).is_valid_artifacts(), Err(RequestValidatorError::MissingAttribute(_))));
+    }
+
+    #[test]
+    fn artifacts_stay_in_the_workspace() {
+        for path in ["/etc/passwd", "../app", "target/../../app"] {
+            let builder = artifacts(&format!(r#"["{}"]"#, path));
+            assert!(matches!(builder.is_valid_artifacts(), Err(RequestValidatorError::InvalidFormat(_))), "{}", path);
+        }
+    }
+
+    #[test]
+    fn artifacts_reject_duplicated_paths() {
+        assert!(matches!(artifacts(r#"["target/app", "target/app"]"#).is_valid_artifacts(), Err(RequestValidatorError::InvalidFormat(_))));
+    }
+}
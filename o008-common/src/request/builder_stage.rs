@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+pub const DEFAULT_BUILDER_STAGE_NAME: &str = "build";
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct BuilderStage {
+    name: String,
+    description: Option<String>,
+    command: String,
+    #[serde(default)]
+    continue_on_error: bool,
+    #[schema(value_type = Option<Object>)]
+    stage_meta: Option<Value>,
+}
+
+impl BuilderStage {
+    pub fn new(name: &str, description: Option<&str>, command: &str, continue_on_error: bool, stage_meta: Option<Value>) -> Self {
+        Self {
+            name: String::from(name),
+            description: description.map(String::from),
+            command: String::from(command),
+            continue_on_error,
+            stage_meta,
+        }
+    }
+
+    pub fn single(command: &str) -> Self {
+        Self::new(DEFAULT_BUILDER_STAGE_NAME, None, command, false, None)
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn command(&self) -> &str {
+        self.command.as_str()
+    }
+
+    pub fn continue_on_error(&self) -> bool {
+        self.continue_on_error
+    }
+
+    pub fn stage_meta(&self) -> Option<&Value> {
+        self.stage_meta.as_ref()
+    }
+}
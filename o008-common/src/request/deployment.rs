@@ -0,0 +1,376 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, ServiceRequest, ServiceVersionRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult, validate_lock_override};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct DeploymentRequest {
+    service_version: Option<ServiceVersionRequest>,
+    environment: Option<String>,
+    deployed_by: Option<String>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+}
+
+impl DeploymentRequest {
+    pub fn new(service_version: Option<ServiceVersionRequest>, environment: Option<String>, deployed_by: Option<String>) -> Self {
+        Self {
+            service_version,
+            environment,
+            deployed_by,
+            override_lock: None,
+            justification: None,
+        }
+    }
+
+    pub fn service_version(&self) -> Option<&ServiceVersionRequest> {
+        self.service_version.as_ref()
+    }
+
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    pub fn deployed_by(&self) -> Option<&String> {
+        self.deployed_by.as_ref()
+    }
+
+    // who deploys while the environment is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.deployed_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    // the service version and the environment come from the route, the body only says who deploys
+    pub fn with_target(&self, service_version: ServiceVersionRequest, environment: String) -> Self {
+        Self {
+            service_version: Some(service_version),
+            environment: Some(environment),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for DeploymentRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.service_version.as_ref(),
+            self.environment.as_ref(),
+            self.deployed_by.as_ref()
+        ) {
+            (Some(svr), Some(_), Some(_)) => svr.is_valid_get()
+                .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.deployed_by.as_ref())),
+            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} all attributes are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.service_version.as_ref(),
+            self.environment.as_ref()
+        ) {
+            (Some(svr), Some(_)) => svr.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service version and environment are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const DEPLOYMENT_REQUEST_TYPE_INFO: &str = "DeploymentRequest";
+
+impl TypeInfo for DeploymentRequest {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for DeploymentRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: DeploymentRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing deployment request: {}", e))?;
+        Ok(res)
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct DeploymentHistoryRequest {
+    service: Option<ServiceRequest>,
+    environment: Option<String>,
+}
+
+impl DeploymentHistoryRequest {
+    pub fn new(service: Option<ServiceRequest>, environment: Option<String>) -> Self {
+        Self {
+            service,
+            environment,
+        }
+    }
+
+    pub fn build_get_request(name: String, application: String, tenant: String, environment: Option<String>) -> Self {
+        Self {
+            service: Some(ServiceRequest::build_get_request(name, application, tenant)),
+            environment,
+        }
+    }
+
+    pub fn service(&self) -> Option<&ServiceRequest> {
+        self.service.as_ref()
+    }
+
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+}
+
+impl RequestValidator for DeploymentHistoryRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.service.as_ref() {
+            Some(srq) => srq.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} service is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+}
+
+const DEPLOYMENT_HISTORY_REQUEST_TYPE_INFO: &str = "DeploymentHistoryRequest";
+
+impl TypeInfo for DeploymentHistoryRequest {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_HISTORY_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_HISTORY_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for DeploymentHistoryRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: DeploymentHistoryRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing deployment history request: {}", e))?;
+        Ok(res)
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct DeploymentRollbackRequest {
+    service: Option<ServiceRequest>,
+    environment: Option<String>,
+    deployed_by: Option<String>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+}
+
+impl DeploymentRollbackRequest {
+    pub fn new(service: Option<ServiceRequest>, environment: Option<String>, deployed_by: Option<String>) -> Self {
+        Self {
+            service,
+            environment,
+            deployed_by,
+            override_lock: None,
+            justification: None,
+        }
+    }
+
+    pub fn service(&self) -> Option<&ServiceRequest> {
+        self.service.as_ref()
+    }
+
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    pub fn deployed_by(&self) -> Option<&String> {
+        self.deployed_by.as_ref()
+    }
+
+    // who rolls back while the environment is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.deployed_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    // the service and the environment come from the route, the body only says who rolls back
+    pub fn with_target(&self, service: ServiceRequest, environment: String) -> Self {
+        Self {
+            service: Some(service),
+            environment: Some(environment),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for DeploymentRollbackRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.service.as_ref(),
+            self.environment.as_ref(),
+            self.deployed_by.as_ref()
+        ) {
+            (Some(srq), Some(_), Some(_)) => srq.is_valid_get()
+                .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.deployed_by.as_ref())),
+            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} all attributes are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.service.as_ref(),
+            self.environment.as_ref()
+        ) {
+            (Some(srq), Some(_)) => srq.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service and environment are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const DEPLOYMENT_ROLLBACK_REQUEST_TYPE_INFO: &str = "DeploymentRollbackRequest";
+
+impl TypeInfo for DeploymentRollbackRequest {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_ROLLBACK_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_ROLLBACK_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for DeploymentRollbackRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: DeploymentRollbackRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing deployment rollback request: {}", e))?;
+        Ok(res)
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct DeploymentSlotRequest {
+    service: Option<ServiceRequest>,
+    environment: Option<String>,
+    slot: Option<String>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+    overridden_by: Option<String>,
+}
+
+impl DeploymentSlotRequest {
+    pub fn new(service: Option<ServiceRequest>, environment: Option<String>, slot: Option<String>) -> Self {
+        Self {
+            service,
+            environment,
+            slot,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+        }
+    }
+
+    pub fn build_get_request(name: String, application: String, tenant: String, environment: String, slot: Option<String>) -> Self {
+        Self {
+            service: Some(ServiceRequest::build_get_request(name, application, tenant)),
+            environment: Some(environment),
+            slot,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+        }
+    }
+
+    pub fn service(&self) -> Option<&ServiceRequest> {
+        self.service.as_ref()
+    }
+
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    // without a slot name the idle slot is targeted
+    pub fn slot(&self) -> Option<&String> {
+        self.slot.as_ref()
+    }
+
+    // who switches or retires a slot while the environment is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.overridden_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    pub fn with_lock_override(&self, override_lock: Option<bool>, justification: Option<String>, overridden_by: Option<String>) -> Self {
+        Self {
+            override_lock,
+            justification,
+            overridden_by,
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for DeploymentSlotRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.service.as_ref(),
+            self.environment.as_ref()
+        ) {
+            (Some(srq), Some(_)) => srq.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service and environment are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+            .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.overridden_by.as_ref()))
+    }
+}
+
+const DEPLOYMENT_SLOT_REQUEST_TYPE_INFO: &str = "DeploymentSlotRequest";
+
+impl TypeInfo for DeploymentSlotRequest {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_SLOT_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_SLOT_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for DeploymentSlotRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: DeploymentSlotRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing deployment slot request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,244 @@
+use std::str::FromStr;
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, TenantRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct DeploymentLockRequest {
+    tenant: Option<TenantRequest>,
+    environment: Option<String>,
+    locked_by: Option<String>,
+    reason: Option<String>,
+    expires_on: Option<DateTime<Utc>>,
+}
+
+impl DeploymentLockRequest {
+    pub fn new(tenant: Option<TenantRequest>, environment: Option<String>, locked_by: Option<String>, reason: Option<String>,
+               expires_on: Option<DateTime<Utc>>) -> Self {
+        Self {
+            tenant,
+            environment,
+            locked_by,
+            reason,
+            expires_on,
+        }
+    }
+
+    pub fn build_get_request(tenant: String, environment: Option<String>) -> Self {
+        Self {
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            environment,
+            ..Default::default()
+        }
+    }
+
+    pub fn tenant(&self) -> Option<&TenantRequest> {
+        self.tenant.as_ref()
+    }
+
+    // without an environment the lock applies to every environment of the tenant
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    pub fn locked_by(&self) -> Option<&String> {
+        self.locked_by.as_ref()
+    }
+
+    pub fn reason(&self) -> Option<&String> {
+        self.reason.as_ref()
+    }
+
+    pub fn expires_on(&self) -> Option<DateTime<Utc>> {
+        self.expires_on
+    }
+
+    // the tenant comes from the route, the body carries the lock attributes
+    pub fn with_tenant(&self, tenant: String) -> Self {
+        Self {
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for DeploymentLockRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.tenant.as_ref(),
+            self.locked_by.as_ref(),
+            self.reason.as_ref()
+        ) {
+            (Some(tenant), Some(_), Some(_)) => match self.expires_on {
+                Some(expires_on) if expires_on <= Utc::now() => Err(RequestValidatorError::InvalidFormat(format!("{} expires on {} is in the past", self.type_of(), expires_on))),
+                _ => tenant.is_valid_get()
+            },
+            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} tenant, locked by and reason are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.tenant.as_ref() {
+            Some(tenant) => tenant.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} tenant is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const DEPLOYMENT_LOCK_REQUEST_TYPE_INFO: &str = "DeploymentLockRequest";
+
+impl TypeInfo for DeploymentLockRequest {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_LOCK_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_LOCK_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for DeploymentLockRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: DeploymentLockRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing deployment lock request: {}", e))?;
+        Ok(res)
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct FreezeWindowRequest {
+    name: Option<String>,
+    tenant: Option<TenantRequest>,
+    environment: Option<String>,
+    days: Option<Vec<String>>,
+    /// Time of day the window starts, in UTC
+    #[schema(value_type = Option<String>, example = "18:00")]
+    starts_at: Option<NaiveTime>,
+    /// Time of day the window ends, in UTC, before the start when the window runs past midnight
+    #[schema(value_type = Option<String>, example = "08:00")]
+    ends_at: Option<NaiveTime>,
+    reason: Option<String>,
+}
+
+impl FreezeWindowRequest {
+    pub fn new(name: Option<String>, tenant: Option<TenantRequest>, environment: Option<String>, days: Option<Vec<String>>,
+               starts_at: Option<NaiveTime>, ends_at: Option<NaiveTime>, reason: Option<String>) -> Self {
+        Self {
+            name,
+            tenant,
+            environment,
+            days,
+            starts_at,
+            ends_at,
+            reason,
+        }
+    }
+
+    pub fn build_get_request(name: String, tenant: String) -> Self {
+        Self {
+            name: Some(name),
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            ..Default::default()
+        }
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn tenant(&self) -> Option<&TenantRequest> {
+        self.tenant.as_ref()
+    }
+
+    // without an environment the window freezes every environment of the tenant
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    // the week days of the window, every day when empty
+    pub fn days(&self) -> Vec<Weekday> {
+        self.days.as_ref()
+            .map(|days| days.iter().filter_map(|d| d.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn starts_at(&self) -> Option<NaiveTime> {
+        self.starts_at
+    }
+
+    pub fn ends_at(&self) -> Option<NaiveTime> {
+        self.ends_at
+    }
+
+    pub fn reason(&self) -> Option<&String> {
+        self.reason.as_ref()
+    }
+
+    // the tenant comes from the route, the body carries the window attributes
+    pub fn with_tenant(&self, tenant: String) -> Self {
+        Self {
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for FreezeWindowRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()?;
+        if let Some(day) = self.days.iter().flatten().find(|d| d.parse::<Weekday>().is_err()) {
+            return Err(RequestValidatorError::InvalidFormat(format!("{} day {} is not a week day", self.type_of(), day)))
+        }
+        match (self.starts_at, self.ends_at) {
+            (Some(s), Some(e)) if s == e => Err(RequestValidatorError::InvalidFormat(format!("{} window starts and ends at {}", self.type_of(), s))),
+            (Some(_), Some(_)) => Ok(()),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} starts at and ends at are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.name.as_ref(),
+            self.tenant.as_ref()
+        ) {
+            (Some(_), Some(tenant)) => tenant.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} name and tenant are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const FREEZE_WINDOW_REQUEST_TYPE_INFO: &str = "FreezeWindowRequest";
+
+impl TypeInfo for FreezeWindowRequest {
+    fn type_name() -> &'static str {
+        FREEZE_WINDOW_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        FREEZE_WINDOW_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for FreezeWindowRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: FreezeWindowRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing freeze window request: {}", e))?;
+        Ok(res)
+    }
+}
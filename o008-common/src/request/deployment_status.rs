@@ -0,0 +1,39 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema, Clone, Copy)]
+pub enum DeploymentStatus {
+    Running,
+    Succeeded,
+    Failed
+}
+
+impl DeploymentStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(self, DeploymentStatus::Succeeded | DeploymentStatus::Failed)
+    }
+}
+
+impl Display for DeploymentStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeploymentStatus::Running => write!(f, "Running"),
+            DeploymentStatus::Succeeded => write!(f, "Succeeded"),
+            DeploymentStatus::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+impl FromStr for DeploymentStatus {
+    type Err = ();
+    fn from_str(input: &str) -> Result<DeploymentStatus, Self::Err> {
+        match input {
+            "Running"  => Ok(Self::Running),
+            "Succeeded"  => Ok(Self::Succeeded),
+            "Failed"  => Ok(Self::Failed),
+            _      => Err(()),
+        }
+    }
+}
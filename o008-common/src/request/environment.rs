@@ -0,0 +1,120 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, TenantRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct EnvironmentRequest {
+    name: Option<String>,
+    tenant: Option<TenantRequest>,
+    position: Option<i32>,
+    soak_minutes: Option<i32>,
+    require_build: Option<bool>,
+    require_approval: Option<bool>,
+}
+
+impl EnvironmentRequest {
+    pub fn new(name: Option<String>, tenant: Option<TenantRequest>, position: Option<i32>, soak_minutes: Option<i32>, require_build: Option<bool>,
+               require_approval: Option<bool>) -> Self {
+        Self {
+            name,
+            tenant,
+            position,
+            soak_minutes,
+            require_build,
+            require_approval,
+        }
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn tenant(&self) -> Option<&TenantRequest> {
+        self.tenant.as_ref()
+    }
+
+    pub fn position(&self) -> Option<i32> {
+        self.position
+    }
+
+    pub fn soak_minutes(&self) -> Option<i32> {
+        self.soak_minutes
+    }
+
+    pub fn require_build(&self) -> bool {
+        self.require_build.unwrap_or(false)
+    }
+
+    pub fn require_approval(&self) -> bool {
+        self.require_approval.unwrap_or(false)
+    }
+
+    pub fn build_get_request(name: String, tenant: String) -> Self {
+        Self {
+            name: Some(name),
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            position: None,
+            soak_minutes: None,
+            require_build: None,
+            require_approval: None,
+        }
+    }
+
+    // the tenant comes from the route, the body only carries the environment attributes
+    pub fn with_tenant(&self, tenant: String) -> Self {
+        Self {
+            tenant: Some(TenantRequest::build_get_request(tenant)),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for EnvironmentRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()?;
+        match (self.position, self.soak_minutes) {
+            (Some(p), _) if p < 0 => Err(RequestValidatorError::InvalidFormat(format!("{} position {} can not be negative", self.type_of(), p))),
+            (_, Some(m)) if m < 0 => Err(RequestValidatorError::InvalidFormat(format!("{} soak minutes {} can not be negative", self.type_of(), m))),
+            (_, _) => Ok(())
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (
+            self.name.as_ref(),
+            self.tenant.as_ref()
+        ) {
+            (Some(_), Some(tenant)) => tenant.is_valid_get(),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} name and tenant are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const ENVIRONMENT_REQUEST_TYPE_INFO: &str = "EnvironmentRequest";
+
+impl TypeInfo for EnvironmentRequest {
+    fn type_name() -> &'static str {
+        ENVIRONMENT_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        ENVIRONMENT_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for EnvironmentRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: EnvironmentRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing environment request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::{ApplicationRequest, RequestValidator, ServiceRequest, SortOrder, TenantRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult};
+
+pub const LIST_DEFAULT_LIMIT: i64 = 50;
+pub const LIST_MAX_LIMIT: i64 = 500;
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct ListRequest {
+    tenant: Option<String>,
+    application: Option<String>,
+    service: Option<String>,
+    name: Option<String>,
+    active: Option<bool>,
+    after: Option<Uuid>,
+    limit: Option<i64>,
+    sort: Option<SortOrder>,
+    include_archived: Option<bool>,
+}
+
+impl ListRequest {
+    pub fn new(name: Option<String>, active: Option<bool>, after: Option<Uuid>, limit: Option<i64>, sort: Option<SortOrder>) -> Self {
+        Self {
+            name,
+            active,
+            after,
+            limit,
+            sort,
+            ..Default::default()
+        }
+    }
+
+    // the parents of the listed items come from the route
+    pub fn with_scope(&self, tenant: Option<String>, application: Option<String>, service: Option<String>) -> Self {
+        Self {
+            tenant,
+            application,
+            service,
+            ..self.clone()
+        }
+    }
+
+    pub fn tenant(&self) -> Option<TenantRequest> {
+        self.tenant.clone().map(TenantRequest::build_get_request)
+    }
+
+    pub fn application(&self) -> Option<ApplicationRequest> {
+        match (self.application.as_ref(), self.tenant.as_ref()) {
+            (Some(application), Some(tenant)) =>
+                Some(ApplicationRequest::build_get_request(application.clone(), tenant.clone()).with_include_archived(self.include_archived())),
+            (_, _) => None
+        }
+    }
+
+    pub fn service(&self) -> Option<ServiceRequest> {
+        match (self.service.as_ref(), self.application.as_ref(), self.tenant.as_ref()) {
+            (Some(service), Some(application), Some(tenant)) =>
+                Some(ServiceRequest::build_get_request(service.clone(), application.clone(), tenant.clone()).with_include_archived(self.include_archived())),
+            (_, _, _) => None
+        }
+    }
+
+    // only the items whose name starts with it
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn active(&self) -> Option<bool> {
+        self.active
+    }
+
+    // the id of the last item of the previous page
+    pub fn after(&self) -> Option<Uuid> {
+        self.after
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(LIST_DEFAULT_LIMIT)
+    }
+
+    pub fn sort(&self) -> SortOrder {
+        self.sort.unwrap_or_default()
+    }
+
+    pub fn with_include_archived(&self, include: bool) -> Self {
+        Self {
+            include_archived: Some(include).filter(|i| *i),
+            ..self.clone()
+        }
+    }
+
+    // the archived items and the items under archived parents are listed too
+    pub fn include_archived(&self) -> bool {
+        self.include_archived.unwrap_or(false)
+    }
+}
+
+impl RequestValidator for ListRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        Err(RequestValidatorError::InvalidFormat(format!("{} is a query", self.type_of())))
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match (self.tenant.as_ref(), self.application.as_ref(), self.service.as_ref()) {
+            (None, Some(_), _) | (_, None, Some(_)) =>
+                Err(RequestValidatorError::MissingAttribute(format!("{} the parents of application or service are mandatory", self.type_of()))),
+            (_, _, _) => match self.limit {
+                Some(l) if !(1..=LIST_MAX_LIMIT).contains(&l) =>
+                    Err(RequestValidatorError::InvalidFormat(format!("{} limit {} is not between 1 and {}", self.type_of(), l, LIST_MAX_LIMIT))),
+                _ => Ok(())
+            }
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const LIST_REQUEST_TYPE_INFO: &str = "ListRequest";
+
+impl TypeInfo for ListRequest {
+    fn type_name() -> &'static str {
+        LIST_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        LIST_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for ListRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: ListRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing list request: {}", e))?;
+        Ok(res)
+    }
+}
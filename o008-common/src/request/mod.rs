@@ -2,11 +2,29 @@ use std::fmt::{Display, Formatter};
 
 pub(crate) mod tenant;
 pub(crate) mod builder;
+pub(crate) mod builder_stage;
 pub(crate) mod application;
+pub(crate) mod environment;
 pub(crate) mod service;
 pub(crate) mod repo_reference;
 pub(crate) mod repo_reference_kind;
+pub(crate) mod repo_reference_resolve;
 pub(crate) mod service_version;
+pub(crate) mod build_status;
+pub(crate) mod deployment;
+pub(crate) mod deployment_status;
+pub(crate) mod deployment_lock;
+pub(crate) mod promotion;
+pub(crate) mod release_status;
+pub(crate) mod list;
+pub(crate) mod sort_order;
+pub(crate) mod build;
+pub(crate) mod build_trigger;
+pub(crate) mod build_stage;
+pub(crate) mod artifact;
+pub(crate) mod approval;
+pub(crate) mod approval_decision;
+pub(crate) mod archive;
 
 pub enum RequestValidatorError {
     MissingAttribute(String),
@@ -26,6 +44,15 @@ impl Display for RequestValidatorError {
 
 pub type RequestValidatorResult = Result<(), RequestValidatorError>;
 
+// a locked or frozen target is only changed with an explicit override, who overrides it and its justification
+pub(crate) fn validate_lock_override(type_of: &str, override_lock: Option<bool>, justification: Option<&String>, overridden_by: Option<&String>) -> RequestValidatorResult {
+    match (override_lock, justification, overridden_by) {
+        (Some(true), None, _) => Err(RequestValidatorError::MissingAttribute(format!("{} justification is mandatory to override a lock", type_of))),
+        (Some(true), _, None) => Err(RequestValidatorError::MissingAttribute(format!("{} overridden_by is mandatory to override a lock", type_of))),
+        (_, _, _) => Ok(())
+    }
+}
+
 pub trait RequestValidator {
     fn is_valid_create(&self) -> RequestValidatorResult;
     fn is_valid_get(&self) -> RequestValidatorResult;
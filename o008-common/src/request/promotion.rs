@@ -0,0 +1,99 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, ServiceVersionRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult, validate_lock_override};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct PromotionRequest {
+    service_version: Option<ServiceVersionRequest>,
+    environment: Option<String>,
+    promoted_by: Option<String>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+}
+
+impl PromotionRequest {
+    pub fn new(service_version: Option<ServiceVersionRequest>, environment: Option<String>, promoted_by: Option<String>) -> Self {
+        Self {
+            service_version,
+            environment,
+            promoted_by,
+            override_lock: None,
+            justification: None,
+        }
+    }
+
+    pub fn service_version(&self) -> Option<&ServiceVersionRequest> {
+        self.service_version.as_ref()
+    }
+
+    // without an environment the service version is promoted to the next one it is not released to yet
+    pub fn environment(&self) -> Option<&String> {
+        self.environment.as_ref()
+    }
+
+    pub fn promoted_by(&self) -> Option<&String> {
+        self.promoted_by.as_ref()
+    }
+
+    // who promotes while the environment is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.promoted_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    // the service version comes from the route, the body says who promotes and optionally where
+    pub fn with_target(&self, service_version: ServiceVersionRequest) -> Self {
+        Self {
+            service_version: Some(service_version),
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for PromotionRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        match (
+            self.service_version.as_ref(),
+            self.promoted_by.as_ref()
+        ) {
+            (Some(svr), Some(_)) => svr.is_valid_get()
+                .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.promoted_by.as_ref())),
+            (_, _) => Err(RequestValidatorError::MissingAttribute(format!("{} service version and promoted by are mandatory", self.type_of()))),
+        }
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.service_version.as_ref() {
+            Some(svr) => svr.is_valid_get(),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} service version is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_create()
+    }
+}
+
+const PROMOTION_REQUEST_TYPE_INFO: &str = "PromotionRequest";
+
+impl TypeInfo for PromotionRequest {
+    fn type_name() -> &'static str {
+        PROMOTION_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        PROMOTION_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for PromotionRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: PromotionRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing promotion request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema, Clone, Copy)]
+pub enum ReleaseStatus {
+    PendingApproval,
+    Released,
+    Rejected
+}
+
+impl Display for ReleaseStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStatus::PendingApproval => write!(f, "PendingApproval"),
+            ReleaseStatus::Released => write!(f, "Released"),
+            ReleaseStatus::Rejected => write!(f, "Rejected"),
+        }
+    }
+}
+
+impl FromStr for ReleaseStatus {
+    type Err = ();
+    fn from_str(input: &str) -> Result<ReleaseStatus, Self::Err> {
+        match input {
+            "PendingApproval"  => Ok(Self::PendingApproval),
+            "Released"  => Ok(Self::Released),
+            "Rejected"  => Ok(Self::Rejected),
+            _      => Err(()),
+        }
+    }
+}
@@ -1,11 +1,14 @@
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 use crate::{RepoReferenceKind, RequestValidator, TypeInfo};
 use crate::request::{RequestValidatorError, RequestValidatorResult};
 
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
 pub struct RepoReferenceRequest {
+    id: Option<Uuid>,
     repo: Option<String>,
     kind: Option<RepoReferenceKind>,
     reference: Option<String>,
@@ -14,6 +17,7 @@ pub struct RepoReferenceRequest {
 impl RepoReferenceRequest {
     pub fn new(repo: Option<String>, kind: Option<RepoReferenceKind>, reference: Option<String>) -> Self {
         Self {
+            id: None,
             repo,
             kind,
             reference,
@@ -22,12 +26,24 @@ impl RepoReferenceRequest {
 
     pub fn build_get_request(repo: &str, kind: RepoReferenceKind, reference: &str) -> Self {
         Self {
+            id: None,
             repo: Some(String::from(repo)),
             kind: Some(kind),
             reference: Some(String::from(reference)),
         }
     }
 
+    pub fn build_id_request(id: Uuid) -> Self {
+        Self {
+            id: Some(id),
+            ..Default::default()
+        }
+    }
+
+    pub fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
     pub fn repo(&self) -> Option<&String> {
         self.repo.as_ref()
     }
@@ -39,6 +55,14 @@ impl RepoReferenceRequest {
     pub fn reference(&self) -> Option<&String> {
         self.reference.as_ref()
     }
+
+    // repo and reference end up as git arguments, a leading dash would be read as an option
+    fn is_valid_arguments(&self) -> RequestValidatorResult {
+        match [self.repo.as_ref(), self.reference.as_ref()].into_iter().flatten().find(|v| v.starts_with('-')) {
+            Some(v) => Err(RequestValidatorError::InvalidFormat(format!("{} '{}' can not start with '-'", self.type_of(), v))),
+            None => Ok(())
+        }
+    }
 }
 
 impl RequestValidator for RepoReferenceRequest {
@@ -48,19 +72,21 @@ impl RequestValidator for RepoReferenceRequest {
             self.kind.as_ref(),
             self.reference.as_ref()
         ) {
-            (Some(_), Some(_), Some(_)) => Ok(()),
+            (Some(_), Some(_), Some(_)) => self.is_valid_arguments(),
             (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} all attributes are mandatory", self.type_of())))
         }
     }
 
     fn is_valid_get(&self) -> RequestValidatorResult {
         match (
+            self.id.as_ref(),
             self.repo.as_ref(),
             self.kind.as_ref(),
             self.reference.as_ref()
         ) {
-            (Some(_), Some(_), Some(_)) => Ok(()),
-            (_, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} repo, kind and reference are mandatory", self.type_of())))
+            (Some(_), _, _, _) => Ok(()),
+            (None, Some(_), Some(_), Some(_)) => self.is_valid_arguments(),
+            (None, _, _, _) => Err(RequestValidatorError::MissingAttribute(format!("{} id or repo, kind and reference are mandatory", self.type_of())))
         }
     }
 
@@ -71,7 +97,7 @@ impl RequestValidator for RepoReferenceRequest {
             self.reference.as_ref()
         ) {
             (None, None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one attribute is mandatory", self.type_of()))),
-            (_, _, _) => Ok(())
+            (_, _, _) => self.is_valid_arguments()
         }
     }
 }
@@ -87,3 +113,13 @@ impl TypeInfo for RepoReferenceRequest {
         REPO_REFERENCE_REQUEST_TYPE_INFO
     }
 }
+
+impl FromStr for RepoReferenceRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: RepoReferenceRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing repo reference request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::{RequestValidator, ServiceVersionRequest, TypeInfo};
+use crate::request::{RequestValidatorError, RequestValidatorResult, validate_lock_override};
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct RepoReferenceResolveRequest {
+    service_version: Option<ServiceVersionRequest>,
+    pin: Option<bool>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+    overridden_by: Option<String>,
+}
+
+impl RepoReferenceResolveRequest {
+    pub fn new(service_version: Option<ServiceVersionRequest>, pin: Option<bool>) -> Self {
+        Self {
+            service_version,
+            pin,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+        }
+    }
+
+    pub fn build_get_request(version: String, service: String, application: String, tenant: String, pin: Option<bool>) -> Self {
+        Self {
+            service_version: Some(ServiceVersionRequest::build_get_request(version, service, application, tenant)),
+            pin,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+        }
+    }
+
+    pub fn service_version(&self) -> Option<ServiceVersionRequest> {
+        self.service_version.clone()
+    }
+
+    pub fn pin(&self) -> bool {
+        self.pin.unwrap_or(false)
+    }
+
+    // who pins the service version while the tenant is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.overridden_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    pub fn with_lock_override(&self, override_lock: Option<bool>, justification: Option<String>, overridden_by: Option<String>) -> Self {
+        Self {
+            override_lock,
+            justification,
+            overridden_by,
+            ..self.clone()
+        }
+    }
+}
+
+impl RequestValidator for RepoReferenceResolveRequest {
+    fn is_valid_create(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+
+    fn is_valid_get(&self) -> RequestValidatorResult {
+        match self.service_version.as_ref() {
+            Some(svr) => svr.is_valid_get()
+                .and_then(|_| validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.overridden_by.as_ref())),
+            None => Err(RequestValidatorError::MissingAttribute(format!("{} service version is mandatory", self.type_of())))
+        }
+    }
+
+    fn is_valid_update(&self) -> RequestValidatorResult {
+        self.is_valid_get()
+    }
+}
+
+const REPO_REFERENCE_RESOLVE_REQUEST_TYPE_INFO: &str = "RepoReferenceResolveRequest";
+
+impl TypeInfo for RepoReferenceResolveRequest {
+    fn type_name() -> &'static str {
+        REPO_REFERENCE_RESOLVE_REQUEST_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        REPO_REFERENCE_RESOLVE_REQUEST_TYPE_INFO
+    }
+}
+
+impl FromStr for RepoReferenceResolveRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let res: RepoReferenceResolveRequest =
+            serde_json::from_str(s).map_err(|e| format!("error parsing repo reference resolve request: {}", e))?;
+        Ok(res)
+    }
+}
@@ -10,6 +10,7 @@ pub struct ServiceRequest {
     name: Option<String>,
     application: Option<ApplicationRequest>,
     default_repo: Option<String>,
+    include_archived: Option<bool>,
 }
 
 impl ServiceRequest {
@@ -18,6 +19,7 @@ impl ServiceRequest {
             name: n,
             application: app,
             default_repo: repo,
+            include_archived: None,
         }
     }
 
@@ -25,7 +27,8 @@ impl ServiceRequest {
         Self {
             name: Some(name),
             application: Some(ApplicationRequest::build_get_request(application, tenant)),
-            default_repo: None
+            default_repo: None,
+            include_archived: None,
         }
     }
 
@@ -52,6 +55,17 @@ impl ServiceRequest {
     pub fn set_default_repo(&mut self, default_repo: &str) {
         self.default_repo = Some(String::from(default_repo))
     }
+
+    pub fn with_include_archived(&self, include: bool) -> Self {
+        Self {
+            include_archived: Some(include).filter(|i| *i),
+            ..self.clone()
+        }
+    }
+
+    pub fn include_archived(&self) -> bool {
+        self.include_archived.unwrap_or(false)
+    }
 }
 
 impl RequestValidator for ServiceRequest {
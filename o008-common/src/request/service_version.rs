@@ -2,7 +2,7 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use crate::{BuilderRequest, RepoReferenceRequest, RequestValidator, ServiceRequest, TypeInfo};
-use crate::request::{RequestValidatorError, RequestValidatorResult};
+use crate::request::{RequestValidatorError, RequestValidatorResult, validate_lock_override};
 
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
@@ -10,7 +10,11 @@ pub struct ServiceVersionRequest {
     version: Option<String>,
     service: Option<ServiceRequest>,
     repo_ref: Option<RepoReferenceRequest>,
-    builder: Option<BuilderRequest>
+    builder: Option<BuilderRequest>,
+    override_lock: Option<bool>,
+    justification: Option<String>,
+    overridden_by: Option<String>,
+    include_archived: Option<bool>,
 }
 
 
@@ -20,16 +24,24 @@ impl ServiceVersionRequest {
             version,
             service,
             repo_ref,
-            builder
+            builder,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+            include_archived: None,
         }
     }
 
     pub fn build_get_request(version: String, service: String, application: String, tenant: String) -> Self {
         Self {
-            version: Some(String::from(version)),
+            version: Some(version),
             service: Some(ServiceRequest::build_get_request(service, application, tenant)),
             repo_ref: None,
             builder: None,
+            override_lock: None,
+            justification: None,
+            overridden_by: None,
+            include_archived: None,
         }
     }
 
@@ -48,6 +60,23 @@ impl ServiceVersionRequest {
     pub fn builder(&self) -> Option<BuilderRequest> {
         self.builder.clone()
     }
+
+    // who changes the service version while its tenant is locked or frozen and why
+    pub fn lock_override(&self) -> Option<(&String, &String)> {
+        self.overridden_by.as_ref().zip(self.justification.as_ref()).filter(|_| self.override_lock.unwrap_or(false))
+    }
+
+    // an archived version is hidden unless included, its service along with it
+    pub fn with_include_archived(&self, include: bool) -> Self {
+        Self {
+            include_archived: Some(include).filter(|i| *i),
+            ..self.clone()
+        }
+    }
+
+    pub fn include_archived(&self) -> bool {
+        self.include_archived.unwrap_or(false)
+    }
 }
 
 impl RequestValidator for ServiceVersionRequest {
@@ -60,7 +89,7 @@ impl RequestValidator for ServiceVersionRequest {
             ) {
             (Some(_), Some(srv), Some(repo_ref), Some(builder)) => {
                 match (srv.is_valid_get(), repo_ref.is_valid_get(), builder.is_valid_get()) {
-                    (Ok(()), Ok(()), Ok(())) => Ok(()),
+                    (Ok(()), Ok(()), Ok(())) => validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.overridden_by.as_ref()),
                     (Err(e), _, _) => Err(e),
                     (_, Err(e), _) => Err(e),
                     (_, _, Err(e)) => Err(e),
@@ -88,6 +117,8 @@ impl RequestValidator for ServiceVersionRequest {
             self.builder.as_ref()
         ) {
             (None, None, None, None) => Err(RequestValidatorError::MissingAttribute(format!("{} at least one is mandatory", self.type_of()))),
+            (_, _, _, _) if self.override_lock == Some(true) && (self.justification.is_none() || self.overridden_by.is_none()) =>
+                validate_lock_override(self.type_of(), self.override_lock, self.justification.as_ref(), self.overridden_by.as_ref()),
             (_, Some(srv), _, _) => srv.is_valid_get(),
             (_, _, Some(repo_ref), _) => repo_ref.is_valid_get(),
             (_, _, _, Some(builder)) => builder.is_valid_get(),
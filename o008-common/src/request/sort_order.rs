@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, ToSchema, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "asc"),
+            SortOrder::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = ();
+    fn from_str(input: &str) -> Result<SortOrder, Self::Err> {
+        match input {
+            "asc"  => Ok(Self::Asc),
+            "desc"  => Ok(Self::Desc),
+            _      => Err(()),
+        }
+    }
+}
@@ -33,6 +33,14 @@ impl TenantRequest {
             coexisting: None
         }
     }
+
+    // the name comes from the route, the body carries the tenant attributes
+    pub fn with_name(&self, name: String) -> Self {
+        Self {
+            name: Some(name),
+            ..self.clone()
+        }
+    }
 }
 
 impl RequestValidator for TenantRequest {
@@ -48,7 +56,7 @@ impl RequestValidator for TenantRequest {
     }
 
     fn is_valid_get(&self) -> RequestValidatorResult {
-        if let Some(_) = self.name.as_ref() {
+        if self.name.is_some() {
             Ok(())
         } else {
             Err(RequestValidatorError::MissingAttribute(format!("{} name attribute is mandatory", self.type_of())))
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+pub const COMMAND_VARIABLES: [&str; 7] = [
+    "service.name",
+    "application.name",
+    "tenant.name",
+    "version",
+    "repo",
+    "ref.kind",
+    "ref.reference",
+];
+
+pub fn validate_command(command: &str) -> Result<(), String> {
+    scan(command, |name| {
+        if COMMAND_VARIABLES.contains(&name) {
+            Ok(String::new())
+        } else {
+            Err(format!("unknown variable '{{{{{}}}}}', expected one of {}", name, COMMAND_VARIABLES.join(", ")))
+        }
+    }).map(|_| ())
+}
+
+// the rendered command runs through `sh -c`, every value is quoted so it is taken as a single word
+pub fn render_command(command: &str, variables: &HashMap<&str, String>) -> Result<String, String> {
+    scan(command, |name| match variables.get(name) {
+        Some(value) => Ok(shell_quote(value)),
+        None => Err(format!("unknown variable '{{{{{}}}}}'", name))
+    })
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// walk the `{{ name }}` placeholders of a command, replacing each one with the resolved value, `\{{` is a literal `{{`
+fn scan<F>(command: &str, mut resolve: F) -> Result<String, String>
+    where F: FnMut(&str) -> Result<String, String>
+{
+    let mut rendered = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(start) = rest.find("{{") {
+        if let Some(text) = rest[..start].strip_suffix('\\') {
+            rendered.push_str(text);
+            rendered.push_str("{{");
+            rest = &rest[start + 2..];
+            continue
+        }
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        match placeholder.find("}}") {
+            Some(end) => {
+                let name = placeholder[..end].trim();
+                if name.is_empty() {
+                    return Err(String::from("empty placeholder '{{}}'"))
+                }
+                rendered.push_str(resolve(name)?.as_str());
+                rest = &placeholder[end + 2..];
+            },
+            None => return Err(format!("unterminated placeholder '{}'", &rest[start..]))
+        }
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(value: &str) -> HashMap<&'static str, String> {
+        HashMap::from([("service.name", String::from(value))])
+    }
+
+    #[test]
+    fn render_quotes_values() {
+        let rendered = render_command("echo {{ service.name }}", &variables("cart")).unwrap();
+        assert_eq!(rendered, "echo 'cart'");
+    }
+
+    #[test]
+    fn render_quotes_shell_metacharacters() {
+        let value = "x'; touch /tmp/owned; `id` $(id) \"$HOME\" | &";
+        let rendered = render_command("printf %s {{service.name}}", &variables(value)).unwrap();
+        let output = std::process::Command::new("sh").arg("-c").arg(&rendered).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), value);
+    }
+
+    #[test]
+    fn validate_accepts_known_variables() {
+        assert!(validate_command("deploy {{ service.name }} {{version}} {{ref.kind}}:{{ref.reference}}").is_ok());
+        assert!(validate_command("deploy --all").is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_variables() {
+        let e = validate_command("deploy {{ service.name }} {{ cluster }}").unwrap_err();
+        assert!(e.starts_with("unknown variable '{{cluster}}'"), "{}", e);
+    }
+
+    #[test]
+    fn validate_rejects_unterminated_and_empty_placeholders() {
+        assert_eq!(validate_command("deploy {{ version").unwrap_err(), "unterminated placeholder '{{ version'");
+        assert_eq!(validate_command("deploy {{ version }} {{version").unwrap_err(), "unterminated placeholder '{{version'");
+        assert_eq!(validate_command("deploy {{ }}").unwrap_err(), "empty placeholder '{{}}'");
+    }
+
+    #[test]
+    fn scan_keeps_escaped_and_plain_braces() {
+        let rendered = render_command(r"echo \{{ service.name }} ${HOME} {a} }}", &variables("cart")).unwrap();
+        assert_eq!(rendered, "echo {{ service.name }} ${HOME} {a} }}");
+        assert!(validate_command(r"echo \{{ cluster }}").is_ok());
+    }
+
+    #[test]
+    fn render_replaces_repeated_variables() {
+        let rendered = render_command("{{service.name}}-{{ service.name }}", &variables("cart")).unwrap();
+        assert_eq!(rendered, "'cart'-'cart'");
+    }
+
+    #[test]
+    fn render_rejects_missing_variables() {
+        assert_eq!(render_command("echo {{ version }}", &variables("cart")).unwrap_err(), "unknown variable '{{version}}'");
+    }
+}
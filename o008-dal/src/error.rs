@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::convert::Infallible;
 use std::error::Error as StdError;
 
 
@@ -10,6 +11,7 @@ pub enum DalError {
     DataDelete(sqlx::Error),
     DataGenericError(sqlx::Error),
     InvalidKey(String),
+    Unsupported(String),
 }
 
 impl Display for DalError {
@@ -21,9 +23,17 @@ impl Display for DalError {
             DalError::DataDelete(e) => write!(f, "could not delete: {}", e),
             DalError::DataGenericError(e) => write!(f, "generic error: {}", e),
             DalError::InvalidKey(e) => write!(f, "specified key is not valid: {}", e),
+            DalError::Unsupported(e) => write!(f, "operation not supported: {}", e),
         }
     }
 }
 
 impl StdError for DalError {
 }
+
+// a typed key converts into itself without error
+impl From<Infallible> for DalError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
@@ -4,6 +4,7 @@ use sqlx::{Database, FromRow, Pool};
 use sqlx::database::HasArguments;
 use sqlx::query::{Query, QueryAs};
 use uuid::Uuid;
+use o008_common::SortOrder;
 
 mod error;
 pub mod pg;
@@ -13,6 +14,99 @@ pub use error::DalError;
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DalCount { pub count: i64 }
 
+// a row referencing the row to delete, by table name
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DalDependent {
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+}
+
+// keyset pagination on the time ordered v7 ids: the rows after the cursor id in the sort order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DalPage {
+    after: Option<Uuid>,
+    limit: i64,
+    sort: SortOrder,
+}
+
+impl DalPage {
+    pub fn new(after: Option<Uuid>, limit: i64, sort: SortOrder) -> Self {
+        Self {
+            after,
+            limit,
+            sort,
+        }
+    }
+
+    pub fn after(&self) -> Option<Uuid> {
+        self.after
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+
+    // the comparison of the ids with the cursor and the order of the ids
+    pub fn keyset(&self) -> (&'static str, &'static str) {
+        match self.sort {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        }
+    }
+}
+
+// the filter of a list: the row owning the listed ones and the optional filters on them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DalFilter {
+    owner: Option<Uuid>,
+    name: Option<String>,
+    active: Option<bool>,
+    include_archived: bool,
+}
+
+impl DalFilter {
+    pub fn new(owner: Option<Uuid>, name: Option<&str>) -> Self {
+        Self {
+            owner,
+            name: name.map(String::from),
+            active: None,
+            include_archived: false,
+        }
+    }
+
+    pub fn with_active(mut self, active: Option<bool>) -> Self {
+        self.active = active;
+        self
+    }
+
+    pub fn with_archived(mut self, include_archived: bool) -> Self {
+        self.include_archived = include_archived;
+        self
+    }
+
+    pub fn owner(&self) -> Option<Uuid> {
+        self.owner
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn active(&self) -> Option<bool> {
+        self.active
+    }
+
+    pub fn include_archived(&self) -> bool {
+        self.include_archived
+    }
+
+    // the owner of the rows, required by the lists scoped to their owner
+    pub fn required_owner(&self, owner: &str) -> Result<Uuid, DalError> {
+        self.owner.ok_or_else(|| DalError::InvalidKey(format!("the list needs the {}", owner)))
+    }
+}
+
 #[async_trait]
 pub trait DBPool<DB> where DB: Database {
     async fn new() -> Self;
@@ -32,17 +126,37 @@ pub trait QueryContext<DB>: DBPool<DB> + Sized
 pub trait CommandContext<DB>: DBPool<DB> + Sized
     where DB: Database {
     async fn execute<'q>(&self, query: Query<'q, DB, <DB as HasArguments<'q>>::Arguments>) -> Result<(), DalError>;
+    async fn execute_all<'q>(&self, queries: Vec<Query<'q, DB, <DB as HasArguments<'q>>::Arguments>>) -> Result<(), DalError>;
 }
 
 #[async_trait]
 pub trait DaoQuery<Q, DB>
     where Q: QueryContext<DB> + Sized,
           DB: Database  {
+    // the json form of a key is only a conversion into the typed key, a malformed one is an invalid key
+    type Key: TryFrom<serde_json::Value, Error = DalError> + Send;
+
     async fn query_ctx() -> Q {
         Q::new().await
     }
-    async fn read(key: serde_json::Value) -> Result<Box<Self>, DalError>;
-    async fn exists(key: serde_json::Value) -> bool;
+    async fn read(key: Self::Key) -> Result<Box<Self>, DalError>;
+    async fn exists(key: Self::Key) -> Result<bool, DalError>;
+    async fn list(_filter: DalFilter, _page: DalPage) -> Result<Vec<Self>, DalError> where Self: Sized + Send {
+        Err(DalError::Unsupported(String::from("no list")))
+    }
+}
+
+// a query of a dao: its typed key or the json form of the key
+pub trait IntoDaoKey<Key>: Send {
+    fn into_key(self) -> Result<Key, DalError>;
+}
+
+impl<K, Key> IntoDaoKey<Key> for K
+    where K: TryInto<Key> + Send,
+          DalError: From<<K as TryInto<Key>>::Error> {
+    fn into_key(self) -> Result<Key, DalError> {
+        self.try_into().map_err(DalError::from)
+    }
 }
 
 #[async_trait]
@@ -57,6 +171,17 @@ pub trait DaoCommand<C, DB>
     async fn delete(&self) -> Result<(), DalError>;
 }
 
+// the foreign keys do not cascade: the dependents are checked before a delete or removed with it
+#[async_trait]
+pub trait DaoDependent<C, DB>: DaoCommand<C, DB>
+    where C: CommandContext<DB> + Sized,
+          DB: Database  {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, DalError>;
+    async fn delete_cascade(&self) -> Result<(), DalError> {
+        Err(DalError::Unsupported(String::from("no cascade delete")))
+    }
+}
+
 fn gen_v7_uuid(id: Uuid) -> Uuid {
     if id.is_nil() {
         Uuid::now_v7()
@@ -1,10 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
-use crate::{CommandContext, DalError, DaoCommand, DaoQuery, QueryContext, DalCount, gen_v7_uuid};
-use crate::pg::{hard_check_key, PgDao, Tenant};
+use crate::{CommandContext, DalError, DaoCommand, DaoDependent, DaoQuery, QueryContext, DalCount, DalDependent, DalFilter, DalPage, gen_v7_uuid};
+use crate::pg::{delete_subtree, has_attribute, include_archived, key_attribute, key_str, key_uuid, PgDao, service_version_subtree, Tenant, TenantKey};
 
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -14,16 +15,20 @@ pub struct Application {
     tenant: Uuid,
     class_unit: String,
     functional_group: String,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl Application {
-    pub fn new(id: Uuid, name: &str, tenant: Uuid, class: &str, fq: &str) -> Self {
+    pub fn new(id: Uuid, name: &str, tenant: Uuid, class: &str, fq: &str, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id: gen_v7_uuid(id),
             name: String::from(name),
             tenant,
             class_unit: String::from(class),
             functional_group: String::from(fq),
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -46,62 +51,114 @@ impl Application {
     pub fn functional_group(&self) -> &str {
         &self.functional_group
     }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+}
+
+// an application is read by id or by its name in a tenant, an archived one only when included
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApplicationKey {
+    Id(Uuid),
+    Natural { name: String, tenant: TenantKey, include_archived: bool },
+}
+
+impl ApplicationKey {
+    // the parent of an archived row is read with the same visibility
+    pub fn with_archived(self, include: bool) -> Self {
+        match self {
+            Self::Natural { name, tenant, include_archived } =>
+                Self::Natural { name, tenant, include_archived: include_archived || include },
+            id => id,
+        }
+    }
+}
+
+impl TryFrom<Value> for ApplicationKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                name: key_str(&key, "name")?,
+                tenant: TenantKey::try_from(key_attribute(&key, "tenant")?.clone())?,
+                include_archived: include_archived(&key),
+            })
+        }
+    }
 }
 
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for Application {
-    async fn read(key: Value) -> Result<Box<Self>, DalError> {
-        match hard_check_key(&key, &["id"]) {
-            Ok(id_key) => {
-                let id= id_key.first().unwrap();
-                Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, Self>("SELECT id, name, tenant, class_unit, functional_group FROM application WHERE id=$1")
-                        .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-                ).await
-            },
-            Err(_) => match hard_check_key(&key, &["name", "tenant"]) {
-                Ok(name_tenant_key) => {
-                    let name = name_tenant_key.get(0).unwrap().as_str().unwrap();
-                    let tenant_qry = name_tenant_key.get(1).unwrap();
-                    if let Ok(tenant) = Tenant::read(tenant_qry.clone()).await {
-                        Self::query_ctx().await.fetch_one(
-                            sqlx::query_as::<_, Self>("SELECT id, name, tenant, class_unit, functional_group FROM application WHERE name=$1 AND tenant=$2")
-                                .bind(name)
-                                .bind(tenant.id())
-                        ).await
-                    } else {
-                        Err(DalError::DataNotFound(format!("tenant {}", tenant_qry)))
-                    }
-                },
-                Err(e) => Err(DalError::InvalidKey(format!("application dao read {}", e)))
+    type Key = ApplicationKey;
+
+    async fn read(key: ApplicationKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ApplicationKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, name, tenant, class_unit, functional_group, archived_at, archived_by FROM application WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ApplicationKey::Natural { name, tenant: tenant_key, include_archived } => {
+                match Tenant::read(tenant_key.clone()).await {
+                    Ok(tenant) => Self::query_ctx().await.fetch_one(
+                        sqlx::query_as::<_, Self>("SELECT id, name, tenant, class_unit, functional_group, archived_at, archived_by FROM application WHERE name=$1 AND tenant=$2 AND ($3::boolean IS TRUE OR archived_at IS NULL)")
+                            .bind(name)
+                            .bind(tenant.id())
+                            .bind(include_archived)
+                    ).await,
+                    Err(DalError::DataNotFound(_)) => Err(DalError::DataNotFound(format!("tenant {:?}", tenant_key))),
+                    Err(e) => Err(e)
+                }
             }
         }
     }
 
-    async fn exists(key: Value) -> bool {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            let r = Self::query_ctx().await.fetch_one(
-                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM application WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await;
-            r.unwrap().count > 0
-        } else if let Ok(name_tenant_key) = hard_check_key(&key, &["name", "tenant"]) {
-            let (name, tenant_qry) = (name_tenant_key.get(0).unwrap(), name_tenant_key.get(1).unwrap());
-            if let Ok(tenant) = Tenant::read(tenant_qry.clone()).await {
+    async fn exists(key: ApplicationKey) -> Result<bool, DalError> {
+        match key {
+            ApplicationKey::Id(id) => {
                 let r = Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) WHERE name=$1 AND tenant=$2")
-                        .bind(name.as_str().unwrap())
-                        .bind(tenant.id())
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM application WHERE id=$1")
+                        .bind(id)
                 ).await;
-                r.unwrap().count > 0
-            } else {
-                false
+                Ok(r?.count > 0)
+            },
+            ApplicationKey::Natural { name, tenant: tenant_key, .. } => {
+                match Tenant::read(tenant_key).await {
+                    Ok(tenant) => {
+                        let r = Self::query_ctx().await.fetch_one(
+                            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM application WHERE name=$1 AND tenant=$2")
+                                .bind(name)
+                                .bind(tenant.id())
+                        ).await;
+                        Ok(r?.count > 0)
+                    },
+                    Err(DalError::DataNotFound(_)) => Ok(false),
+                    Err(e) => Err(e)
+                }
             }
-        } else {
-            false
         }
     }
+
+    async fn list(filter: DalFilter, page: DalPage) -> Result<Vec<Self>, DalError> {
+        let tenant = filter.required_owner("tenant")?;
+        let (cursor, order) = page.keyset();
+        let query = format!("SELECT id, name, tenant, class_unit, functional_group, archived_at, archived_by FROM application WHERE tenant=$1 AND ($2::varchar IS NULL OR starts_with(name, $2)) AND ($3::uuid IS NULL OR id {} $3) AND ($5::boolean IS TRUE OR archived_at IS NULL) ORDER BY id {} LIMIT $4", cursor, order);
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>(&query)
+                .bind(tenant)
+                .bind(filter.name())
+                .bind(page.after())
+                .bind(page.limit())
+                .bind(filter.include_archived())
+        ).await
+    }
 }
 
 #[async_trait]
@@ -121,11 +178,13 @@ impl DaoCommand<PgDao, Postgres> for Application {
     async fn update(&self) -> Result<(), DalError> {
         let cx = Self::command_ctx().await;
         cx.execute(
-            sqlx::query("UPDATE application SET name=$1, tenant=$2, class_unit=$3, functional_group=$4 WHERE id=$5")
+            sqlx::query("UPDATE application SET name=$1, tenant=$2, class_unit=$3, functional_group=$4, archived_at=$5, archived_by=$6 WHERE id=$7")
                 .bind(self.name.as_str())
                 .bind(self.tenant)
                 .bind(self.class_unit.as_str())
                 .bind(self.functional_group.as_str())
+                .bind(self.archived_at)
+                .bind(self.archived_by.as_deref())
                 .bind(self.id)
         ).await
     }
@@ -138,3 +197,20 @@ impl DaoCommand<PgDao, Postgres> for Application {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for Application {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'service' AS kind, id, name FROM service WHERE application = $1")
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete_cascade(&self) -> Result<(), DalError> {
+        let mut deletes = service_version_subtree("SELECT sv.id FROM service_version sv JOIN service s ON s.id = sv.service WHERE s.application = $1");
+        deletes.push(String::from("DELETE FROM service WHERE application = $1"));
+        deletes.push(String::from("DELETE FROM application WHERE id = $1"));
+        delete_subtree(&deletes, self.id).await
+    }
+}
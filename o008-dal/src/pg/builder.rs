@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
-use crate::{QueryContext, error, CommandContext, DaoQuery, DaoCommand, DalCount, gen_v7_uuid};
-use crate::pg::{hard_check_key, PgDao, soft_check_key};
+use crate::{QueryContext, error, CommandContext, DaoQuery, DaoCommand, DaoDependent, DalCount, DalDependent, DalFilter, DalPage, gen_v7_uuid};
+use crate::pg::{has_attribute, key_str, key_uuid, PgDao};
 
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -12,16 +12,20 @@ pub struct Builder {
     id: Uuid,
     name: String,
     active: bool,
-    build_command: String,
+    stages: Value,
+    timeout: Option<i32>,
+    artifacts: Value,
 }
 
 impl Builder {
-    pub fn new(id: Uuid, name: &str, active: bool, build_command: &str) -> Self {
+    pub fn new(id: Uuid, name: &str, active: bool, stages: Value, timeout: Option<i32>, artifacts: Value) -> Self {
         Self {
             id: gen_v7_uuid(id),
             name: String::from(name),
             active,
-            build_command: String::from(build_command)
+            stages,
+            timeout,
+            artifacts
         }
     }
 
@@ -37,47 +41,79 @@ impl Builder {
         self.active
     }
 
-    pub fn build_command(&self) -> &str {
-        &self.build_command
+    pub fn stages(&self) -> &Value {
+        &self.stages
+    }
+
+    pub fn timeout(&self) -> Option<i32> {
+        self.timeout
+    }
+
+    pub fn artifacts(&self) -> &Value {
+        &self.artifacts
+    }
+}
+
+// a builder is read by id or by its unique name
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuilderKey {
+    Id(Uuid),
+    Natural { name: String },
+}
+
+impl TryFrom<Value> for BuilderKey {
+    type Error = error::DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural { name: key_str(&key, "name")? })
+        }
     }
 }
 
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for Builder {
-    async fn read(key: Value) -> Result<Box<Self>, error::DalError> {
-        let id_key = soft_check_key(&key, &["id"])?;
-        return if let Some(id) = id_key.first().unwrap() {
-            Self::query_ctx().await.fetch_one(
-                sqlx::query_as::<_, Self>("SELECT id, name, active, build_command FROM builder WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await
-        } else {
-            let name_key = hard_check_key(&key, &["name"])?;
-            let name = name_key.first().unwrap().as_str().unwrap();
-            Self::query_ctx().await.fetch_one(
-                sqlx::query_as::<_, Self>("SELECT  id, name, active, build_command FROM builder WHERE name=$1")
+    type Key = BuilderKey;
+
+    async fn read(key: BuilderKey) -> Result<Box<Self>, error::DalError> {
+        match key {
+            BuilderKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, name, active, stages, timeout, artifacts FROM builder WHERE id=$1")
+                    .bind(id)
+            ).await,
+            BuilderKey::Natural { name } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT  id, name, active, stages, timeout, artifacts FROM builder WHERE name=$1")
                     .bind(name)
             ).await
         }
     }
 
-    async fn exists(key: Value) -> bool {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            let r = Self::query_ctx().await.fetch_one(
+    async fn exists(key: BuilderKey) -> Result<bool, error::DalError> {
+        let r = match key {
+            BuilderKey::Id(id) => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM builder WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await;
-            return r.unwrap().count > 0
-        } else if let Ok(name_key) = hard_check_key(&key, &["name"]) {
-            let name = name_key.first().unwrap();
-            let r = Self::query_ctx().await.fetch_one(
+                    .bind(id)
+            ).await,
+            BuilderKey::Natural { name } => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM builder WHERE name=$1")
-                    .bind(name.as_str().unwrap())
-            ).await;
-            return r.unwrap().count > 0
-        }
-        false
+                    .bind(name)
+            ).await
+        };
+        Ok(r?.count > 0)
+    }
+
+    async fn list(filter: DalFilter, page: DalPage) -> Result<Vec<Self>, error::DalError> {
+        let (cursor, order) = page.keyset();
+        let query = format!("SELECT id, name, active, stages, timeout, artifacts FROM builder WHERE ($1::varchar IS NULL OR starts_with(name, $1)) AND ($2::boolean IS NULL OR active=$2) AND ($3::uuid IS NULL OR id {} $3) ORDER BY id {} LIMIT $4", cursor, order);
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>(&query)
+                .bind(filter.name())
+                .bind(filter.active())
+                .bind(page.after())
+                .bind(page.limit())
+        ).await
     }
 }
 
@@ -86,20 +122,24 @@ impl DaoCommand<PgDao, Postgres> for Builder {
     async fn insert(&self) -> Result<(), error::DalError> {
         let cx = Self::command_ctx().await;
         cx.execute(
-            sqlx::query("INSERT INTO builder (id, name, active, build_command) VALUES ($1, $2, $3, $4)")
+            sqlx::query("INSERT INTO builder (id, name, active, stages, timeout, artifacts) VALUES ($1, $2, $3, $4, $5, $6)")
                 .bind(self.id)
                 .bind(self.name.clone())
                 .bind(self.active)
-                .bind(self.build_command.clone())
+                .bind(&self.stages)
+                .bind(self.timeout)
+                .bind(&self.artifacts)
         ).await
     }
     async fn update(&self) -> Result<(), error::DalError> {
         let cx = Self::command_ctx().await;
         cx.execute(
-            sqlx::query("UPDATE builder SET name=$1, active=$2, build_command=$3 WHERE id=$4")
+            sqlx::query("UPDATE builder SET name=$1, active=$2, stages=$3, timeout=$4, artifacts=$5 WHERE id=$6")
                 .bind(self.name.clone())
                 .bind(self.active)
-                .bind(self.build_command.clone())
+                .bind(&self.stages)
+                .bind(self.timeout)
+                .bind(&self.artifacts)
                 .bind(self.id)
         ).await
     }
@@ -112,3 +152,13 @@ impl DaoCommand<PgDao, Postgres> for Builder {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for Builder {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, error::DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'service_version' AS kind, id, version AS name FROM service_version WHERE builder = $1")
+                .bind(self.id)
+        ).await
+    }
+}
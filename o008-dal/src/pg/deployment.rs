@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Deployment {
+    id: Uuid,
+    service_version: Uuid,
+    environment: Uuid,
+    status: String,
+    deployed_by: String,
+    previous_version: Option<Uuid>,
+    command: Option<String>,
+    error_reason: Option<String>,
+    logs_link: Option<String>,
+    started_on: DateTime<Utc>,
+    finished_on: Option<DateTime<Utc>>,
+    rollback_of: Option<Uuid>,
+    slot: Option<String>,
+}
+
+impl Deployment {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(id: Uuid, service_version: Uuid, environment: Uuid, status: &str, deployed_by: &str, previous_version: Option<Uuid>,
+               command: Option<&str>, error_reason: Option<&str>, logs_link: Option<&str>, started_on: DateTime<Utc>,
+               finished_on: Option<DateTime<Utc>>, rollback_of: Option<Uuid>, slot: Option<&str>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service_version,
+            environment,
+            status: String::from(status),
+            deployed_by: String::from(deployed_by),
+            previous_version,
+            command: command.map(String::from),
+            error_reason: error_reason.map(String::from),
+            logs_link: logs_link.map(String::from),
+            started_on,
+            finished_on,
+            rollback_of,
+            slot: slot.map(String::from),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn environment(&self) -> Uuid {
+        self.environment
+    }
+
+    pub fn status(&self) -> &str {
+        self.status.as_str()
+    }
+
+    pub fn deployed_by(&self) -> &str {
+        self.deployed_by.as_str()
+    }
+
+    pub fn previous_version(&self) -> Option<Uuid> {
+        self.previous_version
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn logs_link(&self) -> Option<&str> {
+        self.logs_link.as_deref()
+    }
+
+    pub fn started_on(&self) -> DateTime<Utc> {
+        self.started_on
+    }
+
+    pub fn finished_on(&self) -> Option<DateTime<Utc>> {
+        self.finished_on
+    }
+
+    pub fn rollback_of(&self) -> Option<Uuid> {
+        self.rollback_of
+    }
+
+    pub fn slot(&self) -> Option<&str> {
+        self.slot.as_deref()
+    }
+
+    // deployments of every version of a service to an environment, the latest first
+    pub async fn service_deployments(service: Uuid, environment: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT d.id, d.service_version, d.environment, d.status, d.deployed_by, d.previous_version, d.command, d.error_reason, d.logs_link, d.started_on, d.finished_on, d.rollback_of, d.slot FROM deployment d JOIN service_version sv ON sv.id=d.service_version WHERE sv.service=$1 AND d.environment=$2 ORDER BY d.id DESC")
+                .bind(service)
+                .bind(environment)
+        ).await
+    }
+
+    // the deployment running each service in each environment of a tenant: the active slot one, else the latest with the status
+    pub async fn running_deployments(tenant: Uuid, status: &str) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT DISTINCT ON (sv.service, d.environment) d.id, d.service_version, d.environment, d.status, d.deployed_by, d.previous_version, d.command, d.error_reason, d.logs_link, d.started_on, d.finished_on, d.rollback_of, d.slot FROM deployment d JOIN service_version sv ON sv.id=d.service_version JOIN environment e ON e.id=d.environment LEFT JOIN deployment_slot s ON s.service=sv.service AND s.environment=d.environment AND s.active WHERE e.tenant=$1 AND d.status=$2 AND (s.id IS NULL OR s.deployment=d.id) ORDER BY sv.service, d.environment, d.id DESC")
+                .bind(tenant)
+                .bind(status)
+        ).await
+    }
+}
+
+// a deployment is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for DeploymentKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for Deployment {
+    type Key = DeploymentKey;
+
+    async fn read(key: DeploymentKey) -> Result<Box<Self>, DalError> {
+        match key {
+            DeploymentKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, environment, status, deployed_by, previous_version, command, error_reason, logs_link, started_on, finished_on, rollback_of, slot FROM deployment WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: DeploymentKey) -> Result<bool, DalError> {
+        match key {
+            DeploymentKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM deployment WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for Deployment {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO deployment(id, service_version, environment, status, deployed_by, previous_version, command, error_reason, logs_link, started_on, finished_on, rollback_of, slot) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)")
+                .bind(self.id)
+                .bind(self.service_version)
+                .bind(self.environment)
+                .bind(self.status.as_str())
+                .bind(self.deployed_by.as_str())
+                .bind(self.previous_version)
+                .bind(self.command.as_deref())
+                .bind(self.error_reason.as_deref())
+                .bind(self.logs_link.as_deref())
+                .bind(self.started_on)
+                .bind(self.finished_on)
+                .bind(self.rollback_of)
+                .bind(self.slot.as_deref())
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE deployment SET service_version=$1, environment=$2, status=$3, deployed_by=$4, previous_version=$5, command=$6, error_reason=$7, logs_link=$8, started_on=$9, finished_on=$10, rollback_of=$11, slot=$12 WHERE id=$13")
+                .bind(self.service_version)
+                .bind(self.environment)
+                .bind(self.status.as_str())
+                .bind(self.deployed_by.as_str())
+                .bind(self.previous_version)
+                .bind(self.command.as_deref())
+                .bind(self.error_reason.as_deref())
+                .bind(self.logs_link.as_deref())
+                .bind(self.started_on)
+                .bind(self.finished_on)
+                .bind(self.rollback_of)
+                .bind(self.slot.as_deref())
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM deployment WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
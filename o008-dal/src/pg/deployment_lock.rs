@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeploymentLock {
+    id: Uuid,
+    tenant: Uuid,
+    environment: Option<Uuid>,
+    locked_by: String,
+    reason: String,
+    locked_on: DateTime<Utc>,
+    expires_on: Option<DateTime<Utc>>,
+}
+
+impl DeploymentLock {
+    pub fn new(id: Uuid, tenant: Uuid, environment: Option<Uuid>, locked_by: &str, reason: &str, locked_on: DateTime<Utc>, expires_on: Option<DateTime<Utc>>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            tenant,
+            environment,
+            locked_by: String::from(locked_by),
+            reason: String::from(reason),
+            locked_on,
+            expires_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn tenant(&self) -> Uuid {
+        self.tenant
+    }
+
+    pub fn environment(&self) -> Option<Uuid> {
+        self.environment
+    }
+
+    pub fn locked_by(&self) -> &str {
+        self.locked_by.as_str()
+    }
+
+    pub fn reason(&self) -> &str {
+        self.reason.as_str()
+    }
+
+    pub fn locked_on(&self) -> DateTime<Utc> {
+        self.locked_on
+    }
+
+    pub fn expires_on(&self) -> Option<DateTime<Utc>> {
+        self.expires_on
+    }
+
+    pub async fn tenant_locks(tenant: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, tenant, environment, locked_by, reason, locked_on, expires_on FROM deployment_lock WHERE tenant=$1 ORDER BY id")
+                .bind(tenant)
+        ).await
+    }
+}
+
+// a deployment lock is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentLockKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for DeploymentLockKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for DeploymentLock {
+    type Key = DeploymentLockKey;
+
+    async fn read(key: DeploymentLockKey) -> Result<Box<Self>, DalError> {
+        match key {
+            DeploymentLockKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, tenant, environment, locked_by, reason, locked_on, expires_on FROM deployment_lock WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: DeploymentLockKey) -> Result<bool, DalError> {
+        match key {
+            DeploymentLockKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM deployment_lock WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for DeploymentLock {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO deployment_lock(id, tenant, environment, locked_by, reason, locked_on, expires_on) VALUES ($1, $2, $3, $4, $5, $6, $7)")
+                .bind(self.id)
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.locked_by.as_str())
+                .bind(self.reason.as_str())
+                .bind(self.locked_on)
+                .bind(self.expires_on)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE deployment_lock SET tenant=$1, environment=$2, locked_by=$3, reason=$4, locked_on=$5, expires_on=$6 WHERE id=$7")
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.locked_by.as_str())
+                .bind(self.reason.as_str())
+                .bind(self.locked_on)
+                .bind(self.expires_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM deployment_lock WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
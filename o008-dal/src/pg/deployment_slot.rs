@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeploymentSlot {
+    id: Uuid,
+    service: Uuid,
+    environment: Uuid,
+    name: String,
+    service_version: Uuid,
+    deployment: Uuid,
+    active: bool,
+}
+
+impl DeploymentSlot {
+    pub fn new(id: Uuid, service: Uuid, environment: Uuid, name: &str, service_version: Uuid, deployment: Uuid, active: bool) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service,
+            environment,
+            name: String::from(name),
+            service_version,
+            deployment,
+            active,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service(&self) -> Uuid {
+        self.service
+    }
+
+    pub fn environment(&self) -> Uuid {
+        self.environment
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn deployment(&self) -> Uuid {
+        self.deployment
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub async fn service_slots(service: Uuid, environment: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service, environment, name, service_version, deployment, active FROM deployment_slot WHERE service=$1 AND environment=$2 ORDER BY name")
+                .bind(service)
+                .bind(environment)
+        ).await
+    }
+}
+
+// a deployment slot is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentSlotKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for DeploymentSlotKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for DeploymentSlot {
+    type Key = DeploymentSlotKey;
+
+    async fn read(key: DeploymentSlotKey) -> Result<Box<Self>, DalError> {
+        match key {
+            DeploymentSlotKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service, environment, name, service_version, deployment, active FROM deployment_slot WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: DeploymentSlotKey) -> Result<bool, DalError> {
+        match key {
+            DeploymentSlotKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM deployment_slot WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for DeploymentSlot {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO deployment_slot(id, service, environment, name, service_version, deployment, active) VALUES ($1, $2, $3, $4, $5, $6, $7)")
+                .bind(self.id)
+                .bind(self.service)
+                .bind(self.environment)
+                .bind(self.name.as_str())
+                .bind(self.service_version)
+                .bind(self.deployment)
+                .bind(self.active)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE deployment_slot SET service=$1, environment=$2, name=$3, service_version=$4, deployment=$5, active=$6 WHERE id=$7")
+                .bind(self.service)
+                .bind(self.environment)
+                .bind(self.name.as_str())
+                .bind(self.service_version)
+                .bind(self.deployment)
+                .bind(self.active)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM deployment_slot WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{has_attribute, key_attribute, key_str, key_uuid, PgDao, Tenant, TenantKey};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Environment {
+    id: Uuid,
+    name: String,
+    tenant: Uuid,
+    position: i32,
+    soak_minutes: Option<i32>,
+    require_build: bool,
+    require_approval: bool,
+}
+
+impl Environment {
+    pub fn new(id: Uuid, name: &str, tenant: Uuid, position: i32, soak_minutes: Option<i32>, require_build: bool, require_approval: bool) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            name: String::from(name),
+            tenant,
+            position,
+            soak_minutes,
+            require_build,
+            require_approval,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tenant(&self) -> Uuid {
+        self.tenant
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn soak_minutes(&self) -> Option<i32> {
+        self.soak_minutes
+    }
+
+    pub fn require_build(&self) -> bool {
+        self.require_build
+    }
+
+    pub fn require_approval(&self) -> bool {
+        self.require_approval
+    }
+
+    pub async fn tenant_environments(tenant: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, name, tenant, position, soak_minutes, require_build, require_approval FROM environment WHERE tenant=$1 ORDER BY position, id")
+                .bind(tenant)
+        ).await
+    }
+}
+
+// an environment is read by id or by its name in a tenant
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvironmentKey {
+    Id(Uuid),
+    Natural { name: String, tenant: TenantKey },
+}
+
+impl TryFrom<Value> for EnvironmentKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                name: key_str(&key, "name")?,
+                tenant: TenantKey::try_from(key_attribute(&key, "tenant")?.clone())?,
+            })
+        }
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for Environment {
+    type Key = EnvironmentKey;
+
+    async fn read(key: EnvironmentKey) -> Result<Box<Self>, DalError> {
+        match key {
+            EnvironmentKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, name, tenant, position, soak_minutes, require_build, require_approval FROM environment WHERE id=$1")
+                    .bind(id)
+            ).await,
+            EnvironmentKey::Natural { name, tenant: tenant_key } => {
+                match Tenant::read(tenant_key.clone()).await {
+                    Ok(tenant) => Self::query_ctx().await.fetch_one(
+                        sqlx::query_as::<_, Self>("SELECT id, name, tenant, position, soak_minutes, require_build, require_approval FROM environment WHERE name=$1 AND tenant=$2")
+                            .bind(name)
+                            .bind(tenant.id())
+                    ).await,
+                    Err(DalError::DataNotFound(_)) => Err(DalError::DataNotFound(format!("tenant {:?}", tenant_key))),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    }
+
+    async fn exists(key: EnvironmentKey) -> Result<bool, DalError> {
+        match key {
+            EnvironmentKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM environment WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            },
+            EnvironmentKey::Natural { name, tenant: tenant_key } => {
+                match Tenant::read(tenant_key).await {
+                    Ok(tenant) => {
+                        let r = Self::query_ctx().await.fetch_one(
+                            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM environment WHERE name=$1 AND tenant=$2")
+                                .bind(name)
+                                .bind(tenant.id())
+                        ).await;
+                        Ok(r?.count > 0)
+                    },
+                    Err(DalError::DataNotFound(_)) => Ok(false),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for Environment {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO environment(id, name, tenant, position, soak_minutes, require_build, require_approval) VALUES ($1, $2, $3, $4, $5, $6, $7)")
+                .bind(self.id)
+                .bind(self.name.as_str())
+                .bind(self.tenant)
+                .bind(self.position)
+                .bind(self.soak_minutes)
+                .bind(self.require_build)
+                .bind(self.require_approval)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE environment SET name=$1, tenant=$2, position=$3, soak_minutes=$4, require_build=$5, require_approval=$6 WHERE id=$7")
+                .bind(self.name.as_str())
+                .bind(self.tenant)
+                .bind(self.position)
+                .bind(self.soak_minutes)
+                .bind(self.require_build)
+                .bind(self.require_approval)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM environment WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{has_attribute, key_attribute, key_str, key_uuid, PgDao, Tenant, TenantKey};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FreezeWindow {
+    id: Uuid,
+    name: String,
+    tenant: Uuid,
+    environment: Option<Uuid>,
+    days: String,
+    starts_at: NaiveTime,
+    ends_at: NaiveTime,
+    reason: Option<String>,
+}
+
+impl FreezeWindow {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(id: Uuid, name: &str, tenant: Uuid, environment: Option<Uuid>, days: &str, starts_at: NaiveTime, ends_at: NaiveTime, reason: Option<&str>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            name: String::from(name),
+            tenant,
+            environment,
+            days: String::from(days),
+            starts_at,
+            ends_at,
+            reason: reason.map(String::from),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn tenant(&self) -> Uuid {
+        self.tenant
+    }
+
+    pub fn environment(&self) -> Option<Uuid> {
+        self.environment
+    }
+
+    pub fn days(&self) -> &str {
+        self.days.as_str()
+    }
+
+    pub fn starts_at(&self) -> NaiveTime {
+        self.starts_at
+    }
+
+    pub fn ends_at(&self) -> NaiveTime {
+        self.ends_at
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub async fn tenant_windows(tenant: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, name, tenant, environment, days, starts_at, ends_at, reason FROM freeze_window WHERE tenant=$1 ORDER BY name")
+                .bind(tenant)
+        ).await
+    }
+}
+
+// a freeze window is read by id or by its name in a tenant
+#[derive(Debug, Clone, PartialEq)]
+pub enum FreezeWindowKey {
+    Id(Uuid),
+    Natural { name: String, tenant: TenantKey },
+}
+
+impl TryFrom<Value> for FreezeWindowKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                name: key_str(&key, "name")?,
+                tenant: TenantKey::try_from(key_attribute(&key, "tenant")?.clone())?,
+            })
+        }
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for FreezeWindow {
+    type Key = FreezeWindowKey;
+
+    async fn read(key: FreezeWindowKey) -> Result<Box<Self>, DalError> {
+        match key {
+            FreezeWindowKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, name, tenant, environment, days, starts_at, ends_at, reason FROM freeze_window WHERE id=$1")
+                    .bind(id)
+            ).await,
+            FreezeWindowKey::Natural { name, tenant: tenant_key } => {
+                match Tenant::read(tenant_key.clone()).await {
+                    Ok(tenant) => Self::query_ctx().await.fetch_one(
+                        sqlx::query_as::<_, Self>("SELECT id, name, tenant, environment, days, starts_at, ends_at, reason FROM freeze_window WHERE name=$1 AND tenant=$2")
+                            .bind(name)
+                            .bind(tenant.id())
+                    ).await,
+                    Err(DalError::DataNotFound(_)) => Err(DalError::DataNotFound(format!("tenant {:?}", tenant_key))),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    }
+
+    async fn exists(key: FreezeWindowKey) -> Result<bool, DalError> {
+        match key {
+            FreezeWindowKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM freeze_window WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            },
+            FreezeWindowKey::Natural { name, tenant: tenant_key } => {
+                match Tenant::read(tenant_key).await {
+                    Ok(tenant) => {
+                        let r = Self::query_ctx().await.fetch_one(
+                            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM freeze_window WHERE name=$1 AND tenant=$2")
+                                .bind(name)
+                                .bind(tenant.id())
+                        ).await;
+                        Ok(r?.count > 0)
+                    },
+                    Err(DalError::DataNotFound(_)) => Ok(false),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for FreezeWindow {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO freeze_window(id, name, tenant, environment, days, starts_at, ends_at, reason) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)")
+                .bind(self.id)
+                .bind(self.name.as_str())
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.days.as_str())
+                .bind(self.starts_at)
+                .bind(self.ends_at)
+                .bind(self.reason.as_deref())
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE freeze_window SET name=$1, tenant=$2, environment=$3, days=$4, starts_at=$5, ends_at=$6, reason=$7 WHERE id=$8")
+                .bind(self.name.as_str())
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.days.as_str())
+                .bind(self.starts_at)
+                .bind(self.ends_at)
+                .bind(self.reason.as_deref())
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM freeze_window WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LockOverride {
+    id: Uuid,
+    tenant: Uuid,
+    environment: Option<Uuid>,
+    action: String,
+    locked: String,
+    overridden_by: String,
+    justification: String,
+    overridden_on: DateTime<Utc>,
+}
+
+impl LockOverride {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(id: Uuid, tenant: Uuid, environment: Option<Uuid>, action: &str, locked: &str, overridden_by: &str, justification: &str, overridden_on: DateTime<Utc>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            tenant,
+            environment,
+            action: String::from(action),
+            locked: String::from(locked),
+            overridden_by: String::from(overridden_by),
+            justification: String::from(justification),
+            overridden_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn tenant(&self) -> Uuid {
+        self.tenant
+    }
+
+    pub fn environment(&self) -> Option<Uuid> {
+        self.environment
+    }
+
+    pub fn action(&self) -> &str {
+        self.action.as_str()
+    }
+
+    pub fn locked(&self) -> &str {
+        self.locked.as_str()
+    }
+
+    pub fn overridden_by(&self) -> &str {
+        self.overridden_by.as_str()
+    }
+
+    pub fn justification(&self) -> &str {
+        self.justification.as_str()
+    }
+
+    pub fn overridden_on(&self) -> DateTime<Utc> {
+        self.overridden_on
+    }
+
+    pub async fn tenant_overrides(tenant: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, tenant, environment, action, locked, overridden_by, justification, overridden_on FROM lock_override WHERE tenant=$1 ORDER BY id")
+                .bind(tenant)
+        ).await
+    }
+}
+
+// a lock override is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockOverrideKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for LockOverrideKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for LockOverride {
+    type Key = LockOverrideKey;
+
+    async fn read(key: LockOverrideKey) -> Result<Box<Self>, DalError> {
+        match key {
+            LockOverrideKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, tenant, environment, action, locked, overridden_by, justification, overridden_on FROM lock_override WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: LockOverrideKey) -> Result<bool, DalError> {
+        match key {
+            LockOverrideKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM lock_override WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for LockOverride {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO lock_override(id, tenant, environment, action, locked, overridden_by, justification, overridden_on) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)")
+                .bind(self.id)
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.action.as_str())
+                .bind(self.locked.as_str())
+                .bind(self.overridden_by.as_str())
+                .bind(self.justification.as_str())
+                .bind(self.overridden_on)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE lock_override SET tenant=$1, environment=$2, action=$3, locked=$4, overridden_by=$5, justification=$6, overridden_on=$7 WHERE id=$8")
+                .bind(self.tenant)
+                .bind(self.environment)
+                .bind(self.action.as_str())
+                .bind(self.locked.as_str())
+                .bind(self.overridden_by.as_str())
+                .bind(self.justification.as_str())
+                .bind(self.overridden_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM lock_override WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
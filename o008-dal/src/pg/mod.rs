@@ -1,9 +1,21 @@
 mod builder;
 mod tenant;
 mod application;
+mod environment;
+mod deployment;
+mod deployment_slot;
+mod deployment_lock;
+mod freeze_window;
+mod lock_override;
+mod release;
+mod release_approval;
 mod service;
 mod repo_reference;
 mod service_version;
+mod service_version_build;
+mod service_version_build_stage;
+mod service_version_repo_ref_history;
+mod service_version_artifact;
 
 use std::sync::Arc;
 use async_trait::async_trait;
@@ -13,18 +25,31 @@ use sqlx::query::{Query, QueryAs};
 use crate::{QueryContext, CommandContext, DBPool, DaoQuery, DaoCommand, DalError};
 use async_once::AsyncOnce;
 use serde_json::Value;
+use uuid::Uuid;
 use o008_setting::app_config;
 
-pub use builder::Builder;
-pub use tenant::Tenant;
-pub use application::Application;
-pub use service::Service;
-pub use repo_reference::RepoReference;
-pub use service_version::ServiceVersion;
+pub use builder::{Builder, BuilderKey};
+pub use tenant::{Tenant, TenantKey};
+pub use application::{Application, ApplicationKey};
+pub use environment::{Environment, EnvironmentKey};
+pub use deployment::{Deployment, DeploymentKey};
+pub use deployment_slot::{DeploymentSlot, DeploymentSlotKey};
+pub use deployment_lock::{DeploymentLock, DeploymentLockKey};
+pub use freeze_window::{FreezeWindow, FreezeWindowKey};
+pub use lock_override::{LockOverride, LockOverrideKey};
+pub use release::{Release, ReleaseKey};
+pub use release_approval::{ReleaseApproval, ReleaseApprovalKey};
+pub use service::{Service, ServiceKey};
+pub use repo_reference::{RepoReference, RepoReferenceKey};
+pub use service_version::{ServiceVersion, ServiceVersionKey};
+pub use service_version_build::{ServiceVersionBuild, ServiceVersionBuildKey};
+pub use service_version_build_stage::{ServiceVersionBuildStage, ServiceVersionBuildStageKey};
+pub use service_version_repo_ref_history::{ServiceVersionRepoRefHistory, ServiceVersionRepoRefHistoryKey};
+pub use service_version_artifact::{ServiceVersionArtifact, ServiceVersionArtifactKey};
 
 pub type PgQueryContext = dyn QueryContext<Postgres>;
 pub type PgCommandContext = dyn CommandContext<Postgres>;
-pub type PgDaoQuery = dyn DaoQuery<PgQueryContext, Postgres>;
+pub type PgDaoQuery<K> = dyn DaoQuery<PgQueryContext, Postgres, Key = K>;
 pub type PgDaoCommand = dyn DaoCommand<PgCommandContext, Postgres>;
 
 #[derive(Debug, Clone)]
@@ -76,6 +101,14 @@ impl CommandContext<Postgres> for PgDao {
             Err(e) => Err(DalError::DataCreation(e)),
         }
     }
+
+    async fn execute_all<'q>(&self, queries: Vec<Query<'q, Postgres, PgArguments>>) -> Result<(), DalError> {
+        let mut tx = self.pool().begin().await.map_err(DalError::DataGenericError)?;
+        for query in queries {
+            query.execute(&mut *tx).await.map_err(DalError::DataGenericError)?;
+        }
+        tx.commit().await.map_err(DalError::DataGenericError)
+    }
 }
 
 lazy_static::lazy_static! {
@@ -97,35 +130,137 @@ async fn create_pool() -> Pool<Postgres> {
         .expect( "could not connect to postgres database at create_pool")
 }
 
-fn hard_check_key(key: &Value, attributes: &[&str]) -> Result<Vec<Value>, DalError> {
-    if key.is_object() {
-        let mut vec = Vec::<Value>::new();
-        let map = key.as_object().unwrap();
-        for a in attributes.iter() {
-            if !map.contains_key(*a) {
-                return Err(DalError::InvalidKey(format!("missing '{}' attribute: {}", *a, key )))
-            }
-            let value = map.get(*a).unwrap().clone();
-            vec.push(value);
-        }
-        return Ok(vec)
+// the deletes of the service versions selected by the $1 bound subquery and of every row referencing them, in order
+fn service_version_subtree(versions: &str) -> Vec<String> {
+    let deployments = format!("SELECT id FROM deployment WHERE service_version IN ({})", versions);
+    let builds = format!("SELECT id FROM service_version_build WHERE service_version IN ({})", versions);
+    vec![
+        format!("DELETE FROM deployment_slot WHERE service_version IN ({}) OR deployment IN ({})", versions, deployments),
+        format!("DELETE FROM release_approval WHERE release IN (SELECT id FROM release WHERE service_version IN ({}) OR from_deployment IN ({}))", versions, deployments),
+        format!("DELETE FROM release WHERE service_version IN ({}) OR from_deployment IN ({})", versions, deployments),
+        format!("UPDATE deployment SET rollback_of = NULL WHERE rollback_of IN ({}) AND service_version NOT IN ({})", deployments, versions),
+        format!("UPDATE deployment SET previous_version = NULL WHERE previous_version IN ({})", versions),
+        format!("DELETE FROM deployment WHERE service_version IN ({})", versions),
+        format!("DELETE FROM service_version_artifact WHERE service_version IN ({}) OR build IN ({})", versions, builds),
+        format!("DELETE FROM service_version_build_stage WHERE build IN ({})", builds),
+        format!("UPDATE service_version_build SET cached_from = NULL WHERE cached_from IN ({}) AND service_version NOT IN ({})", builds, versions),
+        format!("UPDATE service_version_build SET retry_of = NULL WHERE retry_of IN ({}) AND service_version NOT IN ({})", builds, versions),
+        format!("DELETE FROM service_version_build WHERE service_version IN ({})", versions),
+        format!("DELETE FROM service_version_repo_ref_history WHERE service_version IN ({})", versions),
+        format!("DELETE FROM service_version WHERE id IN ({})", versions),
+    ]
+}
+
+// runs the deletes of a subtree in one transaction, all bound to the id of its root
+async fn delete_subtree(deletes: &[String], id: Uuid) -> Result<(), DalError> {
+    let queries = deletes.iter()
+        .map(|sql| sqlx::query(sql.as_str()).bind(id))
+        .collect();
+    PgDao::new().await.execute_all(queries).await
+}
+
+// archived rows are hidden from the reads by name and the lists unless the key includes them
+fn include_archived(key: &Value) -> bool {
+    key.get("include_archived").and_then(|i| i.as_bool()).unwrap_or(false)
+}
+
+fn has_attribute(key: &Value, attribute: &str) -> bool {
+    key.get(attribute).is_some_and(|v| !v.is_null())
+}
+
+// the attributes of a json key are checked on the conversion into a typed key, never unwrapped
+fn key_attribute<'k>(key: &'k Value, attribute: &str) -> Result<&'k Value, DalError> {
+    match key.as_object() {
+        Some(map) => map.get(attribute)
+            .ok_or_else(|| DalError::InvalidKey(format!("missing '{}' attribute: {}", attribute, key))),
+        None => Err(DalError::InvalidKey(format!("key should be an object: {}", key)))
     }
-    Err(DalError::InvalidKey(format!("key should be an object: {}", key)))
 }
 
-fn soft_check_key(key: &Value, attributes: &[&str]) -> Result<Vec<Option<Value>>, DalError> {
-    if key.is_object() {
-        let mut vec = Vec::<Option<Value>>::new();
-        let map = key.as_object().unwrap();
-        for a in attributes.iter() {
-            if !map.contains_key(*a) {
-                vec.push(None);
-            } else {
-                let value = map.get(*a).unwrap().clone();
-                vec.push(Some(value));
-            }
+fn key_str(key: &Value, attribute: &str) -> Result<String, DalError> {
+    match key_attribute(key, attribute)?.as_str() {
+        Some(s) => Ok(String::from(s)),
+        None => Err(DalError::InvalidKey(format!("'{}' attribute should be a string: {}", attribute, key)))
+    }
+}
+
+fn key_uuid(key: &Value, attribute: &str) -> Result<Uuid, DalError> {
+    let id = key_str(key, attribute)?;
+    Uuid::parse_str(&id).map_err(|e| DalError::InvalidKey(format!("'{}' attribute should be an uuid: {}", attribute, e)))
+}
+
+fn key_i32(key: &Value, attribute: &str) -> Result<i32, DalError> {
+    match key_attribute(key, attribute)?.as_i64().and_then(|i| i32::try_from(i).ok()) {
+        Some(i) => Ok(i),
+        None => Err(DalError::InvalidKey(format!("'{}' attribute should be an integer: {}", attribute, key)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    const ID: &str = "01a14f60-b2df-7658-b2f7-bad7317dd88a";
+
+    fn invalid<K: TryFrom<Value, Error = DalError> + std::fmt::Debug>(keys: Vec<Value>) {
+        for key in keys {
+            let r = K::try_from(key.clone());
+            assert!(matches!(r, Err(DalError::InvalidKey(_))), "{} gave {:?}", key, r);
         }
-        return Ok(vec)
     }
-    Err(DalError::InvalidKey(format!("key should be an object: {}", key)))
+
+    // the keys only read by id
+    fn invalid_id<K: TryFrom<Value, Error = DalError> + std::fmt::Debug>() {
+        invalid::<K>(vec![json!(null), json!(ID), json!([ID]), json!({}), json!({"id": 42}), json!({"id": "42"}), json!({"name": "acme"})]);
+    }
+
+    #[test]
+    fn id_keys_refuse_malformed_values() {
+        invalid_id::<DeploymentKey>();
+        invalid_id::<DeploymentLockKey>();
+        invalid_id::<DeploymentSlotKey>();
+        invalid_id::<LockOverrideKey>();
+        invalid_id::<ReleaseApprovalKey>();
+        invalid_id::<ServiceVersionArtifactKey>();
+        invalid_id::<ServiceVersionBuildKey>();
+        invalid_id::<ServiceVersionRepoRefHistoryKey>();
+        assert_eq!(DeploymentKey::try_from(json!({"id": ID})).unwrap(), DeploymentKey::Id(Uuid::parse_str(ID).unwrap()));
+    }
+
+    #[test]
+    fn named_keys_refuse_malformed_values() {
+        invalid::<TenantKey>(vec![json!(null), json!("acme"), json!({}), json!({"id": "42"}), json!({"name": 42})]);
+        invalid::<BuilderKey>(vec![json!(null), json!("sh"), json!({}), json!({"id": "42"}), json!({"name": true})]);
+        assert_eq!(TenantKey::try_from(json!({"name": "acme"})).unwrap(), TenantKey::Natural { name: String::from("acme") });
+    }
+
+    #[test]
+    fn tenant_scoped_keys_refuse_malformed_values() {
+        let keys = || vec![json!(null), json!({"id": "42"}), json!({"name": "prod"}), json!({"tenant": {"name": "acme"}}),
+                           json!({"name": "prod", "tenant": "acme"}), json!({"name": "prod", "tenant": {"id": "42"}})];
+        invalid::<ApplicationKey>(keys());
+        invalid::<EnvironmentKey>(keys());
+        invalid::<FreezeWindowKey>(keys());
+    }
+
+    #[test]
+    fn nested_keys_refuse_malformed_values() {
+        invalid::<ServiceKey>(vec![json!(null), json!({"name": "cart"}), json!({"name": "cart", "application": {"name": "shop"}}),
+                                   json!({"name": "cart", "application": {"name": "shop", "tenant": {"id": "42"}}})]);
+        invalid::<ServiceVersionKey>(vec![json!(null), json!({"version": "1.0.0"}), json!({"version": 1, "service": {"id": ID}}),
+                                          json!({"version": "1.0.0", "service": {"name": "cart", "application": {"id": "42"}}})]);
+        let key = ServiceVersionKey::try_from(json!({"version": "1.0.0", "service": {"name": "cart", "application": {"name": "shop", "tenant": {"name": "acme"}}}}));
+        assert!(key.is_ok());
+    }
+
+    #[test]
+    fn composite_keys_refuse_malformed_values() {
+        invalid::<ReleaseKey>(vec![json!(null), json!({"service_version": ID}), json!({"service_version": ID, "environment": "prod"})]);
+        invalid::<ServiceVersionBuildStageKey>(vec![json!(null), json!({"build": ID}), json!({"build": ID, "stage": "1"}),
+                                                    json!({"build": ID, "stage": 4_294_967_296_i64}), json!({"build": "42", "stage": 1})]);
+        invalid::<RepoReferenceKey>(vec![json!(null), json!({"repo": "file:///tmp/repo"}), json!({"repo": "file:///tmp/repo", "kind": "Tag"}),
+                                         json!({"repo": "file:///tmp/repo", "kind": "Label", "reference": "main"})]);
+    }
 }
@@ -0,0 +1,174 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use sqlx::postgres::PgArguments;
+use sqlx::query::Query;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{has_attribute, key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Release {
+    id: Uuid,
+    service_version: Uuid,
+    environment: Uuid,
+    status: String,
+    promoted_by: String,
+    promoted_on: DateTime<Utc>,
+    from_deployment: Uuid,
+}
+
+impl Release {
+    pub fn new(id: Uuid, service_version: Uuid, environment: Uuid, status: &str, promoted_by: &str, promoted_on: DateTime<Utc>, from_deployment: Uuid) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service_version,
+            environment,
+            status: String::from(status),
+            promoted_by: String::from(promoted_by),
+            promoted_on,
+            from_deployment,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn environment(&self) -> Uuid {
+        self.environment
+    }
+
+    pub fn status(&self) -> &str {
+        self.status.as_str()
+    }
+
+    pub fn promoted_by(&self) -> &str {
+        self.promoted_by.as_str()
+    }
+
+    pub fn promoted_on(&self) -> DateTime<Utc> {
+        self.promoted_on
+    }
+
+    pub fn from_deployment(&self) -> Uuid {
+        self.from_deployment
+    }
+
+    pub async fn service_version_releases(service_version: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, environment, status, promoted_by, promoted_on, from_deployment FROM release WHERE service_version=$1 ORDER BY id")
+                .bind(service_version)
+        ).await
+    }
+
+    // the latest release with the status of each service to each environment of a tenant
+    pub async fn latest_releases(tenant: Uuid, status: &str) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT DISTINCT ON (sv.service, r.environment) r.id, r.service_version, r.environment, r.status, r.promoted_by, r.promoted_on, r.from_deployment FROM release r JOIN service_version sv ON sv.id=r.service_version JOIN environment e ON e.id=r.environment WHERE e.tenant=$1 AND r.status=$2 ORDER BY sv.service, r.environment, r.promoted_on DESC")
+                .bind(tenant)
+                .bind(status)
+        ).await
+    }
+
+    // the update is also run in the transaction recording a decision on the release
+    pub(crate) fn update_query(&self) -> Query<'_, Postgres, PgArguments> {
+        sqlx::query("UPDATE release SET service_version=$1, environment=$2, status=$3, promoted_by=$4, promoted_on=$5, from_deployment=$6 WHERE id=$7")
+            .bind(self.service_version)
+            .bind(self.environment)
+            .bind(self.status.as_str())
+            .bind(self.promoted_by.as_str())
+            .bind(self.promoted_on)
+            .bind(self.from_deployment)
+            .bind(self.id)
+    }
+}
+
+// a release is read by id or by its service version and environment
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReleaseKey {
+    Id(Uuid),
+    Natural { service_version: Uuid, environment: Uuid },
+}
+
+impl TryFrom<Value> for ReleaseKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                service_version: key_uuid(&key, "service_version")?,
+                environment: key_uuid(&key, "environment")?,
+            })
+        }
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for Release {
+    type Key = ReleaseKey;
+
+    async fn read(key: ReleaseKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ReleaseKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, environment, status, promoted_by, promoted_on, from_deployment FROM release WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ReleaseKey::Natural { service_version, environment } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, environment, status, promoted_by, promoted_on, from_deployment FROM release WHERE service_version=$1 AND environment=$2")
+                    .bind(service_version)
+                    .bind(environment)
+            ).await
+        }
+    }
+
+    async fn exists(key: ReleaseKey) -> Result<bool, DalError> {
+        let r = match key {
+            ReleaseKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM release WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ReleaseKey::Natural { service_version, environment } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM release WHERE service_version=$1 AND environment=$2")
+                    .bind(service_version)
+                    .bind(environment)
+            ).await
+        };
+        Ok(r?.count > 0)
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for Release {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO release(id, service_version, environment, status, promoted_by, promoted_on, from_deployment) VALUES ($1, $2, $3, $4, $5, $6, $7)")
+                .bind(self.id)
+                .bind(self.service_version)
+                .bind(self.environment)
+                .bind(self.status.as_str())
+                .bind(self.promoted_by.as_str())
+                .bind(self.promoted_on)
+                .bind(self.from_deployment)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(self.update_query()).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM release WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use sqlx::postgres::PgArguments;
+use sqlx::query::Query;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao, Release};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReleaseApproval {
+    id: Uuid,
+    release: Uuid,
+    decision: String,
+    approver: String,
+    comment: Option<String>,
+    decided_on: DateTime<Utc>,
+}
+
+impl ReleaseApproval {
+    pub fn new(id: Uuid, release: Uuid, decision: &str, approver: &str, comment: Option<&str>, decided_on: DateTime<Utc>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            release,
+            decision: String::from(decision),
+            approver: String::from(approver),
+            comment: comment.map(String::from),
+            decided_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn release(&self) -> Uuid {
+        self.release
+    }
+
+    pub fn decision(&self) -> &str {
+        self.decision.as_str()
+    }
+
+    pub fn approver(&self) -> &str {
+        self.approver.as_str()
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn decided_on(&self) -> DateTime<Utc> {
+        self.decided_on
+    }
+
+    // the decision and the release status it sets are recorded together
+    pub async fn insert_deciding(&self, release: &Release) -> Result<(), DalError> {
+        Self::command_ctx().await.execute_all(vec![release.update_query(), self.insert_query()]).await
+    }
+
+    fn insert_query(&self) -> Query<'_, Postgres, PgArguments> {
+        sqlx::query("INSERT INTO release_approval(id, release, decision, approver, comment, decided_on) VALUES ($1, $2, $3, $4, $5, $6)")
+            .bind(self.id)
+            .bind(self.release)
+            .bind(self.decision.as_str())
+            .bind(self.approver.as_str())
+            .bind(self.comment.as_deref())
+            .bind(self.decided_on)
+    }
+
+    pub async fn release_approvals(release: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, release, decision, approver, comment, decided_on FROM release_approval WHERE release=$1 ORDER BY id")
+                .bind(release)
+        ).await
+    }
+}
+
+// a release approval is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReleaseApprovalKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for ReleaseApprovalKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for ReleaseApproval {
+    type Key = ReleaseApprovalKey;
+
+    async fn read(key: ReleaseApprovalKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ReleaseApprovalKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, release, decision, approver, comment, decided_on FROM release_approval WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: ReleaseApprovalKey) -> Result<bool, DalError> {
+        match key {
+            ReleaseApprovalKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM release_approval WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for ReleaseApproval {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(self.insert_query()).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE release_approval SET release=$1, decision=$2, approver=$3, comment=$4, decided_on=$5 WHERE id=$6")
+                .bind(self.release)
+                .bind(self.decision.as_str())
+                .bind(self.approver.as_str())
+                .bind(self.comment.as_deref())
+                .bind(self.decided_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM release_approval WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -1,11 +1,12 @@
+use std::str::FromStr;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
 use o008_common::RepoReferenceKind;
-use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
-use crate::pg::{hard_check_key, PgDao};
+use crate::{CommandContext, DalCount, DalDependent, DalError, DaoCommand, DaoDependent, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{delete_subtree, has_attribute, key_str, key_uuid, PgDao, service_version_subtree};
 
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -43,48 +44,65 @@ impl RepoReference {
     }
 }
 
+// a repo reference is read by id or by its repo, kind and reference
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepoReferenceKey {
+    Id(Uuid),
+    Natural { repo: String, kind: RepoReferenceKind, reference: String },
+}
+
+impl TryFrom<Value> for RepoReferenceKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            return Ok(Self::Id(key_uuid(&key, "id")?))
+        }
+        let kind = key_str(&key, "kind")?;
+        match RepoReferenceKind::from_str(&kind) {
+            Ok(kind) => Ok(Self::Natural {
+                repo: key_str(&key, "repo")?,
+                kind,
+                reference: key_str(&key, "reference")?,
+            }),
+            Err(_) => Err(DalError::InvalidKey(format!("unknown repo reference kind '{}': {}", kind, key)))
+        }
+    }
+}
+
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for RepoReference {
-    async fn read(key: Value) -> Result<Box<Self>, DalError> {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            Self::query_ctx().await.fetch_one(
+    type Key = RepoReferenceKey;
+
+    async fn read(key: RepoReferenceKey) -> Result<Box<Self>, DalError> {
+        match key {
+            RepoReferenceKey::Id(id) => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, Self>("SELECT id, repo, kind, reference FROM repo_reference WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await
-        } else if let Ok(rkr_key) = hard_check_key(&key, &["repo", "kind", "reference"]) {
-            let (repo, kind, reference) = (rkr_key.get(0).unwrap(), rkr_key.get(1).unwrap(), rkr_key.get(2).unwrap());
-            Self::query_ctx().await.fetch_one(
+                    .bind(id)
+            ).await,
+            RepoReferenceKey::Natural { repo, kind, reference } => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, Self>("SELECT id, repo, kind, reference FROM repo_reference WHERE repo=$1 AND kind::text=$2 AND reference=$3")
-                    .bind(repo.as_str().unwrap())
-                    .bind(kind.as_str().unwrap())
-                    .bind(reference.as_str().unwrap())
+                    .bind(repo)
+                    .bind(kind.to_string())
+                    .bind(reference)
             ).await
-        } else {
-            Err(DalError::InvalidKey("(id) or (repo/kind/reference) keys expected".to_string()))
         }
     }
 
-    async fn exists(key: Value) -> bool {
-        return if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            let qr = Self::query_ctx().await.fetch_one(
+    async fn exists(key: RepoReferenceKey) -> Result<bool, DalError> {
+        let qr = match key {
+            RepoReferenceKey::Id(id) => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM repo_reference WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await;
-            qr.unwrap().count > 0
-        } else if let Ok(rkr_key) = hard_check_key(&key, &["repo", "kind", "reference"]) {
-            let (repo, kind, reference) = (rkr_key.first().unwrap(), rkr_key.get(1).unwrap(), rkr_key.get(2).unwrap());
-            let qr = Self::query_ctx().await.fetch_one(
+                    .bind(id)
+            ).await,
+            RepoReferenceKey::Natural { repo, kind, reference } => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count  FROM repo_reference WHERE repo=$1 AND kind::text=$2 AND reference=$3")
-                    .bind(repo.as_str().unwrap())
-                    .bind(kind.as_str().unwrap())
-                    .bind(reference.as_str().unwrap())
-            ).await;
-            qr.unwrap().count > 0
-        } else {
-            false
-        }
+                    .bind(repo)
+                    .bind(kind.to_string())
+                    .bind(reference)
+            ).await
+        };
+        Ok(qr?.count > 0)
     }
 }
 
@@ -117,3 +135,20 @@ impl DaoCommand<PgDao, Postgres> for RepoReference {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for RepoReference {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'service_version' AS kind, id, version AS name FROM service_version WHERE repo_ref = $1 UNION ALL SELECT 'service_version_repo_ref_history' AS kind, id, pinned_on::text AS name FROM service_version_repo_ref_history WHERE repo_ref = $1 OR pinned_ref = $1")
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete_cascade(&self) -> Result<(), DalError> {
+        let mut deletes = service_version_subtree("SELECT id FROM service_version WHERE repo_ref = $1");
+        deletes.push(String::from("DELETE FROM service_version_repo_ref_history WHERE repo_ref = $1 OR pinned_ref = $1"));
+        deletes.push(String::from("DELETE FROM repo_reference WHERE id = $1"));
+        delete_subtree(&deletes, self.id).await
+    }
+}
@@ -1,10 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
-use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
-use crate::pg::{Application, hard_check_key, PgDao};
+use crate::{CommandContext, DalCount, DalDependent, DalError, DalFilter, DalPage, DaoCommand, DaoDependent, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{Application, ApplicationKey, delete_subtree, has_attribute, include_archived, key_attribute, key_str, key_uuid, PgDao, service_version_subtree};
 
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -14,16 +15,20 @@ pub struct Service {
     original_name: String,
     application: Uuid,
     default_repo: String,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl Service {
-    pub fn new(id: Uuid, name: &str, original_name: &str, application: Uuid, default_repo: &str) -> Self {
+    pub fn new(id: Uuid, name: &str, original_name: &str, application: Uuid, default_repo: &str, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id: gen_v7_uuid(id),
             name: String::from(name),
             original_name: String::from(original_name),
             application,
-            default_repo: String::from(default_repo)
+            default_repo: String::from(default_repo),
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -46,63 +51,121 @@ impl Service {
     pub fn default_repo(&self) -> &str {
         &self.default_repo
     }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+
+    pub async fn tenant_services(tenant: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT s.id, s.name, s.original_name, s.application, s.default_repo, s.archived_at, s.archived_by FROM service s JOIN application a ON a.id=s.application WHERE a.tenant=$1 ORDER BY a.name, s.name")
+                .bind(tenant)
+        ).await
+    }
+}
+
+// a service is read by id or by its name in an application, an archived one only when included
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceKey {
+    Id(Uuid),
+    Natural { name: String, application: ApplicationKey, include_archived: bool },
+}
+
+impl ServiceKey {
+    pub fn with_archived(self, include: bool) -> Self {
+        match self {
+            Self::Natural { name, application, include_archived } =>
+                Self::Natural { name, application, include_archived: include_archived || include },
+            id => id,
+        }
+    }
+}
+
+impl TryFrom<Value> for ServiceKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                name: key_str(&key, "name")?,
+                application: ApplicationKey::try_from(key_attribute(&key, "application")?.clone())?,
+                include_archived: include_archived(&key),
+            })
+        }
+    }
 }
 
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for Service {
-    async fn read(key: Value) -> Result<Box<Self>, DalError> {
-        match hard_check_key(&key, &["id"]) {
-            Ok(id_key) => {
-                let id = id_key.first().unwrap();
-                Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, Self>("SELECT id, name, original_name, application, default_repo FROM service WHERE id=$1")
-                        .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-                ).await
-            },
-            Err(_) => match hard_check_key(&key, &["name", "application"]) {
-                Ok(name_app_key) => {
-                    let name = name_app_key.get(0).unwrap().as_str().unwrap();
-                    let app_qry = name_app_key.get(1).unwrap();
-                    if let Ok(app) = Application::read(app_qry.clone()).await {
-                        Self::query_ctx().await.fetch_one(
-                            sqlx::query_as::<_, Self>("SELECT id, name, original_name, application, default_repo FROM service WHERE name=$1 AND application=$2")
-                                .bind(name)
-                                .bind(app.id())
-                        ).await
-                    } else {
-                        Err(DalError::DataNotFound(format!("application {}", app_qry)))
-                    }
+    type Key = ServiceKey;
+
+    async fn read(key: ServiceKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, name, original_name, application, default_repo, archived_at, archived_by FROM service WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ServiceKey::Natural { name, application: app_key, include_archived } => {
+                match Application::read(app_key.clone().with_archived(include_archived)).await {
+                    Ok(app) => Self::query_ctx().await.fetch_one(
+                        sqlx::query_as::<_, Self>("SELECT id, name, original_name, application, default_repo, archived_at, archived_by FROM service WHERE name=$1 AND application=$2 AND ($3::boolean IS TRUE OR archived_at IS NULL)")
+                            .bind(name)
+                            .bind(app.id())
+                            .bind(include_archived)
+                    ).await,
+                    Err(DalError::DataNotFound(_)) => Err(DalError::DataNotFound(format!("application {:?}", app_key))),
+                    Err(e) => Err(e)
                 }
-                Err(e) => Err(DalError::InvalidKey(format!("service dao read {}", e)))
             }
         }
     }
 
     #[tracing::instrument]
-    async fn exists(key: Value) -> bool {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            let r = Self::query_ctx().await.fetch_one(
-                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await;
-            r.unwrap().count > 0
-        } else if let Ok(name_app_key) = hard_check_key(&key, &["name", "application"]) {
-            let (name, app_qry) = (name_app_key.get(0).unwrap(), name_app_key.get(1).unwrap());
-            if let Ok(app) = Application::read(app_qry.clone()).await {
+    async fn exists(key: ServiceKey) -> Result<bool, DalError> {
+        match key {
+            ServiceKey::Id(id) => {
                 let r = Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service WHERE name=$1 AND application=$2")
-                        .bind(name.as_str().unwrap())
-                        .bind(app.id())
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service WHERE id=$1")
+                        .bind(id)
                 ).await;
-                r.unwrap().count > 0
-            } else {
-                false
+                Ok(r?.count > 0)
+            },
+            ServiceKey::Natural { name, application: app_key, .. } => {
+                match Application::read(app_key).await {
+                    Ok(app) => {
+                        let r = Self::query_ctx().await.fetch_one(
+                            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service WHERE name=$1 AND application=$2")
+                                .bind(name)
+                                .bind(app.id())
+                        ).await;
+                        Ok(r?.count > 0)
+                    },
+                    Err(DalError::DataNotFound(_)) => Ok(false),
+                    Err(e) => Err(e)
+                }
             }
-        } else {
-            false
         }
     }
+
+    async fn list(filter: DalFilter, page: DalPage) -> Result<Vec<Self>, DalError> {
+        let application = filter.required_owner("application")?;
+        let (cursor, order) = page.keyset();
+        let query = format!("SELECT id, name, original_name, application, default_repo, archived_at, archived_by FROM service WHERE application=$1 AND ($2::varchar IS NULL OR starts_with(name, $2)) AND ($3::uuid IS NULL OR id {} $3) AND ($5::boolean IS TRUE OR archived_at IS NULL) ORDER BY id {} LIMIT $4", cursor, order);
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>(&query)
+                .bind(application)
+                .bind(filter.name())
+                .bind(page.after())
+                .bind(page.limit())
+                .bind(filter.include_archived())
+        ).await
+    }
 }
 
 #[async_trait]
@@ -120,11 +183,13 @@ impl DaoCommand<PgDao, Postgres> for Service {
 
     async fn update(&self) -> Result<(), DalError> {
         Self::command_ctx().await.execute(
-            sqlx::query("UPDATE service SET name=$1, original_name=$2, application=$3, default_repo=$4 WHERE id=$5")
+            sqlx::query("UPDATE service SET name=$1, original_name=$2, application=$3, default_repo=$4, archived_at=$5, archived_by=$6 WHERE id=$7")
                 .bind(self.name.as_str())
                 .bind(self.original_name.as_str())
                 .bind(self.application)
                 .bind(self.default_repo.as_str())
+                .bind(self.archived_at)
+                .bind(self.archived_by.as_deref())
                 .bind(self.id)
         ).await
     }
@@ -136,3 +201,19 @@ impl DaoCommand<PgDao, Postgres> for Service {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for Service {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'service_version' AS kind, id, version AS name FROM service_version WHERE service = $1")
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete_cascade(&self) -> Result<(), DalError> {
+        let mut deletes = service_version_subtree("SELECT id FROM service_version WHERE service = $1");
+        deletes.push(String::from("DELETE FROM service WHERE id = $1"));
+        delete_subtree(&deletes, self.id).await
+    }
+}
@@ -1,10 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::{to_value, Value};
+use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
-use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
-use crate::pg::{hard_check_key, PgDao, Service};
+use crate::{CommandContext, DalCount, DalDependent, DalError, DalFilter, DalPage, DaoCommand, DaoDependent, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{delete_subtree, has_attribute, include_archived, key_attribute, key_str, key_uuid, PgDao, Service, ServiceKey, service_version_subtree};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ServiceVersion {
@@ -13,16 +14,20 @@ pub struct ServiceVersion {
     service: Uuid,
     repo_ref: Uuid,
     builder: Uuid,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl ServiceVersion {
-    pub fn new(id: Uuid, version: &str, service: Uuid, repo_ref: Uuid, builder: Uuid) -> Self {
+    pub fn new(id: Uuid, version: &str, service: Uuid, repo_ref: Uuid, builder: Uuid, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id: gen_v7_uuid(id),
             version: String::from(version),
             service,
             repo_ref,
             builder,
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -46,76 +51,110 @@ impl ServiceVersion {
         self.builder
     }
 
-    pub async fn service_versions(key: Value) -> Result<Vec<Self>, DalError> {
-        match hard_check_key(&key, &["service"]) {
-            Ok(service_key) => {
-                let id = service_key.first().unwrap();
-                Self::query_ctx().await.fetch_all(
-                    sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder FROM service_version WHERE service=$1")
-                        .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-                ).await
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
 
-            }
-            Err(e) => Err(DalError::InvalidKey(format!("service version dao read {}", e)))
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+
+    pub async fn service_versions(service: Uuid, include_archived: bool) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder, archived_at, archived_by FROM service_version WHERE service=$1 AND ($2::boolean IS TRUE OR archived_at IS NULL)")
+                .bind(service)
+                .bind(include_archived)
+        ).await
+    }
+}
+
+// a service version is read by id or by its version of a service, an archived one only when included
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceVersionKey {
+    Id(Uuid),
+    Natural { version: String, service: ServiceKey, include_archived: bool },
+}
+
+impl TryFrom<Value> for ServiceVersionKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                version: key_str(&key, "version")?,
+                service: ServiceKey::try_from(key_attribute(&key, "service")?.clone())?,
+                include_archived: include_archived(&key),
+            })
         }
     }
 }
 
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for ServiceVersion {
-    async fn read(key: Value) -> Result<Box<Self>, DalError> {
-        match hard_check_key(&key, &["id"]) {
-            Ok(id_key) => {
-                let id = id_key.first().unwrap();
-                Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder FROM service_version WHERE id=$1")
-                        .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-                ).await
-            },
-            Err(_) => match hard_check_key(&key, &["version", "service"]) {
-                Ok(version_service_key) => {
-                    let version = version_service_key.first().unwrap();
-                    let service_qry = version_service_key.get(1).unwrap();
-                    if let Ok(srv) = Service::read(to_value(service_qry).unwrap()).await {
-                        Self::query_ctx().await.fetch_one(
-                            sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder FROM service_version WHERE version=$1 AND service=$2")
-                                .bind(version.as_str().unwrap())
-                                .bind(srv.id())
-                        ).await
-                    } else {
-                        Err(DalError::DataNotFound(format!("service {}", service_qry)))
-                    }
+    type Key = ServiceVersionKey;
+
+    async fn read(key: ServiceVersionKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceVersionKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder, archived_at, archived_by FROM service_version WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ServiceVersionKey::Natural { version, service: service_key, include_archived } => {
+                match Service::read(service_key.clone().with_archived(include_archived)).await {
+                    Ok(srv) => Self::query_ctx().await.fetch_one(
+                        sqlx::query_as::<_, Self>("SELECT id, version, service, repo_ref, builder, archived_at, archived_by FROM service_version WHERE version=$1 AND service=$2 AND ($3::boolean IS TRUE OR archived_at IS NULL)")
+                            .bind(version)
+                            .bind(srv.id())
+                            .bind(include_archived)
+                    ).await,
+                    Err(DalError::DataNotFound(_)) => Err(DalError::DataNotFound(format!("service {:?}", service_key))),
+                    Err(e) => Err(e)
                 }
-                Err(e) => Err(DalError::InvalidKey(format!("service version dao read {}", e)))
             }
         }
     }
 
-    async fn exists(key: Value) -> bool {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            let r = Self::query_ctx().await.fetch_one(
-                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM service_version WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await;
-            r.unwrap().count > 0
-        } else if let Ok(version_service_key) = hard_check_key(&key, &["version", "service"]) {
-            let version = version_service_key.first().unwrap();
-            let service_qry = version_service_key.get(1).unwrap();
-            if let Ok(srv) = Service::read(to_value(service_qry).unwrap()).await {
+    async fn exists(key: ServiceVersionKey) -> Result<bool, DalError> {
+        match key {
+            ServiceVersionKey::Id(id) => {
                 let r = Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM service_version WHERE version=$1 AND service=$2")
-                        .bind(version.as_str().unwrap())
-                        .bind(srv.id())
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM service_version WHERE id=$1")
+                        .bind(id)
                 ).await;
-                r.unwrap().count > 0
-            } else {
-                false
+                Ok(r?.count > 0)
+            },
+            ServiceVersionKey::Natural { version, service: service_key, .. } => {
+                match Service::read(service_key).await {
+                    Ok(srv) => {
+                        let r = Self::query_ctx().await.fetch_one(
+                            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) as count FROM service_version WHERE version=$1 AND service=$2")
+                                .bind(version)
+                                .bind(srv.id())
+                        ).await;
+                        Ok(r?.count > 0)
+                    },
+                    Err(DalError::DataNotFound(_)) => Ok(false),
+                    Err(e) => Err(e)
+                }
             }
-        } else {
-            false
         }
     }
+
+    async fn list(filter: DalFilter, page: DalPage) -> Result<Vec<Self>, DalError> {
+        let service = filter.required_owner("service")?;
+        let (cursor, order) = page.keyset();
+        let query = format!("SELECT id, version, service, repo_ref, builder, archived_at, archived_by FROM service_version WHERE service=$1 AND ($2::varchar IS NULL OR starts_with(version, $2)) AND ($3::uuid IS NULL OR id {} $3) AND ($5::boolean IS TRUE OR archived_at IS NULL) ORDER BY id {} LIMIT $4", cursor, order);
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>(&query)
+                .bind(service)
+                .bind(filter.name())
+                .bind(page.after())
+                .bind(page.limit())
+                .bind(filter.include_archived())
+        ).await
+    }
 }
 
 #[async_trait]
@@ -133,11 +172,13 @@ impl DaoCommand<PgDao, Postgres> for ServiceVersion {
 
     async fn update(&self) -> Result<(), DalError> {
         Self::command_ctx().await.execute(
-            sqlx::query("UPDATE service_version SET version=$1, service=$2, repo_ref=$3, builder=$4 WHERE id=$5)")
+            sqlx::query("UPDATE service_version SET version=$1, service=$2, repo_ref=$3, builder=$4, archived_at=$5, archived_by=$6 WHERE id=$7")
                 .bind(self.version.as_str())
                 .bind(self.service)
                 .bind(self.repo_ref)
                 .bind(self.builder)
+                .bind(self.archived_at)
+                .bind(self.archived_by.as_deref())
                 .bind(self.id)
         ).await
     }
@@ -149,3 +190,18 @@ impl DaoCommand<PgDao, Postgres> for ServiceVersion {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for ServiceVersion {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'service_version_build' AS kind, id, status AS name FROM service_version_build WHERE service_version = $1 UNION ALL SELECT 'service_version_artifact' AS kind, id, name FROM service_version_artifact WHERE service_version = $1 UNION ALL SELECT 'service_version_repo_ref_history' AS kind, id, pinned_on::text AS name FROM service_version_repo_ref_history WHERE service_version = $1 UNION ALL SELECT 'deployment' AS kind, d.id, e.name FROM deployment d JOIN environment e ON e.id = d.environment WHERE d.service_version = $1 OR d.previous_version = $1 UNION ALL SELECT 'release' AS kind, r.id, e.name FROM release r JOIN environment e ON e.id = r.environment WHERE r.service_version = $1 UNION ALL SELECT 'deployment_slot' AS kind, id, name FROM deployment_slot WHERE service_version = $1")
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete_cascade(&self) -> Result<(), DalError> {
+        let deletes = service_version_subtree("SELECT $1::uuid");
+        delete_subtree(&deletes, self.id).await
+    }
+}
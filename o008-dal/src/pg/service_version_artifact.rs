@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ServiceVersionArtifact {
+    id: Uuid,
+    service_version: Uuid,
+    build: Uuid,
+    name: String,
+    size: i64,
+    sha256: String,
+    created_on: DateTime<Utc>,
+}
+
+impl ServiceVersionArtifact {
+    pub fn new(id: Uuid, service_version: Uuid, build: Uuid, name: &str, size: i64, sha256: &str, created_on: DateTime<Utc>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service_version,
+            build,
+            name: String::from(name),
+            size,
+            sha256: String::from(sha256),
+            created_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn build(&self) -> Uuid {
+        self.build
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    pub fn sha256(&self) -> &str {
+        self.sha256.as_str()
+    }
+
+    pub fn created_on(&self) -> DateTime<Utc> {
+        self.created_on
+    }
+
+    pub async fn service_version_artifacts(service_version: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, build, name, size, sha256, created_on FROM service_version_artifact WHERE service_version=$1 ORDER BY id")
+                .bind(service_version)
+        ).await
+    }
+
+    pub async fn build_artifacts(build: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, build, name, size, sha256, created_on FROM service_version_artifact WHERE build=$1 ORDER BY id")
+                .bind(build)
+        ).await
+    }
+}
+
+// an artifact is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceVersionArtifactKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for ServiceVersionArtifactKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for ServiceVersionArtifact {
+    type Key = ServiceVersionArtifactKey;
+
+    async fn read(key: ServiceVersionArtifactKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceVersionArtifactKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, build, name, size, sha256, created_on FROM service_version_artifact WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: ServiceVersionArtifactKey) -> Result<bool, DalError> {
+        match key {
+            ServiceVersionArtifactKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_artifact WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for ServiceVersionArtifact {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO service_version_artifact(id, service_version, build, name, size, sha256, created_on) VALUES ($1, $2, $3, $4, $5, $6, $7)")
+                .bind(self.id)
+                .bind(self.service_version)
+                .bind(self.build)
+                .bind(self.name.as_str())
+                .bind(self.size)
+                .bind(self.sha256.as_str())
+                .bind(self.created_on)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_artifact SET service_version=$1, build=$2, name=$3, size=$4, sha256=$5, created_on=$6 WHERE id=$7")
+                .bind(self.service_version)
+                .bind(self.build)
+                .bind(self.name.as_str())
+                .bind(self.size)
+                .bind(self.sha256.as_str())
+                .bind(self.created_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM service_version_artifact WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
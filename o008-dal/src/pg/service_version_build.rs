@@ -0,0 +1,274 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ServiceVersionBuild {
+    id: Uuid,
+    service_version: Uuid,
+    status: String,
+    completed: bool,
+    in_error: bool,
+    error_reason: Option<String>,
+    cancel_requested: bool,
+    start_on: Option<DateTime<Utc>>,
+    end_on: Option<DateTime<Utc>>,
+    retry_of: Option<Uuid>,
+    rerun_from: Option<i32>,
+    commit: Option<String>,
+    builder_digest: Option<String>,
+    cached_from: Option<Uuid>,
+}
+
+impl ServiceVersionBuild {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(id: Uuid, service_version: Uuid, status: &str, completed: bool, in_error: bool, error_reason: Option<&str>,
+               start_on: Option<DateTime<Utc>>, end_on: Option<DateTime<Utc>>, retry_of: Option<Uuid>, rerun_from: Option<i32>,
+               commit: Option<&str>, builder_digest: Option<&str>, cached_from: Option<Uuid>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service_version,
+            status: String::from(status),
+            completed,
+            in_error,
+            error_reason: error_reason.map(String::from),
+            cancel_requested: false,
+            start_on,
+            end_on,
+            retry_of,
+            rerun_from,
+            commit: commit.map(String::from),
+            builder_digest: builder_digest.map(String::from),
+            cached_from,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn status(&self) -> &str {
+        self.status.as_str()
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn in_error(&self) -> bool {
+        self.in_error
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn cancel_requested(&self) -> bool {
+        self.cancel_requested
+    }
+
+    pub fn start_on(&self) -> Option<DateTime<Utc>> {
+        self.start_on
+    }
+
+    pub fn end_on(&self) -> Option<DateTime<Utc>> {
+        self.end_on
+    }
+
+    pub fn retry_of(&self) -> Option<Uuid> {
+        self.retry_of
+    }
+
+    pub fn rerun_from(&self) -> Option<i32> {
+        self.rerun_from
+    }
+
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    pub fn builder_digest(&self) -> Option<&str> {
+        self.builder_digest.as_deref()
+    }
+
+    pub fn cached_from(&self) -> Option<Uuid> {
+        self.cached_from
+    }
+
+    pub async fn service_version_builds(service_version: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from FROM service_version_build WHERE service_version=$1 ORDER BY id")
+                .bind(service_version)
+        ).await
+    }
+
+    // the claiming worker holds a lease on the build and has to renew it while the build runs
+    pub async fn claim_next(from_status: &str, to_status: &str, worker: Uuid, lease: f64) -> Result<Option<Self>, DalError> {
+        let claimed = Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("UPDATE service_version_build SET status=$2, start_on=now(), worker=$3, lease_until=now() + make_interval(secs => $4) WHERE id=(SELECT id FROM service_version_build WHERE status=$1 ORDER BY id LIMIT 1 FOR UPDATE SKIP LOCKED) RETURNING id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from")
+                .bind(from_status)
+                .bind(to_status)
+                .bind(worker)
+                .bind(lease)
+        ).await?;
+        Ok(claimed.into_iter().next())
+    }
+
+    pub async fn renew_leases(worker: Uuid, builds: &[Uuid], lease: f64) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_build SET lease_until=now() + make_interval(secs => $3) WHERE worker=$1 AND id=ANY($2)")
+                .bind(worker)
+                .bind(builds)
+                .bind(lease)
+        ).await
+    }
+
+    // hand the builds whose lease expired over to the worker, so only one worker recovers each of them
+    pub async fn take_expired(status: &str, worker: Uuid, lease: f64) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("UPDATE service_version_build SET worker=$2, lease_until=now() + make_interval(secs => $3) WHERE status=$1 AND (lease_until IS NULL OR lease_until<now()) RETURNING id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from")
+                .bind(status)
+                .bind(worker)
+                .bind(lease)
+        ).await
+    }
+
+    pub async fn cancel_queued(id: Uuid, queued_status: &str, cancelled_status: &str, error_reason: &str) -> Result<Option<Self>, DalError> {
+        let cancelled = Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("UPDATE service_version_build SET status=$3, completed=true, in_error=true, error_reason=$4, end_on=now() WHERE id=$1 AND status=$2 RETURNING id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from")
+                .bind(id)
+                .bind(queued_status)
+                .bind(cancelled_status)
+                .bind(error_reason)
+        ).await?;
+        Ok(cancelled.into_iter().next())
+    }
+
+    pub async fn request_cancel(id: Uuid) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_build SET cancel_requested=true WHERE id=$1 AND NOT completed")
+                .bind(id)
+        ).await
+    }
+
+    pub async fn is_cancel_requested(id: Uuid) -> Result<bool, DalError> {
+        let r = Self::query_ctx().await.fetch_one(
+            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_build WHERE id=$1 AND cancel_requested")
+                .bind(id)
+        ).await;
+        Ok(r?.count > 0)
+    }
+
+    pub async fn cache_hit(commit: &str, builder_digest: &str, status: &str) -> Result<Option<Self>, DalError> {
+        let builds = Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from FROM service_version_build WHERE commit=$1 AND builder_digest=$2 AND status=$3 ORDER BY id DESC LIMIT 1")
+                .bind(commit)
+                .bind(builder_digest)
+                .bind(status)
+        ).await?;
+        Ok(builds.into_iter().next())
+    }
+
+    pub async fn queue_position(id: Uuid, status: &str) -> Result<i64, DalError> {
+        let r = Self::query_ctx().await.fetch_one(
+            sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_build WHERE status=$1 AND id<=$2")
+                .bind(status)
+                .bind(id)
+        ).await?;
+        Ok(r.count)
+    }
+}
+
+// a build is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceVersionBuildKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for ServiceVersionBuildKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for ServiceVersionBuild {
+    type Key = ServiceVersionBuildKey;
+
+    async fn read(key: ServiceVersionBuildKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceVersionBuildKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, status, completed, in_error, error_reason, cancel_requested, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from FROM service_version_build WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: ServiceVersionBuildKey) -> Result<bool, DalError> {
+        match key {
+            ServiceVersionBuildKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_build WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for ServiceVersionBuild {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO service_version_build(id, service_version, status, completed, in_error, error_reason, start_on, end_on, retry_of, rerun_from, commit, builder_digest, cached_from) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)")
+                .bind(self.id)
+                .bind(self.service_version)
+                .bind(self.status.as_str())
+                .bind(self.completed)
+                .bind(self.in_error)
+                .bind(self.error_reason.as_deref())
+                .bind(self.start_on)
+                .bind(self.end_on)
+                .bind(self.retry_of)
+                .bind(self.rerun_from)
+                .bind(self.commit.as_deref())
+                .bind(self.builder_digest.as_deref())
+                .bind(self.cached_from)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_build SET service_version=$1, status=$2, completed=$3, in_error=$4, error_reason=$5, start_on=$6, end_on=$7, commit=$8, builder_digest=$9 WHERE id=$10")
+                .bind(self.service_version)
+                .bind(self.status.as_str())
+                .bind(self.completed)
+                .bind(self.in_error)
+                .bind(self.error_reason.as_deref())
+                .bind(self.start_on)
+                .bind(self.end_on)
+                .bind(self.commit.as_deref())
+                .bind(self.builder_digest.as_deref())
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM service_version_build WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{has_attribute, key_i32, key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ServiceVersionBuildStage {
+    id: Uuid,
+    build: Uuid,
+    stage: i32,
+    name: String,
+    description: Option<String>,
+    stage_meta: Option<Value>,
+    status: Option<String>,
+    completed: bool,
+    in_error: bool,
+    error_reason: Option<String>,
+    logs_link: Option<String>,
+    start_on: DateTime<Utc>,
+    end_on: Option<DateTime<Utc>>,
+}
+
+impl ServiceVersionBuildStage {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(id: Uuid, build: Uuid, stage: i32, name: &str, description: Option<&str>, stage_meta: Option<Value>,
+               status: Option<&str>, completed: bool, in_error: bool, error_reason: Option<&str>, logs_link: Option<&str>,
+               start_on: DateTime<Utc>, end_on: Option<DateTime<Utc>>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            build,
+            stage,
+            name: String::from(name),
+            description: description.map(String::from),
+            stage_meta,
+            status: status.map(String::from),
+            completed,
+            in_error,
+            error_reason: error_reason.map(String::from),
+            logs_link: logs_link.map(String::from),
+            start_on,
+            end_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn build(&self) -> Uuid {
+        self.build
+    }
+
+    pub fn stage(&self) -> i32 {
+        self.stage
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn stage_meta(&self) -> Option<&Value> {
+        self.stage_meta.as_ref()
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn in_error(&self) -> bool {
+        self.in_error
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn logs_link(&self) -> Option<&str> {
+        self.logs_link.as_deref()
+    }
+
+    pub fn start_on(&self) -> DateTime<Utc> {
+        self.start_on
+    }
+
+    pub fn end_on(&self) -> Option<DateTime<Utc>> {
+        self.end_on
+    }
+
+    pub async fn build_stages(build: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, build, stage, name, description, stage_meta, status, completed, in_error, error_reason, logs_link, start_on, end_on FROM service_version_build_stage WHERE build=$1 ORDER BY stage")
+                .bind(build)
+        ).await
+    }
+}
+
+// a build stage is read by id or by its position in the build
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceVersionBuildStageKey {
+    Id(Uuid),
+    Natural { build: Uuid, stage: i32 },
+}
+
+impl TryFrom<Value> for ServiceVersionBuildStageKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural {
+                build: key_uuid(&key, "build")?,
+                stage: key_i32(&key, "stage")?,
+            })
+        }
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for ServiceVersionBuildStage {
+    type Key = ServiceVersionBuildStageKey;
+
+    async fn read(key: ServiceVersionBuildStageKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceVersionBuildStageKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, build, stage, name, description, stage_meta, status, completed, in_error, error_reason, logs_link, start_on, end_on FROM service_version_build_stage WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ServiceVersionBuildStageKey::Natural { build, stage } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, build, stage, name, description, stage_meta, status, completed, in_error, error_reason, logs_link, start_on, end_on FROM service_version_build_stage WHERE build=$1 AND stage=$2")
+                    .bind(build)
+                    .bind(stage)
+            ).await
+        }
+    }
+
+    async fn exists(key: ServiceVersionBuildStageKey) -> Result<bool, DalError> {
+        let r = match key {
+            ServiceVersionBuildStageKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_build_stage WHERE id=$1")
+                    .bind(id)
+            ).await,
+            ServiceVersionBuildStageKey::Natural { build, stage } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_build_stage WHERE build=$1 AND stage=$2")
+                    .bind(build)
+                    .bind(stage)
+            ).await
+        };
+        Ok(r?.count > 0)
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for ServiceVersionBuildStage {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO service_version_build_stage(id, build, stage, name, description, stage_meta, status, completed, in_error, error_reason, logs_link, start_on, end_on) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)")
+                .bind(self.id)
+                .bind(self.build)
+                .bind(self.stage)
+                .bind(self.name.as_str())
+                .bind(self.description.as_deref())
+                .bind(self.stage_meta.as_ref())
+                .bind(self.status.as_deref())
+                .bind(self.completed)
+                .bind(self.in_error)
+                .bind(self.error_reason.as_deref())
+                .bind(self.logs_link.as_deref())
+                .bind(self.start_on)
+                .bind(self.end_on)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_build_stage SET build=$1, stage=$2, name=$3, description=$4, stage_meta=$5, status=$6, completed=$7, in_error=$8, error_reason=$9, logs_link=$10, start_on=$11, end_on=$12 WHERE id=$13")
+                .bind(self.build)
+                .bind(self.stage)
+                .bind(self.name.as_str())
+                .bind(self.description.as_deref())
+                .bind(self.stage_meta.as_ref())
+                .bind(self.status.as_deref())
+                .bind(self.completed)
+                .bind(self.in_error)
+                .bind(self.error_reason.as_deref())
+                .bind(self.logs_link.as_deref())
+                .bind(self.start_on)
+                .bind(self.end_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM service_version_build_stage WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
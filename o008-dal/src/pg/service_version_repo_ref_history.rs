@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Postgres;
+use uuid::Uuid;
+use crate::{CommandContext, DalCount, DalError, DaoCommand, DaoQuery, gen_v7_uuid, QueryContext};
+use crate::pg::{key_uuid, PgDao};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ServiceVersionRepoRefHistory {
+    id: Uuid,
+    service_version: Uuid,
+    repo_ref: Uuid,
+    pinned_ref: Uuid,
+    pinned_on: DateTime<Utc>,
+}
+
+impl ServiceVersionRepoRefHistory {
+    pub fn new(id: Uuid, service_version: Uuid, repo_ref: Uuid, pinned_ref: Uuid, pinned_on: DateTime<Utc>) -> Self {
+        Self {
+            id: gen_v7_uuid(id),
+            service_version,
+            repo_ref,
+            pinned_ref,
+            pinned_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn repo_ref(&self) -> Uuid {
+        self.repo_ref
+    }
+
+    pub fn pinned_ref(&self) -> Uuid {
+        self.pinned_ref
+    }
+
+    pub fn pinned_on(&self) -> DateTime<Utc> {
+        self.pinned_on
+    }
+
+    pub async fn service_version_history(service_version: Uuid) -> Result<Vec<Self>, DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>("SELECT id, service_version, repo_ref, pinned_ref, pinned_on FROM service_version_repo_ref_history WHERE service_version=$1 ORDER BY id")
+                .bind(service_version)
+        ).await
+    }
+}
+
+// a repo reference history entry is only read by id
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceVersionRepoRefHistoryKey {
+    Id(Uuid),
+}
+
+impl TryFrom<Value> for ServiceVersionRepoRefHistoryKey {
+    type Error = DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        Ok(Self::Id(key_uuid(&key, "id")?))
+    }
+}
+
+#[async_trait]
+impl DaoQuery<PgDao, Postgres> for ServiceVersionRepoRefHistory {
+    type Key = ServiceVersionRepoRefHistoryKey;
+
+    async fn read(key: ServiceVersionRepoRefHistoryKey) -> Result<Box<Self>, DalError> {
+        match key {
+            ServiceVersionRepoRefHistoryKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, Self>("SELECT id, service_version, repo_ref, pinned_ref, pinned_on FROM service_version_repo_ref_history WHERE id=$1")
+                    .bind(id)
+            ).await
+        }
+    }
+
+    async fn exists(key: ServiceVersionRepoRefHistoryKey) -> Result<bool, DalError> {
+        match key {
+            ServiceVersionRepoRefHistoryKey::Id(id) => {
+                let r = Self::query_ctx().await.fetch_one(
+                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM service_version_repo_ref_history WHERE id=$1")
+                        .bind(id)
+                ).await;
+                Ok(r?.count > 0)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DaoCommand<PgDao, Postgres> for ServiceVersionRepoRefHistory {
+    async fn insert(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("INSERT INTO service_version_repo_ref_history(id, service_version, repo_ref, pinned_ref, pinned_on) VALUES ($1, $2, $3, $4, $5)")
+                .bind(self.id)
+                .bind(self.service_version)
+                .bind(self.repo_ref)
+                .bind(self.pinned_ref)
+                .bind(self.pinned_on)
+        ).await
+    }
+
+    async fn update(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("UPDATE service_version_repo_ref_history SET service_version=$1, repo_ref=$2, pinned_ref=$3, pinned_on=$4 WHERE id=$5")
+                .bind(self.service_version)
+                .bind(self.repo_ref)
+                .bind(self.pinned_ref)
+                .bind(self.pinned_on)
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete(&self) -> Result<(), DalError> {
+        Self::command_ctx().await.execute(
+            sqlx::query("DELETE FROM service_version_repo_ref_history WHERE id=$1")
+                .bind(self.id)
+        ).await
+    }
+}
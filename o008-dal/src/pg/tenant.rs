@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::Postgres;
 use uuid::Uuid;
-use crate::{QueryContext, error, CommandContext, DaoCommand, DaoQuery, DalCount, gen_v7_uuid};
-use crate::pg::{hard_check_key, PgDao, soft_check_key};
+use crate::{QueryContext, error, CommandContext, DaoCommand, DaoDependent, DaoQuery, DalCount, DalDependent, DalFilter, DalPage, gen_v7_uuid};
+use crate::pg::{delete_subtree, has_attribute, key_str, key_uuid, PgDao, service_version_subtree};
 
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
@@ -36,44 +36,65 @@ impl Tenant {
     }
 }
 
+// a tenant is read by id or by its unique name
+#[derive(Debug, Clone, PartialEq)]
+pub enum TenantKey {
+    Id(Uuid),
+    Natural { name: String },
+}
+
+impl TryFrom<Value> for TenantKey {
+    type Error = error::DalError;
+
+    fn try_from(key: Value) -> Result<Self, Self::Error> {
+        if has_attribute(&key, "id") {
+            Ok(Self::Id(key_uuid(&key, "id")?))
+        } else {
+            Ok(Self::Natural { name: key_str(&key, "name")? })
+        }
+    }
+}
+
 #[async_trait]
 impl DaoQuery<PgDao, Postgres> for Tenant {
-    async fn read(key: Value) -> Result<Box<Self>, error::DalError> {
-        if let Ok(id_key) = hard_check_key(&key, &["id"]) {
-            let id = id_key.first().unwrap();
-            Self::query_ctx().await.fetch_one(
+    type Key = TenantKey;
+
+    async fn read(key: TenantKey) -> Result<Box<Self>, error::DalError> {
+        match key {
+            TenantKey::Id(id) => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, Self>("SELECT id, name, coexisting FROM tenant WHERE id=$1")
-                    .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-            ).await
-        } else {
-            let name_key = hard_check_key(&key, &["name"])?;
-            let name = name_key.first().unwrap().as_str().unwrap();
-            Self::query_ctx().await.fetch_one(
+                    .bind(id)
+            ).await,
+            TenantKey::Natural { name } => Self::query_ctx().await.fetch_one(
                 sqlx::query_as::<_, Self>("SELECT id, name, coexisting FROM tenant WHERE name=$1")
                     .bind(name)
             ).await
         }
     }
 
-    async fn exists(key: Value) -> bool {
-        if let Ok(id_key) = soft_check_key(&key, &["id"]) {
-            if let Some(id) = id_key.first().unwrap() {
-                let r = Self::query_ctx().await.fetch_one(
-                    sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM tenant WHERE id=$1")
-                        .bind(Uuid::parse_str(id.as_str().unwrap()).unwrap())
-                ).await;
-                return r.unwrap().count > 0
-            } else if let Ok(name_key) = soft_check_key(&key, &["name"]) {
-                if let Some(name) = name_key.first().unwrap() {
-                    let r = Self::query_ctx().await.fetch_one(
-                        sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM tenant WHERE name=$1")
-                            .bind(name.as_str().unwrap())
-                    ).await;
-                    return r.unwrap().count > 0
-                }
-            }
-        }
-        false
+    async fn exists(key: TenantKey) -> Result<bool, error::DalError> {
+        let r = match key {
+            TenantKey::Id(id) => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM tenant WHERE id=$1")
+                    .bind(id)
+            ).await,
+            TenantKey::Natural { name } => Self::query_ctx().await.fetch_one(
+                sqlx::query_as::<_, DalCount>("SELECT COUNT(*) AS count FROM tenant WHERE name=$1")
+                    .bind(name)
+            ).await
+        };
+        Ok(r?.count > 0)
+    }
+
+    async fn list(filter: DalFilter, page: DalPage) -> Result<Vec<Self>, error::DalError> {
+        let (cursor, order) = page.keyset();
+        let query = format!("SELECT id, name, coexisting FROM tenant WHERE ($1::varchar IS NULL OR starts_with(name, $1)) AND ($2::uuid IS NULL OR id {} $2) ORDER BY id {} LIMIT $3", cursor, order);
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, Self>(&query)
+                .bind(filter.name())
+                .bind(page.after())
+                .bind(page.limit())
+        ).await
     }
 }
 
@@ -108,3 +129,25 @@ impl DaoCommand<PgDao, Postgres> for Tenant {
         ).await
     }
 }
+
+#[async_trait]
+impl DaoDependent<PgDao, Postgres> for Tenant {
+    async fn dependents(&self) -> Result<Vec<DalDependent>, error::DalError> {
+        Self::query_ctx().await.fetch_all(
+            sqlx::query_as::<_, DalDependent>("SELECT 'application' AS kind, id, name FROM application WHERE tenant = $1 UNION ALL SELECT 'environment' AS kind, id, name FROM environment WHERE tenant = $1 UNION ALL SELECT 'deployment_lock' AS kind, id, locked_by AS name FROM deployment_lock WHERE tenant = $1 UNION ALL SELECT 'freeze_window' AS kind, id, name FROM freeze_window WHERE tenant = $1 UNION ALL SELECT 'lock_override' AS kind, id, overridden_by AS name FROM lock_override WHERE tenant = $1")
+                .bind(self.id)
+        ).await
+    }
+
+    async fn delete_cascade(&self) -> Result<(), error::DalError> {
+        let mut deletes = service_version_subtree("SELECT sv.id FROM service_version sv JOIN service s ON s.id = sv.service JOIN application a ON a.id = s.application WHERE a.tenant = $1");
+        deletes.push(String::from("DELETE FROM deployment_lock WHERE tenant = $1"));
+        deletes.push(String::from("DELETE FROM freeze_window WHERE tenant = $1"));
+        deletes.push(String::from("DELETE FROM lock_override WHERE tenant = $1"));
+        deletes.push(String::from("DELETE FROM environment WHERE tenant = $1"));
+        deletes.push(String::from("DELETE FROM service WHERE application IN (SELECT id FROM application WHERE tenant = $1)"));
+        deletes.push(String::from("DELETE FROM application WHERE tenant = $1"));
+        deletes.push(String::from("DELETE FROM tenant WHERE id = $1"));
+        delete_subtree(&deletes, self.id).await
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::{Debug, Display, Formatter};
 use o008_dal::DalError;
+use crate::Dependent;
 
 #[derive(Debug)]
 pub enum EntityError {
@@ -8,6 +9,8 @@ pub enum EntityError {
     UnPersisted(String),
     WrongQuery(String),
     NotFound(String),
+    Query(DalError),
+    Dependents(Vec<Dependent>),
 }
 
 impl Display for EntityError {
@@ -17,7 +20,9 @@ impl Display for EntityError {
             EntityError::Destroy(e) => write!(f, "could not destroy entity: {}", e),
             EntityError::UnPersisted(s) => write!(f, "entity {} has not been persisted", s),
             EntityError::WrongQuery(s) => write!(f, "{}", s),
-            EntityError::NotFound(s) => write!(f, "{}", s)
+            EntityError::NotFound(s) => write!(f, "{}", s),
+            EntityError::Query(e) => write!(f, "could not query entity: {}", e),
+            EntityError::Dependents(d) => write!(f, "entity is referenced by {} dependents", d.len())
         }
     }
 }
@@ -6,17 +6,85 @@ use async_trait::async_trait;
 use serde::Serialize;
 use serde_json::Value;
 use sqlx::{Database};
-use o008_dal::{CommandContext, DaoCommand, DaoQuery, QueryContext};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::ListRequest;
+use o008_dal::{CommandContext, DalDependent, DalPage, DaoCommand, DaoDependent, DaoQuery, IntoDaoKey, QueryContext};
 
 pub use error::EntityError;
 pub use pg::Application;
 pub use pg::Builder;
+pub use pg::{Deployment, DeploymentLock, DeploymentSlot, FreezeWindow, LockOverride};
+pub use pg::Environment;
+pub use pg::{Release, ReleaseApproval};
 pub use pg::RepoReference;
 pub use pg::Service;
 pub use pg::{ServiceVersion, ServiceVersionItem};
+pub use pg::{ServiceVersionBuild, ServiceVersionBuildStage};
+pub use pg::ServiceVersionRepoRefHistory;
+pub use pg::ServiceVersionArtifact;
 pub use pg::Tenant;
+pub use o008_dal::DalFilter;
+pub use o008_dal::pg::{ApplicationKey, BuilderKey, DeploymentKey, DeploymentLockKey, DeploymentSlotKey, EnvironmentKey, FreezeWindowKey, LockOverrideKey, ReleaseApprovalKey, ReleaseKey,
+                       RepoReferenceKey, ServiceKey, ServiceVersionArtifactKey, ServiceVersionBuildKey, ServiceVersionBuildStageKey, ServiceVersionKey,
+                       ServiceVersionRepoRefHistoryKey, TenantKey};
 
 
+// a page of entities with the id to list the next page after, none on the last page
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(TenantPage = EntityPage<Tenant>, ApplicationPage = EntityPage<Application>, BuilderPage = EntityPage<Builder>, ServicePage = EntityPage<Service>, ServiceVersionPage = EntityPage<ServiceVersionItem>)]
+pub struct EntityPage<E> {
+    items: Vec<E>,
+    next: Option<Uuid>,
+}
+
+impl<E> EntityPage<E> {
+    // the items are read with one more row than the limit to know if a next page exists
+    fn new(mut items: Vec<E>, limit: i64, id: fn(&E) -> Uuid) -> Self {
+        let next = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(id)
+        } else {
+            None
+        };
+        Self {
+            items,
+            next,
+        }
+    }
+
+    pub fn items(&self) -> &Vec<E> {
+        &self.items
+    }
+
+    pub fn next(&self) -> Option<Uuid> {
+        self.next
+    }
+}
+
+// an item referencing the entity to destroy
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Dependent {
+    kind: String,
+    #[serde(rename(serialize = "_id"))]
+    id: Uuid,
+    name: String,
+}
+
+impl From<DalDependent> for Dependent {
+    fn from(value: DalDependent) -> Self {
+        Self {
+            kind: value.kind,
+            id: value.id,
+            name: value.name,
+        }
+    }
+}
+
+fn dal_page(lrq: &ListRequest) -> DalPage {
+    DalPage::new(lrq.after(), lrq.limit() + 1, lrq.sort())
+}
+
 pub trait Entity<T>
     where T: Send + Unpin + Sized {
     fn dao(&self) -> Box<T>;
@@ -27,9 +95,15 @@ pub trait QueryEntity<T, Q, DB>: Entity<T>
     where T: DaoQuery<Q, DB> + Send + Unpin + Sized,
           Q: QueryContext<DB>,
           DB: Database {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError>;
+    // the query is the typed key of the dao or its json form
+    async fn read<K: IntoDaoKey<T::Key>>(qry: K) -> Result<Box<Self>, EntityError>;
 
-    async fn persisted(qry: Value) -> bool;
+    async fn persisted<K: IntoDaoKey<T::Key>>(qry: K) -> Result<bool, EntityError>;
+}
+
+// a malformed query is wrong before reaching the dao
+fn dal_key<K: IntoDaoKey<Key>, Key>(qry: K) -> Result<Key, EntityError> {
+    qry.into_key().map_err(|e| EntityError::WrongQuery(e.to_string()))
 }
 
 #[async_trait]
@@ -50,6 +124,44 @@ pub trait DestroyEntity<T, C, DB>: Entity<T>
     async fn destroy(&self) -> Result<(), EntityError>;
 }
 
+#[async_trait]
+pub trait DependentEntity<T, C, DB>: DestroyEntity<T, C, DB> + Sync
+    where T: DaoDependent<C, DB> + Send + Sync + Unpin + Sized,
+          C: CommandContext<DB>,
+          DB: Database {
+
+    async fn dependents(&self) -> Result<Vec<Dependent>, EntityError> {
+        match self.dao().dependents().await {
+            Ok(d) => Ok(d.into_iter().map(Dependent::from).collect()),
+            Err(e) => Err(EntityError::Destroy(e))
+        }
+    }
+
+    async fn destroy_cascade(&self) -> Result<(), EntityError> {
+        match self.dao().delete_cascade().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(EntityError::Destroy(e))
+        }
+    }
+}
+
+// without cascade the entity is only destroyed when nothing references it
+pub async fn destroy_checked<E, T, C, DB>(entity: &E, cascade: bool) -> Result<(), EntityError>
+    where E: DependentEntity<T, C, DB>,
+          T: DaoDependent<C, DB> + Send + Sync + Unpin + Sized,
+          C: CommandContext<DB>,
+          DB: Database {
+    if cascade {
+        return entity.destroy_cascade().await
+    }
+    let dependents = entity.dependents().await?;
+    if dependents.is_empty() {
+        entity.destroy().await
+    } else {
+        Err(EntityError::Dependents(dependents))
+    }
+}
+
 pub async fn persist_json<E, T, C, DB>(entity: &E) -> Result<Value, EntityError>
     where E: PersistEntity<T, C, DB> + Serialize,
           T: DaoCommand<C, DB> + Send + Unpin + Sized,
@@ -1,14 +1,13 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use sqlx::Postgres;
 use utoipa::ToSchema;
 use uuid::Uuid;
-use o008_common::{AsyncFrom};
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use o008_dal::pg::{PgDao};
-use crate::{DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity, Tenant};
-use crate::pg::tenant::TenantDao;
+use o008_common::{ListRequest, TryAsyncFrom};
+use o008_dal::{DalError, DalFilter, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, ApplicationKey, TenantKey};
+use crate::{dal_key, dal_page, DependentEntity, DestroyEntity, Entity, EntityError, EntityPage, PersistEntity, QueryEntity, Tenant};
 
 pub(crate) type ApplicationDao = o008_dal::pg::Application;
 
@@ -20,6 +19,8 @@ pub struct Application {
     tenant: Tenant,
     class_unit: String,
     functional_group: String,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl Application {
@@ -30,16 +31,20 @@ impl Application {
             tenant: t,
             class_unit: String::from(cu),
             functional_group: String::from(fg),
+            archived_at: None,
+            archived_by: None,
         }
     }
 
-    pub fn load(id: Uuid, name: &str, t: Tenant, cu: &str, fg: &str) -> Self {
+    pub fn load(id: Uuid, name: &str, t: Tenant, cu: &str, fg: &str, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id,
             name: String::from(name),
             tenant: t,
             class_unit: String::from(cu),
             functional_group: String::from(fg),
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -62,19 +67,58 @@ impl Application {
     pub fn functional_group(&self) -> &str {
         &self.functional_group
     }
+
+    pub async fn list(filter: DalFilter, lrq: &ListRequest) -> Result<EntityPage<Self>, EntityError> {
+        match ApplicationDao::list(filter, dal_page(lrq)).await {
+            Ok(applications) => {
+                let mut v: Vec<Self> = Vec::new();
+                for a in applications {
+                    v.push(TryAsyncFrom::<ApplicationDao>::try_from(a).await?)
+                }
+                Ok(EntityPage::new(v, lrq.limit(), Self::id))
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
+                _ => Err(EntityError::NotFound(e.to_string())),
+            }
+        }
+    }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    // an archived application hides its services from the reads by name and the lists until it is restored
+    pub fn archive(&mut self, archived_by: &str) {
+        self.archived_at = Some(Utc::now());
+        self.archived_by = Some(String::from(archived_by));
+    }
+
+    pub fn restore(&mut self) {
+        self.archived_at = None;
+        self.archived_by = None;
+    }
 }
 
 impl Entity<ApplicationDao> for Application {
     fn dao(&self) -> Box<ApplicationDao> {
-        Box::new(ApplicationDao::new(self.id, &self.name, self.tenant.dao().id(), &self.class_unit, &self.functional_group))
+        Box::new(ApplicationDao::new(self.id, &self.name, self.tenant.dao().id(), &self.class_unit, &self.functional_group, self.archived_at, self.archived_by.as_deref()))
     }
 }
 
 #[async_trait]
 impl QueryEntity<ApplicationDao, PgDao, Postgres> for Application {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match ApplicationDao::read(qry).await {
-            Ok(app) => Ok(Box::new(AsyncFrom::<ApplicationDao>::from(*app).await)),
+    async fn read<K: IntoDaoKey<ApplicationKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ApplicationDao::read(dal_key(qry)?).await {
+            Ok(app) => Ok(Box::new(TryAsyncFrom::<ApplicationDao>::try_from(*app).await?)),
             Err(e) => match e {
                 DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
                 _ => Err(EntityError::NotFound(e.to_string())),
@@ -82,8 +126,8 @@ impl QueryEntity<ApplicationDao, PgDao, Postgres> for Application {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        ApplicationDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<ApplicationKey>>(qry: K) -> Result<bool, EntityError> {
+        ApplicationDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -104,6 +148,8 @@ impl PersistEntity<ApplicationDao, PgDao, Postgres> for Application {
                     tenant: self.tenant.clone(),
                     class_unit: String::from(&self.class_unit),
                     functional_group: String::from(&self.functional_group),
+                    archived_at: self.archived_at,
+                    archived_by: self.archived_by.clone(),
                 }))
             },
             Err(e) => Err(EntityError::Persist(e))
@@ -125,10 +171,14 @@ impl DestroyEntity<ApplicationDao, PgDao, Postgres> for Application {
     }
 }
 
+impl DependentEntity<ApplicationDao, PgDao, Postgres> for Application {}
+
 #[async_trait]
-impl AsyncFrom<ApplicationDao> for Application {
-    async fn from(value: ApplicationDao) -> Self {
-        let td = TenantDao::read(json!({"id": value.tenant().to_string()})).await.unwrap();
-        Self::load(value.id(), value.name(), From::<TenantDao>::from(*td), value.class_unit(), value.functional_group())
+impl TryAsyncFrom<ApplicationDao> for Application {
+    type Error = EntityError;
+
+    async fn try_from(value: ApplicationDao) -> Result<Self, EntityError> {
+        let td = Tenant::read(TenantKey::Id(value.tenant())).await?;
+        Ok(Self::load(value.id(), value.name(), *td, value.class_unit(), value.functional_group(), value.archived_at(), value.archived_by()))
     }
 }
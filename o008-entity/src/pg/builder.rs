@@ -1,13 +1,12 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use sqlx::Postgres;
 use utoipa::ToSchema;
 use uuid::Uuid;
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use crate::{DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
-use o008_common::{AsyncFrom, BuilderRequest};
-use o008_dal::pg::{PgDao};
+use o008_dal::{DalError, DalFilter, DaoCommand, DaoQuery, IntoDaoKey};
+use crate::{dal_key, dal_page, DependentEntity, DestroyEntity, Entity, EntityError, EntityPage, PersistEntity, QueryEntity};
+use o008_common::{BuilderRequest, BuilderStage, ListRequest, TryAsyncFrom};
+use o008_dal::pg::{PgDao, BuilderKey};
 
 type BuilderDao = o008_dal::pg::Builder;
 
@@ -17,25 +16,31 @@ pub struct Builder {
     id: Uuid,
     name: String,
     active: bool,
-    build_command: String,
+    stages: Vec<BuilderStage>,
+    timeout: Option<i32>,
+    artifacts: Vec<String>,
 }
 
 impl Builder {
-    pub fn new(name: &str, active: bool, build_command: &str) -> Self {
+    pub fn new(name: &str, active: bool, stages: Vec<BuilderStage>, timeout: Option<i32>, artifacts: Vec<String>) -> Self {
         Self {
             id: Uuid::nil(),
             name: String::from(name),
             active,
-            build_command: String::from(build_command)
+            stages,
+            timeout,
+            artifacts
         }
     }
 
-    pub fn load(id: Uuid, name: &str, active: bool, build_command: &str) -> Self {
+    pub fn load(id: Uuid, name: &str, active: bool, stages: Vec<BuilderStage>, timeout: Option<i32>, artifacts: Vec<String>) -> Self {
         Self {
             id,
             name: String::from(name),
             active,
-            build_command: String::from(build_command)
+            stages,
+            timeout,
+            artifacts
         }
     }
 
@@ -51,22 +56,41 @@ impl Builder {
         self.active
     }
 
-    pub fn build_command(&self) -> &str {
-        self.build_command.as_str()
+    pub fn stages(&self) -> &Vec<BuilderStage> {
+        &self.stages
+    }
+
+    pub fn timeout(&self) -> Option<i32> {
+        self.timeout
+    }
+
+    pub fn artifacts(&self) -> &Vec<String> {
+        &self.artifacts
+    }
+
+    pub async fn list(filter: DalFilter, lrq: &ListRequest) -> Result<EntityPage<Self>, EntityError> {
+        match BuilderDao::list(filter, dal_page(lrq)).await {
+            Ok(builders) => Ok(EntityPage::new(builders.into_iter().map(From::<BuilderDao>::from).collect(), lrq.limit(), Self::id)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
+                _ => Err(EntityError::NotFound(e.to_string())),
+            }
+        }
     }
 }
 
 #[async_trait]
 impl Entity<BuilderDao> for Builder {
     fn dao(&self) -> Box<BuilderDao> {
-        Box::new(BuilderDao::new(self.id, &self.name, self.active, &self.build_command))
+        Box::new(BuilderDao::new(self.id, &self.name, self.active, serde_json::to_value(&self.stages).unwrap(), self.timeout,
+                              serde_json::to_value(&self.artifacts).unwrap()))
     }
 }
 
 #[async_trait]
 impl QueryEntity<BuilderDao, PgDao, Postgres> for Builder {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match BuilderDao::read(qry).await {
+    async fn read<K: IntoDaoKey<BuilderKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match BuilderDao::read(dal_key(qry)?).await {
             Ok(b) => Ok(Box::new(From::<BuilderDao>::from(*b))),
             Err(e) => match e {
                 DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
@@ -75,8 +99,8 @@ impl QueryEntity<BuilderDao, PgDao, Postgres> for Builder {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        BuilderDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<BuilderKey>>(qry: K) -> Result<bool, EntityError> {
+        BuilderDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -95,7 +119,9 @@ impl PersistEntity<BuilderDao, PgDao, Postgres> for Builder {
                     id: dao.id(),
                     name: String::from(&self.name),
                     active: self.active,
-                    build_command: String::from(&self.build_command),
+                    stages: self.stages.clone(),
+                    timeout: self.timeout,
+                    artifacts: self.artifacts.clone(),
                 }))
             },
             Err(e) => Err(EntityError::Persist(e))
@@ -117,21 +143,28 @@ impl DestroyEntity<BuilderDao, PgDao, Postgres> for Builder {
     }
 }
 
+impl DependentEntity<BuilderDao, PgDao, Postgres> for Builder {}
+
 impl From<BuilderDao> for Builder {
     fn from(value: BuilderDao) -> Self {
-        Self::load(value.id(), value.name(), value.active(), value.build_command())
+        let stages = serde_json::from_value(value.stages().clone()).unwrap_or_default();
+        let artifacts = serde_json::from_value(value.artifacts().clone()).unwrap_or_default();
+        Self::load(value.id(), value.name(), value.active(), stages, value.timeout(), artifacts)
     }
 }
 
 impl From<BuilderRequest> for Builder {
     fn from(value: BuilderRequest) -> Self {
-        Self::new(value.name(), value.active(), value.build_command())
+        Self::new(value.name(), value.active(), value.stages(), value.timeout(), value.artifacts())
     }
 }
 
 #[async_trait]
-impl AsyncFrom<BuilderRequest> for Builder {
-    async fn from(value: BuilderRequest) -> Self {
-        *Builder::read(serde_json::to_value(value).unwrap()).await.unwrap()
+impl TryAsyncFrom<BuilderRequest> for Builder {
+    type Error = EntityError;
+
+    async fn try_from(value: BuilderRequest) -> Result<Self, EntityError> {
+        let qry = serde_json::to_value(value).map_err(|e| EntityError::WrongQuery(e.to_string()))?;
+        Ok(*Builder::read(qry).await?)
     }
 }
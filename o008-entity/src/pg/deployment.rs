@@ -0,0 +1,433 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{DeploymentStatus, TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, DeploymentKey, EnvironmentKey, ServiceVersionKey};
+use crate::{dal_key, DeploymentSlot, DestroyEntity, Entity, EntityError, Environment, PersistEntity, QueryEntity, ServiceVersion};
+
+type DeploymentDao = o008_dal::pg::Deployment;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Deployment {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    service_version: ServiceVersion,
+    environment: Environment,
+    status: DeploymentStatus,
+    deployed_by: String,
+    previous_version: Option<Uuid>,
+    command: Option<String>,
+    error_reason: Option<String>,
+    logs_link: Option<String>,
+    started_on: DateTime<Utc>,
+    finished_on: Option<DateTime<Utc>>,
+    rollback_of: Option<Uuid>,
+    slot: Option<String>,
+}
+
+impl Deployment {
+    pub fn new(service_version: ServiceVersion, environment: Environment, deployed_by: &str, previous_version: Option<Uuid>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            service_version,
+            environment,
+            status: DeploymentStatus::Running,
+            deployed_by: String::from(deployed_by),
+            previous_version,
+            command: None,
+            error_reason: None,
+            logs_link: None,
+            started_on: Utc::now(),
+            finished_on: None,
+            rollback_of: None,
+            slot: None,
+        }
+    }
+
+    // redeploy the version of a previous deployment in place of the current one
+    pub fn rollback(current: &Deployment, target: &Deployment, deployed_by: &str) -> Self {
+        let mut deployment = Self::new(target.service_version.clone(), current.environment.clone(), deployed_by, Some(current.service_version.id()));
+        deployment.rollback_of = Some(current.id);
+        deployment
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn load(id: Uuid, service_version: ServiceVersion, environment: Environment, status: DeploymentStatus, deployed_by: &str,
+                previous_version: Option<Uuid>, command: Option<&str>, error_reason: Option<&str>, logs_link: Option<&str>,
+                started_on: DateTime<Utc>, finished_on: Option<DateTime<Utc>>, rollback_of: Option<Uuid>, slot: Option<&str>) -> Self {
+        Self {
+            id,
+            service_version,
+            environment,
+            status,
+            deployed_by: String::from(deployed_by),
+            previous_version,
+            command: command.map(String::from),
+            error_reason: error_reason.map(String::from),
+            logs_link: logs_link.map(String::from),
+            started_on,
+            finished_on,
+            rollback_of,
+            slot: slot.map(String::from),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> &ServiceVersion {
+        &self.service_version
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn status(&self) -> DeploymentStatus {
+        self.status
+    }
+
+    pub fn deployed_by(&self) -> &str {
+        &self.deployed_by
+    }
+
+    pub fn previous_version(&self) -> Option<Uuid> {
+        self.previous_version
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn logs_link(&self) -> Option<&str> {
+        self.logs_link.as_deref()
+    }
+
+    pub fn started_on(&self) -> DateTime<Utc> {
+        self.started_on
+    }
+
+    pub fn finished_on(&self) -> Option<DateTime<Utc>> {
+        self.finished_on
+    }
+
+    pub fn rollback_of(&self) -> Option<Uuid> {
+        self.rollback_of
+    }
+
+    pub fn slot(&self) -> Option<&str> {
+        self.slot.as_deref()
+    }
+
+    pub fn set_slot(&mut self, slot: &str) {
+        self.slot = Some(String::from(slot))
+    }
+
+    pub fn set_command(&mut self, command: &str) {
+        self.command = Some(String::from(command))
+    }
+
+    pub fn set_logs_link(&mut self, logs_link: &str) {
+        self.logs_link = Some(String::from(logs_link))
+    }
+
+    pub fn finish(&mut self, error_reason: Option<&str>) {
+        self.status = if error_reason.is_some() { DeploymentStatus::Failed } else { DeploymentStatus::Succeeded };
+        self.error_reason = error_reason.map(String::from);
+        self.finished_on = Some(Utc::now());
+    }
+
+    // the deployment finished successfully at least the given minutes ago
+    pub fn soaked(&self, minutes: i32) -> bool {
+        self.status == DeploymentStatus::Succeeded &&
+            self.finished_on.is_some_and(|f| f + Duration::minutes(minutes as i64) <= Utc::now())
+    }
+
+    pub async fn service_deployments(service: Uuid, environment: Uuid) -> Result<Vec<Self>, EntityError> {
+        match DeploymentDao::service_deployments(service, environment).await {
+            Ok(deployments) => {
+                let mut v: Vec<Self> = Vec::new();
+                for d in deployments {
+                    v.push(TryAsyncFrom::<DeploymentDao>::try_from(d).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    // the successful deployments running the services of a tenant, one per service and environment
+    pub async fn running(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match DeploymentDao::running_deployments(tenant, DeploymentStatus::Succeeded.to_string().as_str()).await {
+            Ok(deployments) => {
+                let mut v: Vec<Self> = Vec::new();
+                for d in deployments {
+                    v.push(TryAsyncFrom::<DeploymentDao>::try_from(d).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    // the deployment of the active slot is the version running in the environment, without slots the last successful deployment
+    pub async fn current(service: Uuid, environment: Uuid) -> Result<Option<Self>, EntityError> {
+        let active = Self::active(service, environment).await?;
+        let deployments = Self::service_deployments(service, environment).await?;
+        Ok(deployments.into_iter().find(|d| d.status == DeploymentStatus::Succeeded && active.is_none_or(|id| d.id == id)))
+    }
+
+    // the current deployment and the last successful one before it with another version, skipping the deployments already rolled back
+    pub async fn rollback_target(service: Uuid, environment: Uuid) -> Result<Option<(Self, Self)>, EntityError> {
+        let active = Self::active(service, environment).await?;
+        Ok(Self::rollback_pair(Self::service_deployments(service, environment).await?, active))
+    }
+
+    // the deployment served by the active slot, none without slots
+    async fn active(service: Uuid, environment: Uuid) -> Result<Option<Uuid>, EntityError> {
+        Ok(DeploymentSlot::service_slots(service, environment).await?
+            .into_iter()
+            .find(|s| s.active())
+            .map(|s| s.deployment()))
+    }
+
+    // the deployments are the newest first, with an active slot the current deployment is the one it serves and the target ran on the other slot
+    fn rollback_pair(deployments: Vec<Self>, active: Option<Uuid>) -> Option<(Self, Self)> {
+        let succeeded: Vec<Self> = deployments
+            .into_iter()
+            .filter(|d| d.status == DeploymentStatus::Succeeded)
+            .collect();
+        let rolled_back: Vec<Uuid> = succeeded.iter().filter_map(|d| d.rollback_of).collect();
+        let mut deployments = succeeded.into_iter().skip_while(|d| active.is_some_and(|id| d.id != id));
+        deployments.next().and_then(|current| {
+            deployments
+                .find(|d| d.service_version.id() != current.service_version.id() && !rolled_back.contains(&d.id) &&
+                    (active.is_none() || d.slot != current.slot))
+                .map(|target| (current, target))
+        })
+    }
+}
+
+impl Entity<DeploymentDao> for Deployment {
+    fn dao(&self) -> Box<DeploymentDao> {
+        Box::new(DeploymentDao::new(
+            self.id,
+            self.service_version.id(),
+            self.environment.id(),
+            self.status.to_string().as_str(),
+            self.deployed_by.as_str(),
+            self.previous_version,
+            self.command.as_deref(),
+            self.error_reason.as_deref(),
+            self.logs_link.as_deref(),
+            self.started_on,
+            self.finished_on,
+            self.rollback_of,
+            self.slot.as_deref()
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<DeploymentDao, PgDao, Postgres> for Deployment {
+    async fn read<K: IntoDaoKey<DeploymentKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match DeploymentDao::read(dal_key(qry)?).await {
+            Ok(deployment) => Ok(Box::new(TryAsyncFrom::<DeploymentDao>::try_from(*deployment).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<DeploymentKey>>(qry: K) -> Result<bool, EntityError> {
+        DeploymentDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<DeploymentDao, PgDao, Postgres> for Deployment {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut deployment = self.clone();
+                deployment.id = dao.id();
+                Ok(Box::new(deployment))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<DeploymentDao, PgDao, Postgres> for Deployment {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const DEPLOYMENT_TYPE_INFO: &str = "Deployment";
+
+impl TypeInfo for Deployment {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<DeploymentDao> for Deployment {
+    type Error = EntityError;
+
+    async fn try_from(value: DeploymentDao) -> Result<Self, EntityError> {
+        let service_version = ServiceVersion::read(ServiceVersionKey::Id(value.service_version())).await?;
+        let environment = Environment::read(EnvironmentKey::Id(value.environment())).await?;
+        Ok(Self::load(
+            value.id(),
+            *service_version,
+            *environment,
+            value.status().parse().unwrap_or(DeploymentStatus::Failed),
+            value.deployed_by(),
+            value.previous_version(),
+            value.command(),
+            value.error_reason(),
+            value.logs_link(),
+            value.started_on(),
+            value.finished_on(),
+            value.rollback_of(),
+            value.slot()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use o008_common::RepoReferenceKind;
+    use crate::{Application, Builder, RepoReference, Service, Tenant};
+    use super::*;
+
+    fn version(id: u128) -> ServiceVersion {
+        let application = Application::new("shop", Tenant::new("acme", false), "cu", "fg");
+        let service = Service::new("cart", application, "file:///tmp/repo");
+        let repo_ref = RepoReference::new("file:///tmp/repo", RepoReferenceKind::Branch, "main");
+        ServiceVersion::load(Uuid::from_u128(id), &format!("{}.0.0", id), service, repo_ref, Builder::new("sh", true, Vec::new(), None, Vec::new()), None, None)
+    }
+
+    fn deployment(id: u128, version_id: u128, status: DeploymentStatus, rollback_of: Option<u128>) -> Deployment {
+        let environment = Environment::new("prod", Tenant::new("acme", false), 0);
+        Deployment::load(Uuid::from_u128(id), version(version_id), environment, status, "ops", None, None, None, None,
+                         Utc::now(), None, rollback_of.map(Uuid::from_u128), None)
+    }
+
+    fn slotted(id: u128, version_id: u128, slot: &str) -> Deployment {
+        let mut deployment = deployment(id, version_id, DeploymentStatus::Succeeded, None);
+        deployment.set_slot(slot);
+        deployment
+    }
+
+    fn ids(pair: Option<(Deployment, Deployment)>) -> Option<(Uuid, Uuid)> {
+        pair.map(|(current, target)| (current.id(), target.id()))
+    }
+
+    #[test]
+    fn rollback_to_the_previous_version() {
+        let deployments = vec![
+            deployment(3, 2, DeploymentStatus::Succeeded, None),
+            deployment(2, 2, DeploymentStatus::Succeeded, None),
+            deployment(1, 1, DeploymentStatus::Succeeded, None),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, None)), Some((Uuid::from_u128(3), Uuid::from_u128(1))));
+    }
+
+    #[test]
+    fn rollback_skips_the_unsuccessful_deployments() {
+        let deployments = vec![
+            deployment(4, 3, DeploymentStatus::Failed, None),
+            deployment(3, 2, DeploymentStatus::Succeeded, None),
+            deployment(2, 1, DeploymentStatus::Failed, None),
+            deployment(1, 4, DeploymentStatus::Succeeded, None),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, None)), Some((Uuid::from_u128(3), Uuid::from_u128(1))));
+    }
+
+    #[test]
+    fn rollback_skips_the_deployments_already_rolled_back() {
+        // 3 rolled 2 back to version 1, the next rollback goes to version 0 instead of returning to 2
+        let deployments = vec![
+            deployment(3, 1, DeploymentStatus::Succeeded, Some(2)),
+            deployment(2, 2, DeploymentStatus::Succeeded, None),
+            deployment(1, 1, DeploymentStatus::Succeeded, None),
+            deployment(0, 0, DeploymentStatus::Succeeded, None),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, None)), Some((Uuid::from_u128(3), Uuid::from_u128(0))));
+    }
+
+    #[test]
+    fn no_rollback_without_another_version() {
+        assert_eq!(ids(Deployment::rollback_pair(Vec::new(), None)), None);
+        let deployments = vec![
+            deployment(2, 1, DeploymentStatus::Succeeded, None),
+            deployment(1, 1, DeploymentStatus::Succeeded, None),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, None)), None);
+    }
+
+    #[test]
+    fn rollback_from_the_active_slot_to_the_other_one() {
+        // 4 waits in the idle slot, the active slot serves 3 and the rollback goes to 2 on the other slot, never to 1 on the same one
+        let deployments = vec![
+            slotted(4, 3, "green"),
+            slotted(3, 2, "blue"),
+            slotted(2, 1, "blue"),
+            slotted(1, 0, "green"),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, Some(Uuid::from_u128(3)))), Some((Uuid::from_u128(3), Uuid::from_u128(1))));
+        let deployments = vec![
+            slotted(4, 3, "green"),
+            slotted(3, 2, "blue"),
+            slotted(2, 1, "green"),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, Some(Uuid::from_u128(3)))), Some((Uuid::from_u128(3), Uuid::from_u128(2))));
+    }
+
+    #[test]
+    fn no_rollback_without_a_deployment_on_the_other_slot() {
+        let deployments = vec![
+            slotted(2, 2, "blue"),
+            slotted(1, 1, "blue"),
+        ];
+        assert_eq!(ids(Deployment::rollback_pair(deployments, Some(Uuid::from_u128(2)))), None);
+    }
+}
@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, DeploymentLockKey, EnvironmentKey, TenantKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, Environment, PersistEntity, QueryEntity, Tenant};
+
+type DeploymentLockDao = o008_dal::pg::DeploymentLock;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeploymentLock {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    tenant: Tenant,
+    environment: Option<Environment>,
+    locked_by: String,
+    reason: String,
+    locked_on: DateTime<Utc>,
+    expires_on: Option<DateTime<Utc>>,
+}
+
+impl DeploymentLock {
+    pub fn new(tenant: Tenant, environment: Option<Environment>, locked_by: &str, reason: &str, expires_on: Option<DateTime<Utc>>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            tenant,
+            environment,
+            locked_by: String::from(locked_by),
+            reason: String::from(reason),
+            locked_on: Utc::now(),
+            expires_on,
+        }
+    }
+
+    pub fn load(id: Uuid, tenant: Tenant, environment: Option<Environment>, locked_by: &str, reason: &str, locked_on: DateTime<Utc>,
+                expires_on: Option<DateTime<Utc>>) -> Self {
+        Self {
+            id,
+            tenant,
+            environment,
+            locked_by: String::from(locked_by),
+            reason: String::from(reason),
+            locked_on,
+            expires_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    pub fn locked_by(&self) -> &str {
+        &self.locked_by
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub fn locked_on(&self) -> DateTime<Utc> {
+        self.locked_on
+    }
+
+    pub fn expires_on(&self) -> Option<DateTime<Utc>> {
+        self.expires_on
+    }
+
+    // a lock without expiry holds until it is removed
+    pub fn is_active(&self) -> bool {
+        self.expires_on.is_none_or(|e| e > Utc::now())
+    }
+
+    pub async fn tenant_locks(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match DeploymentLockDao::tenant_locks(tenant).await {
+            Ok(locks) => {
+                let mut v: Vec<Self> = Vec::new();
+                for l in locks {
+                    v.push(TryAsyncFrom::<DeploymentLockDao>::try_from(l).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<DeploymentLockDao> for DeploymentLock {
+    fn dao(&self) -> Box<DeploymentLockDao> {
+        Box::new(DeploymentLockDao::new(
+            self.id,
+            self.tenant.id(),
+            self.environment.as_ref().map(|e| e.id()),
+            self.locked_by.as_str(),
+            self.reason.as_str(),
+            self.locked_on,
+            self.expires_on
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<DeploymentLockDao, PgDao, Postgres> for DeploymentLock {
+    async fn read<K: IntoDaoKey<DeploymentLockKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match DeploymentLockDao::read(dal_key(qry)?).await {
+            Ok(lock) => Ok(Box::new(TryAsyncFrom::<DeploymentLockDao>::try_from(*lock).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<DeploymentLockKey>>(qry: K) -> Result<bool, EntityError> {
+        DeploymentLockDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<DeploymentLockDao, PgDao, Postgres> for DeploymentLock {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut lock = self.clone();
+                lock.id = dao.id();
+                Ok(Box::new(lock))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<DeploymentLockDao, PgDao, Postgres> for DeploymentLock {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const DEPLOYMENT_LOCK_TYPE_INFO: &str = "DeploymentLock";
+
+impl TypeInfo for DeploymentLock {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_LOCK_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_LOCK_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<DeploymentLockDao> for DeploymentLock {
+    type Error = EntityError;
+
+    async fn try_from(value: DeploymentLockDao) -> Result<Self, EntityError> {
+        let tenant = Tenant::read(TenantKey::Id(value.tenant())).await?;
+        let environment = match value.environment() {
+            Some(id) => Some(*Environment::read(EnvironmentKey::Id(id)).await?),
+            None => None
+        };
+        Ok(Self::load(value.id(), *tenant, environment, value.locked_by(), value.reason(), value.locked_on(), value.expires_on()))
+    }
+}
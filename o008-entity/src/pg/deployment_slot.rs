@@ -0,0 +1,243 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, DeploymentSlotKey, EnvironmentKey, ServiceVersionKey};
+use crate::{dal_key, Deployment, DestroyEntity, Entity, EntityError, Environment, PersistEntity, QueryEntity, ServiceVersion};
+
+type DeploymentSlotDao = o008_dal::pg::DeploymentSlot;
+
+pub const SLOT_NAMES: [&str; 2] = ["blue", "green"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeploymentSlot {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    name: String,
+    service_version: ServiceVersion,
+    environment: Environment,
+    deployment: Uuid,
+    active: bool,
+}
+
+impl DeploymentSlot {
+    pub fn new(name: &str, deployment: &Deployment, active: bool) -> Self {
+        Self {
+            id: Uuid::nil(),
+            name: String::from(name),
+            service_version: deployment.service_version().clone(),
+            environment: deployment.environment().clone(),
+            deployment: deployment.id(),
+            active,
+        }
+    }
+
+    pub fn load(id: Uuid, name: &str, service_version: ServiceVersion, environment: Environment, deployment: Uuid, active: bool) -> Self {
+        Self {
+            id,
+            name: String::from(name),
+            service_version,
+            environment,
+            deployment,
+            active,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn service_version(&self) -> &ServiceVersion {
+        &self.service_version
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn deployment(&self) -> Uuid {
+        self.deployment
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active
+    }
+
+    // the slot now runs the version of the deployment
+    pub fn assign(&mut self, deployment: &Deployment) {
+        self.service_version = deployment.service_version().clone();
+        self.deployment = deployment.id();
+    }
+
+    // the slot already running the version, else the idle slot, else a free slot name, the active slot is never replaced
+    pub fn target(slots: &[Self], service_version: Uuid) -> Option<String> {
+        slots.iter().find(|s| s.service_version.id() == service_version)
+            .or_else(|| slots.iter().find(|s| !s.active))
+            .map(|s| s.name.clone())
+            .or_else(|| SLOT_NAMES.iter().find(|n| !slots.iter().any(|s| s.name == **n)).map(|n| String::from(*n)))
+    }
+
+    pub async fn service_slots(service: Uuid, environment: Uuid) -> Result<Vec<Self>, EntityError> {
+        match DeploymentSlotDao::service_slots(service, environment).await {
+            Ok(slots) => {
+                let mut v: Vec<Self> = Vec::new();
+                for s in slots {
+                    v.push(TryAsyncFrom::<DeploymentSlotDao>::try_from(s).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<DeploymentSlotDao> for DeploymentSlot {
+    fn dao(&self) -> Box<DeploymentSlotDao> {
+        Box::new(DeploymentSlotDao::new(
+            self.id,
+            self.service_version.service().id(),
+            self.environment.id(),
+            self.name.as_str(),
+            self.service_version.id(),
+            self.deployment,
+            self.active
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<DeploymentSlotDao, PgDao, Postgres> for DeploymentSlot {
+    async fn read<K: IntoDaoKey<DeploymentSlotKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match DeploymentSlotDao::read(dal_key(qry)?).await {
+            Ok(slot) => Ok(Box::new(TryAsyncFrom::<DeploymentSlotDao>::try_from(*slot).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<DeploymentSlotKey>>(qry: K) -> Result<bool, EntityError> {
+        DeploymentSlotDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<DeploymentSlotDao, PgDao, Postgres> for DeploymentSlot {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut slot = self.clone();
+                slot.id = dao.id();
+                Ok(Box::new(slot))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<DeploymentSlotDao, PgDao, Postgres> for DeploymentSlot {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const DEPLOYMENT_SLOT_TYPE_INFO: &str = "DeploymentSlot";
+
+impl TypeInfo for DeploymentSlot {
+    fn type_name() -> &'static str {
+        DEPLOYMENT_SLOT_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        DEPLOYMENT_SLOT_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<DeploymentSlotDao> for DeploymentSlot {
+    type Error = EntityError;
+
+    async fn try_from(value: DeploymentSlotDao) -> Result<Self, EntityError> {
+        let service_version = ServiceVersion::read(ServiceVersionKey::Id(value.service_version())).await?;
+        let environment = Environment::read(EnvironmentKey::Id(value.environment())).await?;
+        Ok(Self::load(value.id(), value.name(), *service_version, *environment, value.deployment(), value.active()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use o008_common::RepoReferenceKind;
+    use crate::{Application, Builder, RepoReference, Service, Tenant};
+    use super::*;
+
+    fn version(id: u128) -> ServiceVersion {
+        let application = Application::new("shop", Tenant::new("acme", true), "cu", "fg");
+        let service = Service::new("cart", application, "file:///tmp/repo");
+        let repo_ref = RepoReference::new("file:///tmp/repo", RepoReferenceKind::Branch, "main");
+        ServiceVersion::load(Uuid::from_u128(id), "1.0.0", service, repo_ref, Builder::new("sh", true, Vec::new(), None, Vec::new()), None, None)
+    }
+
+    fn slot(name: &str, service_version: u128, active: bool) -> DeploymentSlot {
+        let environment = Environment::new("prod", Tenant::new("acme", true), 0);
+        DeploymentSlot::load(Uuid::now_v7(), name, version(service_version), environment, Uuid::now_v7(), active)
+    }
+
+    #[test]
+    fn target_is_the_first_free_name_without_slots() {
+        assert_eq!(DeploymentSlot::target(&[], Uuid::from_u128(1)), Some(String::from("blue")));
+    }
+
+    #[test]
+    fn target_is_a_free_name_next_to_the_active_slot() {
+        let slots = [slot("blue", 1, true)];
+        assert_eq!(DeploymentSlot::target(&slots, Uuid::from_u128(2)), Some(String::from("green")));
+    }
+
+    #[test]
+    fn target_is_the_idle_slot() {
+        let slots = [slot("blue", 1, false), slot("green", 2, true)];
+        assert_eq!(DeploymentSlot::target(&slots, Uuid::from_u128(3)), Some(String::from("blue")));
+    }
+
+    #[test]
+    fn target_is_the_slot_running_the_version() {
+        let slots = [slot("blue", 1, false), slot("green", 2, true)];
+        assert_eq!(DeploymentSlot::target(&slots, Uuid::from_u128(2)), Some(String::from("green")));
+        assert_eq!(DeploymentSlot::target(&slots, Uuid::from_u128(1)), Some(String::from("blue")));
+    }
+
+    #[test]
+    fn target_never_replaces_the_active_slot() {
+        let slots = [slot("blue", 1, true), slot("green", 2, true)];
+        assert_eq!(DeploymentSlot::target(&slots, Uuid::from_u128(3)), None);
+    }
+}
@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, EnvironmentKey, TenantKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity, Tenant};
+
+pub(crate) type EnvironmentDao = o008_dal::pg::Environment;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Environment {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    name: String,
+    tenant: Tenant,
+    position: i32,
+    soak_minutes: Option<i32>,
+    require_build: bool,
+    require_approval: bool,
+}
+
+impl Environment {
+    pub fn new(name: &str, tenant: Tenant, position: i32) -> Self {
+        Self {
+            id: Uuid::nil(),
+            name: String::from(name),
+            tenant,
+            position,
+            soak_minutes: None,
+            require_build: false,
+            require_approval: false,
+        }
+    }
+
+    pub fn load(id: Uuid, name: &str, tenant: Tenant, position: i32, soak_minutes: Option<i32>, require_build: bool, require_approval: bool) -> Self {
+        Self {
+            id,
+            name: String::from(name),
+            tenant,
+            position,
+            soak_minutes,
+            require_build,
+            require_approval,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn soak_minutes(&self) -> Option<i32> {
+        self.soak_minutes
+    }
+
+    pub fn require_build(&self) -> bool {
+        self.require_build
+    }
+
+    pub fn require_approval(&self) -> bool {
+        self.require_approval
+    }
+
+    // the promotion rules a service version has to pass to be released to this environment
+    pub fn set_rules(&mut self, soak_minutes: Option<i32>, require_build: bool, require_approval: bool) {
+        self.soak_minutes = soak_minutes;
+        self.require_build = require_build;
+        self.require_approval = require_approval;
+    }
+
+    pub fn set_require_approval(&mut self, require_approval: bool) {
+        self.require_approval = require_approval
+    }
+
+    pub async fn tenant_environments(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match EnvironmentDao::tenant_environments(tenant).await {
+            Ok(environments) => {
+                let mut v: Vec<Self> = Vec::new();
+                for e in environments {
+                    v.push(TryAsyncFrom::<EnvironmentDao>::try_from(e).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<EnvironmentDao> for Environment {
+    fn dao(&self) -> Box<EnvironmentDao> {
+        Box::new(EnvironmentDao::new(self.id, &self.name, self.tenant.id(), self.position, self.soak_minutes, self.require_build, self.require_approval))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<EnvironmentDao, PgDao, Postgres> for Environment {
+    async fn read<K: IntoDaoKey<EnvironmentKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match EnvironmentDao::read(dal_key(qry)?).await {
+            Ok(environment) => Ok(Box::new(TryAsyncFrom::<EnvironmentDao>::try_from(*environment).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<EnvironmentKey>>(qry: K) -> Result<bool, EntityError> {
+        EnvironmentDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<EnvironmentDao, PgDao, Postgres> for Environment {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut environment = self.clone();
+                environment.id = dao.id();
+                Ok(Box::new(environment))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<EnvironmentDao, PgDao, Postgres> for Environment {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const ENVIRONMENT_TYPE_INFO: &str = "Environment";
+
+impl TypeInfo for Environment {
+    fn type_name() -> &'static str {
+        ENVIRONMENT_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        ENVIRONMENT_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<EnvironmentDao> for Environment {
+    type Error = EntityError;
+
+    async fn try_from(value: EnvironmentDao) -> Result<Self, EntityError> {
+        let td = Tenant::read(TenantKey::Id(value.tenant())).await?;
+        Ok(Self::load(value.id(), value.name(), *td, value.position(), value.soak_minutes(), value.require_build(), value.require_approval()))
+    }
+}
@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+use chrono::{Datelike, DateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, EnvironmentKey, FreezeWindowKey, TenantKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, Environment, PersistEntity, QueryEntity, Tenant};
+
+type FreezeWindowDao = o008_dal::pg::FreezeWindow;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FreezeWindow {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    name: String,
+    tenant: Tenant,
+    environment: Option<Environment>,
+    days: Vec<String>,
+    /// Time of day the window starts, in UTC
+    #[schema(value_type = String, example = "18:00:00")]
+    starts_at: NaiveTime,
+    /// Time of day the window ends, in UTC
+    #[schema(value_type = String, example = "08:00:00")]
+    ends_at: NaiveTime,
+    reason: Option<String>,
+}
+
+impl FreezeWindow {
+    pub fn new(name: &str, tenant: Tenant, environment: Option<Environment>, days: &[Weekday], starts_at: NaiveTime, ends_at: NaiveTime,
+               reason: Option<&str>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            name: String::from(name),
+            tenant,
+            environment,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            starts_at,
+            ends_at,
+            reason: reason.map(String::from),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn load(id: Uuid, name: &str, tenant: Tenant, environment: Option<Environment>, days: &str, starts_at: NaiveTime, ends_at: NaiveTime,
+                reason: Option<&str>) -> Self {
+        Self {
+            id,
+            name: String::from(name),
+            tenant,
+            environment,
+            days: days.split(',').filter(|d| !d.is_empty()).map(String::from).collect(),
+            starts_at,
+            ends_at,
+            reason: reason.map(String::from),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    pub fn days(&self) -> &[String] {
+        &self.days
+    }
+
+    pub fn starts_at(&self) -> NaiveTime {
+        self.starts_at
+    }
+
+    pub fn ends_at(&self) -> NaiveTime {
+        self.ends_at
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    // the window recurs on its days (every day when none), in UTC, and runs past midnight when it ends before it starts
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Utc::now())
+    }
+
+    fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        let time = now.time();
+        let (day, in_window) = if self.starts_at < self.ends_at {
+            (now.weekday(), self.starts_at <= time && time < self.ends_at)
+        } else if time >= self.starts_at {
+            (now.weekday(), true)
+        } else {
+            // the early hours belong to the window started the day before
+            (now.weekday().pred(), time < self.ends_at)
+        };
+        in_window && (self.days.is_empty() || self.days.iter().any(|d| d.parse::<Weekday>().is_ok_and(|d| d == day)))
+    }
+
+    pub async fn tenant_windows(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match FreezeWindowDao::tenant_windows(tenant).await {
+            Ok(windows) => {
+                let mut v: Vec<Self> = Vec::new();
+                for w in windows {
+                    v.push(TryAsyncFrom::<FreezeWindowDao>::try_from(w).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<FreezeWindowDao> for FreezeWindow {
+    fn dao(&self) -> Box<FreezeWindowDao> {
+        Box::new(FreezeWindowDao::new(
+            self.id,
+            self.name.as_str(),
+            self.tenant.id(),
+            self.environment.as_ref().map(|e| e.id()),
+            self.days.join(",").as_str(),
+            self.starts_at,
+            self.ends_at,
+            self.reason.as_deref()
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<FreezeWindowDao, PgDao, Postgres> for FreezeWindow {
+    async fn read<K: IntoDaoKey<FreezeWindowKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match FreezeWindowDao::read(dal_key(qry)?).await {
+            Ok(window) => Ok(Box::new(TryAsyncFrom::<FreezeWindowDao>::try_from(*window).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<FreezeWindowKey>>(qry: K) -> Result<bool, EntityError> {
+        FreezeWindowDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<FreezeWindowDao, PgDao, Postgres> for FreezeWindow {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut window = self.clone();
+                window.id = dao.id();
+                Ok(Box::new(window))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<FreezeWindowDao, PgDao, Postgres> for FreezeWindow {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const FREEZE_WINDOW_TYPE_INFO: &str = "FreezeWindow";
+
+impl TypeInfo for FreezeWindow {
+    fn type_name() -> &'static str {
+        FREEZE_WINDOW_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        FREEZE_WINDOW_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<FreezeWindowDao> for FreezeWindow {
+    type Error = EntityError;
+
+    async fn try_from(value: FreezeWindowDao) -> Result<Self, EntityError> {
+        let tenant = Tenant::read(TenantKey::Id(value.tenant())).await?;
+        let environment = match value.environment() {
+            Some(id) => Some(*Environment::read(EnvironmentKey::Id(id)).await?),
+            None => None
+        };
+        Ok(Self::load(value.id(), value.name(), *tenant, environment, value.days(), value.starts_at(), value.ends_at(), value.reason()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn window(days: &[Weekday], starts_at: (u32, u32), ends_at: (u32, u32)) -> FreezeWindow {
+        FreezeWindow::new("freeze", Tenant::new("acme", false), None, days,
+                          NaiveTime::from_hms_opt(starts_at.0, starts_at.1, 0).unwrap(),
+                          NaiveTime::from_hms_opt(ends_at.0, ends_at.1, 0).unwrap(), None)
+    }
+
+    // 2026-10-16 is a Friday
+    fn at(day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn starts_inclusive_and_ends_exclusive() {
+        let w = window(&[], (9, 0), (17, 0));
+        assert!(!w.is_active_at(at(16, 8, 59, 59)));
+        assert!(w.is_active_at(at(16, 9, 0, 0)));
+        assert!(w.is_active_at(at(16, 16, 59, 59)));
+        assert!(!w.is_active_at(at(16, 17, 0, 0)));
+    }
+
+    #[test]
+    fn runs_past_midnight_on_its_day() {
+        let w = window(&[Weekday::Fri], (18, 0), (8, 0));
+        assert!(!w.is_active_at(at(16, 17, 59, 59)));
+        assert!(w.is_active_at(at(16, 18, 0, 0)));
+        assert!(w.is_active_at(at(17, 0, 0, 0)));
+        assert!(w.is_active_at(at(17, 7, 59, 59)));
+        assert!(!w.is_active_at(at(17, 8, 0, 0)));
+        // the Saturday evening does not start a window of its own
+        assert!(!w.is_active_at(at(17, 18, 0, 0)));
+        // the Friday early hours belong to the Thursday window
+        assert!(!w.is_active_at(at(16, 7, 0, 0)));
+    }
+
+    #[test]
+    fn only_on_its_days() {
+        let w = window(&[Weekday::Sat, Weekday::Sun], (0, 0), (23, 59));
+        assert!(!w.is_active_at(at(16, 12, 0, 0)));
+        assert!(w.is_active_at(at(17, 12, 0, 0)));
+        assert!(w.is_active_at(at(18, 12, 0, 0)));
+    }
+
+    #[test]
+    fn compares_in_utc() {
+        let w = window(&[], (9, 0), (17, 0));
+        let paris = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        // 10:00 in Paris is 08:00 UTC
+        assert!(!w.is_active_at(paris.with_ymd_and_hms(2026, 10, 16, 10, 0, 0).unwrap().with_timezone(&Utc)));
+        assert!(w.is_active_at(paris.with_ymd_and_hms(2026, 10, 16, 11, 0, 0).unwrap().with_timezone(&Utc)));
+    }
+}
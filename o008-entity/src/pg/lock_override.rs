@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, EnvironmentKey, LockOverrideKey, TenantKey};
+use crate::{dal_key, Entity, EntityError, Environment, PersistEntity, QueryEntity, Tenant};
+
+type LockOverrideDao = o008_dal::pg::LockOverride;
+
+// who ran an action despite a lock or a freeze window, what was locked and why it was overridden
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LockOverride {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    tenant: Tenant,
+    environment: Option<Environment>,
+    action: String,
+    locked: String,
+    overridden_by: String,
+    justification: String,
+    overridden_on: DateTime<Utc>,
+}
+
+impl LockOverride {
+    pub fn new(tenant: Tenant, environment: Option<Environment>, action: &str, locked: &str, overridden_by: &str, justification: &str) -> Self {
+        Self {
+            id: Uuid::nil(),
+            tenant,
+            environment,
+            action: String::from(action),
+            locked: String::from(locked),
+            overridden_by: String::from(overridden_by),
+            justification: String::from(justification),
+            overridden_on: Utc::now(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn load(id: Uuid, tenant: Tenant, environment: Option<Environment>, action: &str, locked: &str, overridden_by: &str, justification: &str,
+                overridden_on: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            tenant,
+            environment,
+            action: String::from(action),
+            locked: String::from(locked),
+            overridden_by: String::from(overridden_by),
+            justification: String::from(justification),
+            overridden_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn locked(&self) -> &str {
+        &self.locked
+    }
+
+    pub fn overridden_by(&self) -> &str {
+        &self.overridden_by
+    }
+
+    pub fn justification(&self) -> &str {
+        &self.justification
+    }
+
+    pub fn overridden_on(&self) -> DateTime<Utc> {
+        self.overridden_on
+    }
+
+    pub async fn tenant_overrides(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match LockOverrideDao::tenant_overrides(tenant).await {
+            Ok(overrides) => {
+                let mut v: Vec<Self> = Vec::new();
+                for o in overrides {
+                    v.push(TryAsyncFrom::<LockOverrideDao>::try_from(o).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<LockOverrideDao> for LockOverride {
+    fn dao(&self) -> Box<LockOverrideDao> {
+        Box::new(LockOverrideDao::new(
+            self.id,
+            self.tenant.id(),
+            self.environment.as_ref().map(|e| e.id()),
+            self.action.as_str(),
+            self.locked.as_str(),
+            self.overridden_by.as_str(),
+            self.justification.as_str(),
+            self.overridden_on
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<LockOverrideDao, PgDao, Postgres> for LockOverride {
+    async fn read<K: IntoDaoKey<LockOverrideKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match LockOverrideDao::read(dal_key(qry)?).await {
+            Ok(o) => Ok(Box::new(TryAsyncFrom::<LockOverrideDao>::try_from(*o).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<LockOverrideKey>>(qry: K) -> Result<bool, EntityError> {
+        LockOverrideDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<LockOverrideDao, PgDao, Postgres> for LockOverride {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut o = self.clone();
+                o.id = dao.id();
+                Ok(Box::new(o))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+const LOCK_OVERRIDE_TYPE_INFO: &str = "LockOverride";
+
+impl TypeInfo for LockOverride {
+    fn type_name() -> &'static str {
+        LOCK_OVERRIDE_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        LOCK_OVERRIDE_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<LockOverrideDao> for LockOverride {
+    type Error = EntityError;
+
+    async fn try_from(value: LockOverrideDao) -> Result<Self, EntityError> {
+        let tenant = Tenant::read(TenantKey::Id(value.tenant())).await?;
+        let environment = match value.environment() {
+            Some(id) => Some(*Environment::read(EnvironmentKey::Id(id)).await?),
+            None => None
+        };
+        Ok(Self::load(value.id(), *tenant, environment, value.action(), value.locked(), value.overridden_by(), value.justification(),
+                      value.overridden_on()))
+    }
+}
@@ -1,13 +1,37 @@
 mod builder;
 mod tenant;
 mod application;
+mod environment;
+mod deployment;
+mod deployment_slot;
+mod deployment_lock;
+mod freeze_window;
+mod lock_override;
+mod release;
+mod release_approval;
 mod service;
 mod repo_reference;
 mod service_version;
+mod service_version_build;
+mod service_version_build_stage;
+mod service_version_repo_ref_history;
+mod service_version_artifact;
 
 pub use application::Application;
 pub use builder::Builder;
+pub use deployment::Deployment;
+pub use deployment_slot::DeploymentSlot;
+pub use deployment_lock::DeploymentLock;
+pub use environment::Environment;
+pub use freeze_window::FreezeWindow;
+pub use lock_override::LockOverride;
+pub use release::Release;
+pub use release_approval::ReleaseApproval;
 pub use repo_reference::RepoReference;
 pub use service::Service;
 pub use service_version::{ServiceVersion, ServiceVersionItem};
+pub use service_version_build::ServiceVersionBuild;
+pub use service_version_build_stage::ServiceVersionBuildStage;
+pub use service_version_repo_ref_history::ServiceVersionRepoRefHistory;
+pub use service_version_artifact::ServiceVersionArtifact;
 pub use tenant::Tenant;
@@ -0,0 +1,221 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{ReleaseStatus, TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, EnvironmentKey, ReleaseKey, ServiceVersionKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, Environment, PersistEntity, QueryEntity, ServiceVersion};
+
+type ReleaseDao = o008_dal::pg::Release;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Release {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    service_version: ServiceVersion,
+    environment: Environment,
+    status: ReleaseStatus,
+    promoted_by: String,
+    promoted_on: DateTime<Utc>,
+    from_deployment: Uuid,
+}
+
+impl Release {
+    pub fn new(service_version: ServiceVersion, environment: Environment, promoted_by: &str, from_deployment: Uuid, status: ReleaseStatus) -> Self {
+        Self {
+            id: Uuid::nil(),
+            service_version,
+            environment,
+            status,
+            promoted_by: String::from(promoted_by),
+            promoted_on: Utc::now(),
+            from_deployment,
+        }
+    }
+
+    pub fn load(id: Uuid, service_version: ServiceVersion, environment: Environment, status: ReleaseStatus, promoted_by: &str,
+                promoted_on: DateTime<Utc>, from_deployment: Uuid) -> Self {
+        Self {
+            id,
+            service_version,
+            environment,
+            status,
+            promoted_by: String::from(promoted_by),
+            promoted_on,
+            from_deployment,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> &ServiceVersion {
+        &self.service_version
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn status(&self) -> ReleaseStatus {
+        self.status
+    }
+
+    pub fn promoted_by(&self) -> &str {
+        &self.promoted_by
+    }
+
+    pub fn promoted_on(&self) -> DateTime<Utc> {
+        self.promoted_on
+    }
+
+    pub fn from_deployment(&self) -> Uuid {
+        self.from_deployment
+    }
+
+    pub fn set_status(&mut self, status: ReleaseStatus) {
+        self.status = status
+    }
+
+    // a rejected release is promoted again from a new deployment of the previous environment
+    pub fn promote_again(&mut self, promoted_by: &str, from_deployment: Uuid, status: ReleaseStatus) {
+        self.status = status;
+        self.promoted_by = String::from(promoted_by);
+        self.promoted_on = Utc::now();
+        self.from_deployment = from_deployment;
+    }
+
+    // the last version released of each service to each environment of a tenant
+    pub async fn latest_released(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ReleaseDao::latest_releases(tenant, ReleaseStatus::Released.to_string().as_str()).await {
+            Ok(releases) => {
+                let mut v: Vec<Self> = Vec::new();
+                for r in releases {
+                    v.push(TryAsyncFrom::<ReleaseDao>::try_from(r).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    pub async fn service_version_releases(service_version: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ReleaseDao::service_version_releases(service_version).await {
+            Ok(releases) => {
+                let mut v: Vec<Self> = Vec::new();
+                for r in releases {
+                    v.push(TryAsyncFrom::<ReleaseDao>::try_from(r).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ReleaseDao> for Release {
+    fn dao(&self) -> Box<ReleaseDao> {
+        Box::new(ReleaseDao::new(
+            self.id,
+            self.service_version.id(),
+            self.environment.id(),
+            self.status.to_string().as_str(),
+            self.promoted_by.as_str(),
+            self.promoted_on,
+            self.from_deployment
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ReleaseDao, PgDao, Postgres> for Release {
+    async fn read<K: IntoDaoKey<ReleaseKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ReleaseDao::read(dal_key(qry)?).await {
+            Ok(release) => Ok(Box::new(TryAsyncFrom::<ReleaseDao>::try_from(*release).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                DalError::DataNotFound(_) => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::Query(e)),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ReleaseKey>>(qry: K) -> Result<bool, EntityError> {
+        ReleaseDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ReleaseDao, PgDao, Postgres> for Release {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut release = self.clone();
+                release.id = dao.id();
+                Ok(Box::new(release))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ReleaseDao, PgDao, Postgres> for Release {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const RELEASE_TYPE_INFO: &str = "Release";
+
+impl TypeInfo for Release {
+    fn type_name() -> &'static str {
+        RELEASE_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        RELEASE_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<ReleaseDao> for Release {
+    type Error = EntityError;
+
+    async fn try_from(value: ReleaseDao) -> Result<Self, EntityError> {
+        let service_version = ServiceVersion::read(ServiceVersionKey::Id(value.service_version())).await?;
+        let environment = Environment::read(EnvironmentKey::Id(value.environment())).await?;
+        Ok(Self::load(
+            value.id(),
+            *service_version,
+            *environment,
+            value.status().parse().unwrap_or(ReleaseStatus::PendingApproval),
+            value.promoted_by(),
+            value.promoted_on(),
+            value.from_deployment()
+        ))
+    }
+}
@@ -0,0 +1,194 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{ApprovalDecision, TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, ReleaseApprovalKey, ReleaseKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity, Release};
+
+type ReleaseApprovalDao = o008_dal::pg::ReleaseApproval;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReleaseApproval {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    release: Release,
+    decision: ApprovalDecision,
+    approver: String,
+    comment: Option<String>,
+    decided_on: DateTime<Utc>,
+}
+
+impl ReleaseApproval {
+    pub fn new(release: Release, decision: ApprovalDecision, approver: &str, comment: Option<&str>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            release,
+            decision,
+            approver: String::from(approver),
+            comment: comment.map(String::from),
+            decided_on: Utc::now(),
+        }
+    }
+
+    pub fn load(id: Uuid, release: Release, decision: ApprovalDecision, approver: &str, comment: Option<&str>, decided_on: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            release,
+            decision,
+            approver: String::from(approver),
+            comment: comment.map(String::from),
+            decided_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn release(&self) -> &Release {
+        &self.release
+    }
+
+    pub fn decision(&self) -> ApprovalDecision {
+        self.decision
+    }
+
+    pub fn approver(&self) -> &str {
+        &self.approver
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn decided_on(&self) -> DateTime<Utc> {
+        self.decided_on
+    }
+
+    // a new decision is recorded in the same transaction as the status it gives the release
+    pub async fn persist_deciding(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        match dao.insert_deciding(&self.release.dao()).await {
+            Ok(_) => {
+                let mut approval = self.clone();
+                approval.id = dao.id();
+                Ok(Box::new(approval))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+
+    pub async fn release_approvals(release: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ReleaseApprovalDao::release_approvals(release).await {
+            Ok(approvals) => {
+                let mut v: Vec<Self> = Vec::new();
+                for a in approvals {
+                    v.push(TryAsyncFrom::<ReleaseApprovalDao>::try_from(a).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ReleaseApprovalDao> for ReleaseApproval {
+    fn dao(&self) -> Box<ReleaseApprovalDao> {
+        Box::new(ReleaseApprovalDao::new(
+            self.id,
+            self.release.id(),
+            self.decision.to_string().as_str(),
+            self.approver.as_str(),
+            self.comment.as_deref(),
+            self.decided_on
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ReleaseApprovalDao, PgDao, Postgres> for ReleaseApproval {
+    async fn read<K: IntoDaoKey<ReleaseApprovalKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ReleaseApprovalDao::read(dal_key(qry)?).await {
+            Ok(approval) => Ok(Box::new(TryAsyncFrom::<ReleaseApprovalDao>::try_from(*approval).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ReleaseApprovalKey>>(qry: K) -> Result<bool, EntityError> {
+        ReleaseApprovalDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ReleaseApprovalDao, PgDao, Postgres> for ReleaseApproval {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut approval = self.clone();
+                approval.id = dao.id();
+                Ok(Box::new(approval))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ReleaseApprovalDao, PgDao, Postgres> for ReleaseApproval {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const RELEASE_APPROVAL_TYPE_INFO: &str = "ReleaseApproval";
+
+impl TypeInfo for ReleaseApproval {
+    fn type_name() -> &'static str {
+        RELEASE_APPROVAL_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        RELEASE_APPROVAL_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<ReleaseApprovalDao> for ReleaseApproval {
+    type Error = EntityError;
+
+    async fn try_from(value: ReleaseApprovalDao) -> Result<Self, EntityError> {
+        let release = Release::read(ReleaseKey::Id(value.release())).await?;
+        Ok(Self::load(
+            value.id(),
+            *release,
+            value.decision().parse().unwrap_or(ApprovalDecision::Rejected),
+            value.approver(),
+            value.comment(),
+            value.decided_on()
+        ))
+    }
+}
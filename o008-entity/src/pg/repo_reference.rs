@@ -1,14 +1,13 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use sqlx::Postgres;
 use tracing::error;
 use utoipa::ToSchema;
 use uuid::Uuid;
 use o008_common::{RepoReferenceKind, RepoReferenceRequest};
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use o008_dal::pg::{PgDao};
-use crate::{DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, RepoReferenceKey};
+use crate::{dal_key, DependentEntity, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
 
 pub type RepoReferenceDao = o008_dal::pg::RepoReference;
 
@@ -65,8 +64,8 @@ impl Entity<RepoReferenceDao> for RepoReference {
 
 #[async_trait]
 impl QueryEntity<RepoReferenceDao, PgDao, Postgres> for RepoReference {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match RepoReferenceDao::read(qry).await {
+    async fn read<K: IntoDaoKey<RepoReferenceKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match RepoReferenceDao::read(dal_key(qry)?).await {
             Ok(rf) => Ok(Box::new(From::from(*rf))),
             Err(e) => match e {
                 DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
@@ -78,8 +77,8 @@ impl QueryEntity<RepoReferenceDao, PgDao, Postgres> for RepoReference {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        RepoReferenceDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<RepoReferenceKey>>(qry: K) -> Result<bool, EntityError> {
+        RepoReferenceDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -113,6 +112,8 @@ impl DestroyEntity<RepoReferenceDao, PgDao, Postgres> for RepoReference {
     }
 }
 
+impl DependentEntity<RepoReferenceDao, PgDao, Postgres> for RepoReference {}
+
 impl From<RepoReferenceDao> for RepoReference {
     fn from(value: RepoReferenceDao) -> Self {
         Self::load(value.id(), value.repo(), value.kind(), value.reference())
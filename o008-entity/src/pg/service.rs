@@ -1,15 +1,14 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use sqlx::Postgres;
 use uuid::Uuid;
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use crate::{DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+use o008_dal::{DalError, DalFilter, DaoCommand, DaoQuery, IntoDaoKey};
+use crate::{dal_key, dal_page, DependentEntity, DestroyEntity, Entity, EntityError, EntityPage, PersistEntity, QueryEntity};
 use crate::pg::{Application, ServiceVersionItem};
-use crate::pg::application::ApplicationDao;
 use utoipa::ToSchema;
-use o008_common::{AsyncFrom, ServiceRequest};
-use o008_dal::pg::{PgDao};
+use o008_common::{ListRequest, ServiceRequest, TryAsyncFrom};
+use o008_dal::pg::{PgDao, ApplicationKey, ServiceKey};
 
 type ServiceDao = o008_dal::pg::Service;
 
@@ -22,7 +21,9 @@ pub struct Service {
     original_name: String,
     application: Application,
     default_repo: String,
-    versions: Option<Vec<ServiceVersionItem>>
+    versions: Option<Vec<ServiceVersionItem>>,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl Service {
@@ -33,11 +34,13 @@ impl Service {
             original_name: String::from(name),
             application: app,
             default_repo: String::from(repo),
-            versions: None
+            versions: None,
+            archived_at: None,
+            archived_by: None,
         }
     }
 
-    pub fn load(id: Uuid, name: &str, original_name: &str, app: Application, repo: &str) -> Self {
+    pub fn load(id: Uuid, name: &str, original_name: &str, app: Application, repo: &str, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id,
             name: String::from(name),
@@ -45,6 +48,8 @@ impl Service {
             application: app,
             default_repo: String::from(repo),
             versions: None,
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -81,22 +86,77 @@ impl Service {
         }
     }
 
+    pub async fn tenant_services(tenant: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ServiceDao::tenant_services(tenant).await {
+            Ok(services) => {
+                let mut v: Vec<Self> = Vec::new();
+                for s in services {
+                    v.push(TryAsyncFrom::<ServiceDao>::try_from(s).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
+                _ => Err(EntityError::NotFound(e.to_string())),
+            }
+        }
+    }
+
     pub fn set_versions(&mut self, versions: Vec<ServiceVersionItem>) {
         self.versions = Some(versions)
     }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    // an archived service hides its versions from the reads by name and the lists until it is restored
+    pub fn archive(&mut self, archived_by: &str) {
+        self.archived_at = Some(Utc::now());
+        self.archived_by = Some(String::from(archived_by));
+    }
+
+    pub fn restore(&mut self) {
+        self.archived_at = None;
+        self.archived_by = None;
+    }
+
+    pub async fn list(filter: DalFilter, lrq: &ListRequest) -> Result<EntityPage<Self>, EntityError> {
+        match ServiceDao::list(filter, dal_page(lrq)).await {
+            Ok(services) => {
+                let mut v: Vec<Self> = Vec::new();
+                for s in services {
+                    v.push(TryAsyncFrom::<ServiceDao>::try_from(s).await?)
+                }
+                Ok(EntityPage::new(v, lrq.limit(), Self::id))
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
+                _ => Err(EntityError::NotFound(e.to_string())),
+            }
+        }
+    }
 }
 
 impl Entity<ServiceDao> for Service {
     fn dao(&self) -> Box<ServiceDao> {
-        Box::new(ServiceDao::new(self.id, &self.name, &self.original_name, self.application.id(), &self.default_repo))
+        Box::new(ServiceDao::new(self.id, &self.name, &self.original_name, self.application.id(), &self.default_repo, self.archived_at, self.archived_by.as_deref()))
     }
 }
 
 #[async_trait]
 impl QueryEntity<ServiceDao, PgDao, Postgres> for Service {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match ServiceDao::read(qry).await {
-          Ok(app) => Ok(Box::new(AsyncFrom::<ServiceDao>::from(*app).await)),
+    async fn read<K: IntoDaoKey<ServiceKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceDao::read(dal_key(qry)?).await {
+          Ok(app) => Ok(Box::new(TryAsyncFrom::<ServiceDao>::try_from(*app).await?)),
           Err(e) => match e {
               DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
               _ => Err(EntityError::NotFound(e.to_string())),
@@ -104,8 +164,8 @@ impl QueryEntity<ServiceDao, PgDao, Postgres> for Service {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        ServiceDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<ServiceKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -121,7 +181,7 @@ impl PersistEntity<ServiceDao, PgDao, Postgres> for Service {
         match r {
             Ok(_) => {
                 Ok(Box::new(
-                    Self::load(dao.id(), &self.name, &self.original_name, self.application(), &self.default_repo)
+                    Self::load(dao.id(), &self.name, &self.original_name, self.application(), &self.default_repo, self.archived_at, self.archived_by.as_deref())
                 ))
             },
             Err(e) => Err(EntityError::Persist(e))
@@ -143,18 +203,24 @@ impl DestroyEntity<ServiceDao, PgDao, Postgres> for Service {
     }
 }
 
+impl DependentEntity<ServiceDao, PgDao, Postgres> for Service {}
+
 #[async_trait]
-impl AsyncFrom<ServiceDao> for Service {
-    async fn from(value: ServiceDao) -> Self {
-        let app = ApplicationDao::read(json!({"id": value.application().to_string()})).await.unwrap();
-        Self::load(value.id(), value.name(), value.original_name(), AsyncFrom::<ApplicationDao>::from(*app).await, value.default_repo())
+impl TryAsyncFrom<ServiceDao> for Service {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceDao) -> Result<Self, EntityError> {
+        let app = Application::read(ApplicationKey::Id(value.application())).await?;
+        Ok(Self::load(value.id(), value.name(), value.original_name(), *app, value.default_repo(), value.archived_at(), value.archived_by()))
     }
 }
 
 #[async_trait]
-impl AsyncFrom<ServiceRequest> for Service {
-    async fn from(value: ServiceRequest) -> Self {
-        let srv = ServiceDao::read(serde_json::to_value(value).unwrap()).await.unwrap();
-        AsyncFrom::<ServiceDao>::from(*srv).await
+impl TryAsyncFrom<ServiceRequest> for Service {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceRequest) -> Result<Self, EntityError> {
+        let qry = serde_json::to_value(value).map_err(|e| EntityError::WrongQuery(e.to_string()))?;
+        Ok(*Service::read(qry).await?)
     }
 }
\ No newline at end of file
@@ -1,14 +1,14 @@
-
+use std::collections::HashMap;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use sqlx::Postgres;
 use utoipa::ToSchema;
 use uuid::Uuid;
-use o008_common::{AsyncFrom, TypeInfo};
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use o008_dal::pg::PgDao;
-use crate::{Builder, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity, Service};
+use o008_common::{ListRequest, TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DalFilter, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, BuilderKey, RepoReferenceKey, ServiceKey, ServiceVersionKey};
+use crate::{dal_key, Builder, dal_page, DependentEntity, DestroyEntity, Entity, EntityError, EntityPage, PersistEntity, QueryEntity, Service};
 use crate::pg::RepoReference;
 
 type ServiceVersionDao = o008_dal::pg::ServiceVersion;
@@ -21,6 +21,8 @@ pub struct ServiceVersion {
     service: Service,
     repo_ref: RepoReference,
     builder: Builder,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -30,6 +32,8 @@ pub struct ServiceVersionItem {
     version: String,
     repo_ref: RepoReference,
     builder: Builder,
+    archived_at: Option<DateTime<Utc>>,
+    archived_by: Option<String>,
 }
 
 impl ServiceVersion {
@@ -40,16 +44,20 @@ impl ServiceVersion {
             service,
             repo_ref,
             builder,
+            archived_at: None,
+            archived_by: None,
         }
     }
 
-    pub fn load(id: Uuid, version: &str, service: Service, repo_ref: RepoReference, builder: Builder) -> Self {
+    pub fn load(id: Uuid, version: &str, service: Service, repo_ref: RepoReference, builder: Builder, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id,
             version: String::from(version),
             service,
             repo_ref,
             builder,
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
 
@@ -73,6 +81,34 @@ impl ServiceVersion {
         &self.builder
     }
 
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn archived_by(&self) -> Option<&str> {
+        self.archived_by.as_deref()
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    // an archived version is kept with its builds and deployments but hidden from the reads by name and the lists
+    pub fn archive(&mut self, archived_by: &str) {
+        self.archived_at = Some(Utc::now());
+        self.archived_by = Some(String::from(archived_by));
+    }
+
+    pub fn restore(&mut self) {
+        self.archived_at = None;
+        self.archived_by = None;
+    }
+
+    // the repository of the reference, the service default one when the reference does not name it
+    pub fn repo(&self) -> &str {
+        if self.repo_ref.repo().is_empty() { self.service.default_repo() } else { self.repo_ref.repo() }
+    }
+
     pub fn set_service(&mut self, service: Service) {
         self.service = service
     }
@@ -89,12 +125,25 @@ impl ServiceVersion {
         self.version = String::from(version)
     }
 
-    pub async fn service_versions(qry: Value) -> Result<Vec<ServiceVersionItem>, EntityError> {
-        match ServiceVersionDao::service_versions(qry).await {
+    pub fn command_variables(&self) -> HashMap<&'static str, String> {
+        let application = self.service.application();
+        HashMap::from([
+            ("service.name", String::from(self.service.name())),
+            ("application.name", String::from(application.name())),
+            ("tenant.name", String::from(application.tenant().name())),
+            ("version", self.version.clone()),
+            ("repo", String::from(self.repo_ref.repo())),
+            ("ref.kind", self.repo_ref.kind().to_string()),
+            ("ref.reference", String::from(self.repo_ref.reference())),
+        ])
+    }
+
+    pub async fn service_versions(service: Uuid, include_archived: bool) -> Result<Vec<ServiceVersionItem>, EntityError> {
+        match ServiceVersionDao::service_versions(service, include_archived).await {
             Ok(versions) => {
                 let mut v : Vec<ServiceVersionItem> = Vec::new();
                 for sv in versions {
-                    let short: ServiceVersionItem = AsyncFrom::<ServiceVersionDao>::from(sv).await;
+                    let short: ServiceVersionItem = TryAsyncFrom::<ServiceVersionDao>::try_from(sv).await?;
                     v.push(short)
                 }
                 Ok(v)
@@ -105,30 +154,53 @@ impl ServiceVersion {
             }
         }
     }
+
+    // the versions of a service, a page at a time
+    pub async fn list(filter: DalFilter, lrq: &ListRequest) -> Result<EntityPage<ServiceVersionItem>, EntityError> {
+        match ServiceVersionDao::list(filter, dal_page(lrq)).await {
+            Ok(versions) => {
+                let mut v: Vec<ServiceVersionItem> = Vec::new();
+                for sv in versions {
+                    v.push(TryAsyncFrom::<ServiceVersionDao>::try_from(sv).await?)
+                }
+                Ok(EntityPage::new(v, lrq.limit(), ServiceVersionItem::id))
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
 }
 
 impl ServiceVersionItem {
-    pub fn load(id: Uuid, version: &str, repo_ref: RepoReference, builder: Builder) -> Self {
+    pub fn load(id: Uuid, version: &str, repo_ref: RepoReference, builder: Builder, archived_at: Option<DateTime<Utc>>, archived_by: Option<&str>) -> Self {
         Self {
             id,
             version: String::from(version),
             repo_ref,
             builder,
+            archived_at,
+            archived_by: archived_by.map(String::from),
         }
     }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
 }
 
 impl Entity<ServiceVersionDao> for ServiceVersion {
     fn dao(&self) -> Box<ServiceVersionDao> {
-        Box::new(ServiceVersionDao::new(self.id, self.version.as_str(), self.service.id(), self.repo_ref.id(), self.builder.id()))
+        Box::new(ServiceVersionDao::new(self.id, self.version.as_str(), self.service.id(), self.repo_ref.id(), self.builder.id(), self.archived_at, self.archived_by.as_deref()))
     }
 }
 
 #[async_trait]
 impl QueryEntity<ServiceVersionDao, PgDao, Postgres> for ServiceVersion {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match ServiceVersionDao::read(qry).await {
-            Ok(sv) => Ok(Box::new(AsyncFrom::<ServiceVersionDao>::from(*sv).await)),
+    async fn read<K: IntoDaoKey<ServiceVersionKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceVersionDao::read(dal_key(qry)?).await {
+            Ok(sv) => Ok(Box::new(TryAsyncFrom::<ServiceVersionDao>::try_from(*sv).await?)),
             Err(e) => match e {
                 DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
                 _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
@@ -136,8 +208,8 @@ impl QueryEntity<ServiceVersionDao, PgDao, Postgres> for ServiceVersion {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        ServiceVersionDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<ServiceVersionKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceVersionDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -157,6 +229,8 @@ impl PersistEntity<ServiceVersionDao, PgDao, Postgres> for ServiceVersion {
                 service: self.service.clone(),
                 repo_ref: self.repo_ref.clone(),
                 builder: self.builder.clone(),
+                archived_at: self.archived_at,
+                archived_by: self.archived_by.clone(),
             })),
             Err(e) => Err(EntityError::Persist(e))
         }
@@ -177,6 +251,8 @@ impl DestroyEntity<ServiceVersionDao, PgDao, Postgres> for ServiceVersion {
     }
 }
 
+impl DependentEntity<ServiceVersionDao, PgDao, Postgres> for ServiceVersion {}
+
 const SERVICE_VERSION_TYPE_INFO: &str = "ServiceVersion";
 
 impl TypeInfo for ServiceVersion {
@@ -190,20 +266,24 @@ impl TypeInfo for ServiceVersion {
 }
 
 #[async_trait]
-impl AsyncFrom<ServiceVersionDao> for ServiceVersion {
-    async fn from(value: ServiceVersionDao) -> Self {
-        let service = Service::read(json!({"id": value.service()})).await.unwrap();
-        let repo_ref = RepoReference::read(json!({"id": value.repo_ref()})).await.unwrap();
-        let builder = Builder::read(json!({"id": value.builder().to_string()})).await.unwrap();
-        Self::load(value.id(), value.version(), *service, *repo_ref, *builder)
+impl TryAsyncFrom<ServiceVersionDao> for ServiceVersion {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceVersionDao) -> Result<Self, EntityError> {
+        let service = Service::read(ServiceKey::Id(value.service())).await?;
+        let repo_ref = RepoReference::read(RepoReferenceKey::Id(value.repo_ref())).await?;
+        let builder = Builder::read(BuilderKey::Id(value.builder())).await?;
+        Ok(Self::load(value.id(), value.version(), *service, *repo_ref, *builder, value.archived_at(), value.archived_by()))
     }
 }
 
 #[async_trait]
-impl AsyncFrom<ServiceVersionDao> for ServiceVersionItem {
-    async fn from(value: ServiceVersionDao) -> Self {
-        let repo_ref = RepoReference::read(json!({"id": value.repo_ref()})).await.unwrap();
-        let builder = Builder::read(json!({"id": value.builder().to_string()})).await.unwrap();
-        Self::load(value.id(), value.version(), *repo_ref, *builder)
+impl TryAsyncFrom<ServiceVersionDao> for ServiceVersionItem {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceVersionDao) -> Result<Self, EntityError> {
+        let repo_ref = RepoReference::read(RepoReferenceKey::Id(value.repo_ref())).await?;
+        let builder = Builder::read(BuilderKey::Id(value.builder())).await?;
+        Ok(Self::load(value.id(), value.version(), *repo_ref, *builder, value.archived_at(), value.archived_by()))
     }
 }
\ No newline at end of file
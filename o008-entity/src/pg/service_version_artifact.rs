@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::TypeInfo;
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, ServiceVersionArtifactKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+
+type ServiceVersionArtifactDao = o008_dal::pg::ServiceVersionArtifact;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServiceVersionArtifact {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    service_version: Uuid,
+    build: Uuid,
+    name: String,
+    size: i64,
+    sha256: String,
+    created_on: DateTime<Utc>,
+}
+
+impl ServiceVersionArtifact {
+    pub fn new(service_version: Uuid, build: Uuid, name: &str, size: i64, sha256: &str) -> Self {
+        Self {
+            id: Uuid::nil(),
+            service_version,
+            build,
+            name: String::from(name),
+            size,
+            sha256: String::from(sha256),
+            created_on: Utc::now(),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn build(&self) -> Uuid {
+        self.build
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    pub fn sha256(&self) -> &str {
+        self.sha256.as_str()
+    }
+
+    pub fn created_on(&self) -> DateTime<Utc> {
+        self.created_on
+    }
+
+    pub async fn service_version_artifacts(service_version: Uuid) -> Result<Vec<Self>, EntityError> {
+        let r = ServiceVersionArtifactDao::service_version_artifacts(service_version).await;
+        Self::from_daos(r)
+    }
+
+    pub async fn build_artifacts(build: Uuid) -> Result<Vec<Self>, EntityError> {
+        let r = ServiceVersionArtifactDao::build_artifacts(build).await;
+        Self::from_daos(r)
+    }
+
+    fn from_daos(r: Result<Vec<ServiceVersionArtifactDao>, DalError>) -> Result<Vec<Self>, EntityError> {
+        match r {
+            Ok(artifacts) => Ok(artifacts.into_iter().map(From::<ServiceVersionArtifactDao>::from).collect()),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ServiceVersionArtifactDao> for ServiceVersionArtifact {
+    fn dao(&self) -> Box<ServiceVersionArtifactDao> {
+        Box::new(ServiceVersionArtifactDao::new(
+            self.id,
+            self.service_version,
+            self.build,
+            self.name.as_str(),
+            self.size,
+            self.sha256.as_str(),
+            self.created_on
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ServiceVersionArtifactDao, PgDao, Postgres> for ServiceVersionArtifact {
+    async fn read<K: IntoDaoKey<ServiceVersionArtifactKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceVersionArtifactDao::read(dal_key(qry)?).await {
+            Ok(artifact) => Ok(Box::new(From::<ServiceVersionArtifactDao>::from(*artifact))),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ServiceVersionArtifactKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceVersionArtifactDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ServiceVersionArtifactDao, PgDao, Postgres> for ServiceVersionArtifact {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut artifact = self.clone();
+                artifact.id = dao.id();
+                Ok(Box::new(artifact))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ServiceVersionArtifactDao, PgDao, Postgres> for ServiceVersionArtifact {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const SERVICE_VERSION_ARTIFACT_TYPE_INFO: &str = "ServiceVersionArtifact";
+
+impl TypeInfo for ServiceVersionArtifact {
+    fn type_name() -> &'static str {
+        SERVICE_VERSION_ARTIFACT_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        SERVICE_VERSION_ARTIFACT_TYPE_INFO
+    }
+}
+
+impl From<ServiceVersionArtifactDao> for ServiceVersionArtifact {
+    fn from(value: ServiceVersionArtifactDao) -> Self {
+        Self {
+            id: value.id(),
+            service_version: value.service_version(),
+            build: value.build(),
+            name: String::from(value.name()),
+            size: value.size(),
+            sha256: String::from(value.sha256()),
+            created_on: value.created_on(),
+        }
+    }
+}
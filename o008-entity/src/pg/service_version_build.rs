@@ -0,0 +1,393 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{BuildStatus, TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, ServiceVersionBuildKey, ServiceVersionKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity, ServiceVersion};
+use crate::pg::ServiceVersionBuildStage;
+
+type ServiceVersionBuildDao = o008_dal::pg::ServiceVersionBuild;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServiceVersionBuild {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    service_version: ServiceVersion,
+    status: BuildStatus,
+    completed: bool,
+    in_error: bool,
+    error_reason: Option<String>,
+    cancel_requested: bool,
+    start_on: Option<DateTime<Utc>>,
+    end_on: Option<DateTime<Utc>>,
+    retry_of: Option<Uuid>,
+    rerun_from: Option<i32>,
+    commit: Option<String>,
+    builder_digest: Option<String>,
+    cached_from: Option<Uuid>,
+    stages: Option<Vec<ServiceVersionBuildStage>>,
+    queue_position: Option<i64>,
+}
+
+impl ServiceVersionBuild {
+    pub fn new(service_version: ServiceVersion) -> Self {
+        Self {
+            id: Uuid::nil(),
+            service_version,
+            status: BuildStatus::Queued,
+            completed: false,
+            in_error: false,
+            error_reason: None,
+            cancel_requested: false,
+            start_on: None,
+            end_on: None,
+            retry_of: None,
+            rerun_from: None,
+            commit: None,
+            builder_digest: None,
+            cached_from: None,
+            stages: None,
+            queue_position: None,
+        }
+    }
+
+    pub fn retry(&self) -> Self {
+        let mut build = Self::new(self.service_version.clone());
+        build.retry_of = Some(self.id);
+        build
+    }
+
+    pub fn rerun(&self, from_stage: i32) -> Self {
+        let mut build = self.retry();
+        build.rerun_from = Some(from_stage);
+        build
+    }
+
+    // a build completed right away by reusing the results of a successful one with the same cache key
+    pub fn cached(service_version: ServiceVersion, from: &ServiceVersionBuild) -> Self {
+        let now = Utc::now();
+        let mut build = Self::new(service_version);
+        build.status = BuildStatus::Cached;
+        build.completed = true;
+        build.start_on = Some(now);
+        build.end_on = Some(now);
+        build.commit = from.commit.clone();
+        build.builder_digest = from.builder_digest.clone();
+        build.cached_from = Some(from.id);
+        build
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn load(id: Uuid, service_version: ServiceVersion, status: BuildStatus, completed: bool, in_error: bool, error_reason: Option<&str>,
+                cancel_requested: bool, start_on: Option<DateTime<Utc>>, end_on: Option<DateTime<Utc>>, retry_of: Option<Uuid>,
+                rerun_from: Option<i32>, commit: Option<&str>, builder_digest: Option<&str>, cached_from: Option<Uuid>) -> Self {
+        Self {
+            id,
+            service_version,
+            status,
+            completed,
+            in_error,
+            error_reason: error_reason.map(String::from),
+            cancel_requested,
+            start_on,
+            end_on,
+            retry_of,
+            rerun_from,
+            commit: commit.map(String::from),
+            builder_digest: builder_digest.map(String::from),
+            cached_from,
+            stages: None,
+            queue_position: None,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> &ServiceVersion {
+        &self.service_version
+    }
+
+    pub fn status(&self) -> BuildStatus {
+        self.status
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn in_error(&self) -> bool {
+        self.in_error
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn cancel_requested(&self) -> bool {
+        self.cancel_requested
+    }
+
+    pub fn start_on(&self) -> Option<DateTime<Utc>> {
+        self.start_on
+    }
+
+    pub fn end_on(&self) -> Option<DateTime<Utc>> {
+        self.end_on
+    }
+
+    pub fn retry_of(&self) -> Option<Uuid> {
+        self.retry_of
+    }
+
+    pub fn rerun_from(&self) -> Option<i32> {
+        self.rerun_from
+    }
+
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    pub fn builder_digest(&self) -> Option<&str> {
+        self.builder_digest.as_deref()
+    }
+
+    pub fn cached_from(&self) -> Option<Uuid> {
+        self.cached_from
+    }
+
+    pub fn set_cache_key(&mut self, commit: Option<&str>, builder_digest: &str) {
+        self.commit = commit.map(String::from);
+        self.builder_digest = Some(String::from(builder_digest));
+    }
+
+    pub fn stages(&self) -> Option<&Vec<ServiceVersionBuildStage>> {
+        self.stages.as_ref()
+    }
+
+    pub fn set_stages(&mut self, stages: Vec<ServiceVersionBuildStage>) {
+        self.stages = Some(stages)
+    }
+
+    pub fn queue_position(&self) -> Option<i64> {
+        self.queue_position
+    }
+
+    pub async fn load_queue_position(&mut self) -> Result<(), EntityError> {
+        self.queue_position = if self.status == BuildStatus::Queued && !self.id.is_nil() {
+            match ServiceVersionBuildDao::queue_position(self.id, BuildStatus::Queued.to_string().as_str()).await {
+                Ok(position) => Some(position),
+                Err(e) => return Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e)))
+            }
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    pub fn requeue(&mut self) {
+        self.status = BuildStatus::Queued;
+        self.completed = false;
+        self.in_error = false;
+        self.error_reason = None;
+        self.start_on = None;
+        self.end_on = None;
+        self.stages = None;
+    }
+
+    pub fn finish(&mut self, error_reason: Option<&str>) {
+        self.status = if error_reason.is_some() { BuildStatus::Failed } else { BuildStatus::Succeeded };
+        self.completed = true;
+        self.in_error = error_reason.is_some();
+        self.error_reason = error_reason.map(String::from);
+        self.end_on = Some(Utc::now());
+    }
+
+    pub async fn cancel_queued(&self, error_reason: &str) -> Result<Option<Box<Self>>, EntityError> {
+        let queued = BuildStatus::Queued.to_string();
+        let failed = BuildStatus::Failed.to_string();
+        match ServiceVersionBuildDao::cancel_queued(self.id, queued.as_str(), failed.as_str(), error_reason).await {
+            Ok(Some(b)) => Ok(Some(Box::new(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(b).await?))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+
+    pub async fn request_cancel(&self) -> Result<(), EntityError> {
+        ServiceVersionBuildDao::request_cancel(self.id).await.map_err(EntityError::Persist)
+    }
+
+    pub async fn is_cancel_requested(id: Uuid) -> Result<bool, EntityError> {
+        ServiceVersionBuildDao::is_cancel_requested(id).await
+            .map_err(|e| EntityError::NotFound(format!("{}: {}", Self::type_name(), e)))
+    }
+
+    pub async fn cache_hit(commit: &str, builder_digest: &str) -> Result<Option<Box<Self>>, EntityError> {
+        let succeeded = BuildStatus::Succeeded.to_string();
+        match ServiceVersionBuildDao::cache_hit(commit, builder_digest, succeeded.as_str()).await {
+            Ok(Some(b)) => Ok(Some(Box::new(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(b).await?))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e)))
+        }
+    }
+
+    pub async fn claim_next(worker: Uuid, lease: Duration) -> Result<Option<Box<Self>>, EntityError> {
+        let queued = BuildStatus::Queued.to_string();
+        let running = BuildStatus::Running.to_string();
+        match ServiceVersionBuildDao::claim_next(queued.as_str(), running.as_str(), worker, lease.as_secs_f64()).await {
+            Ok(Some(b)) => Ok(Some(Box::new(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(b).await?))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+
+    pub async fn renew_leases(worker: Uuid, builds: &[Uuid], lease: Duration) -> Result<(), EntityError> {
+        ServiceVersionBuildDao::renew_leases(worker, builds, lease.as_secs_f64()).await.map_err(EntityError::Persist)
+    }
+
+    // the running builds whose worker stopped renewing the lease, now leased to the given worker
+    pub async fn take_expired(worker: Uuid, lease: Duration) -> Result<Vec<Self>, EntityError> {
+        let running = BuildStatus::Running.to_string();
+        match ServiceVersionBuildDao::take_expired(running.as_str(), worker, lease.as_secs_f64()).await {
+            Ok(builds) => {
+                let mut v: Vec<Self> = Vec::new();
+                for b in builds {
+                    v.push(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(b).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+
+    pub async fn service_version_builds(service_version: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ServiceVersionBuildDao::service_version_builds(service_version).await {
+            Ok(builds) => {
+                let mut v: Vec<Self> = Vec::new();
+                for b in builds {
+                    v.push(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(b).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ServiceVersionBuildDao> for ServiceVersionBuild {
+    fn dao(&self) -> Box<ServiceVersionBuildDao> {
+        Box::new(ServiceVersionBuildDao::new(
+            self.id,
+            self.service_version.id(),
+            self.status.to_string().as_str(),
+            self.completed,
+            self.in_error,
+            self.error_reason.as_deref(),
+            self.start_on,
+            self.end_on,
+            self.retry_of,
+            self.rerun_from,
+            self.commit.as_deref(),
+            self.builder_digest.as_deref(),
+            self.cached_from
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ServiceVersionBuildDao, PgDao, Postgres> for ServiceVersionBuild {
+    async fn read<K: IntoDaoKey<ServiceVersionBuildKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceVersionBuildDao::read(dal_key(qry)?).await {
+            Ok(b) => Ok(Box::new(TryAsyncFrom::<ServiceVersionBuildDao>::try_from(*b).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ServiceVersionBuildKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceVersionBuildDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ServiceVersionBuildDao, PgDao, Postgres> for ServiceVersionBuild {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut build = self.clone();
+                build.id = dao.id();
+                Ok(Box::new(build))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ServiceVersionBuildDao, PgDao, Postgres> for ServiceVersionBuild {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const SERVICE_VERSION_BUILD_TYPE_INFO: &str = "ServiceVersionBuild";
+
+impl TypeInfo for ServiceVersionBuild {
+    fn type_name() -> &'static str {
+        SERVICE_VERSION_BUILD_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        SERVICE_VERSION_BUILD_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<ServiceVersionBuildDao> for ServiceVersionBuild {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceVersionBuildDao) -> Result<Self, EntityError> {
+        let service_version = ServiceVersion::read(ServiceVersionKey::Id(value.service_version())).await?;
+        Ok(Self::load(
+            value.id(),
+            *service_version,
+            value.status().parse().unwrap_or(BuildStatus::Pending),
+            value.completed(),
+            value.in_error(),
+            value.error_reason(),
+            value.cancel_requested(),
+            value.start_on(),
+            value.end_on(),
+            value.retry_of(),
+            value.rerun_from(),
+            value.commit(),
+            value.builder_digest(),
+            value.cached_from()
+        ))
+    }
+}
@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{BuildStatus, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, ServiceVersionBuildStageKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+
+pub(crate) type ServiceVersionBuildStageDao = o008_dal::pg::ServiceVersionBuildStage;
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServiceVersionBuildStage {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    build: Uuid,
+    stage: i32,
+    name: String,
+    description: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    stage_meta: Option<Value>,
+    status: BuildStatus,
+    completed: bool,
+    in_error: bool,
+    error_reason: Option<String>,
+    logs_link: Option<String>,
+    start_on: DateTime<Utc>,
+    end_on: Option<DateTime<Utc>>,
+}
+
+impl ServiceVersionBuildStage {
+    pub fn new(build: Uuid, stage: i32, name: &str, description: Option<&str>) -> Self {
+        Self {
+            id: Uuid::nil(),
+            build,
+            stage,
+            name: String::from(name),
+            description: description.map(String::from),
+            stage_meta: None,
+            status: BuildStatus::Pending,
+            completed: false,
+            in_error: false,
+            error_reason: None,
+            logs_link: None,
+            start_on: Utc::now(),
+            end_on: None,
+        }
+    }
+
+    // a copy of this stage result for another build, its logs stay the ones of this build
+    pub fn reuse(&self, build: Uuid) -> Self {
+        let mut stage_meta = self.stage_meta.clone().unwrap_or_else(|| json!({}));
+        if let Some(meta) = stage_meta.as_object_mut() {
+            meta.insert(String::from("reused_from"), json!(self.build));
+        }
+        Self {
+            id: Uuid::nil(),
+            build,
+            stage_meta: Some(stage_meta),
+            ..self.clone()
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn build(&self) -> Uuid {
+        self.build
+    }
+
+    pub fn stage(&self) -> i32 {
+        self.stage
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn stage_meta(&self) -> Option<&Value> {
+        self.stage_meta.as_ref()
+    }
+
+    pub fn status(&self) -> BuildStatus {
+        self.status
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn in_error(&self) -> bool {
+        self.in_error
+    }
+
+    pub fn error_reason(&self) -> Option<&str> {
+        self.error_reason.as_deref()
+    }
+
+    pub fn logs_link(&self) -> Option<&str> {
+        self.logs_link.as_deref()
+    }
+
+    pub fn start_on(&self) -> DateTime<Utc> {
+        self.start_on
+    }
+
+    pub fn end_on(&self) -> Option<DateTime<Utc>> {
+        self.end_on
+    }
+
+    pub fn set_stage_meta(&mut self, stage_meta: Value) {
+        self.stage_meta = Some(stage_meta)
+    }
+
+    pub fn set_logs_link(&mut self, logs_link: &str) {
+        self.logs_link = Some(String::from(logs_link))
+    }
+
+    pub fn start(&mut self) {
+        self.status = BuildStatus::Running;
+        self.start_on = Utc::now();
+    }
+
+    pub fn finish(&mut self, error_reason: Option<&str>) {
+        self.status = if error_reason.is_some() { BuildStatus::Failed } else { BuildStatus::Succeeded };
+        self.completed = true;
+        self.in_error = error_reason.is_some();
+        self.error_reason = error_reason.map(String::from);
+        self.end_on = Some(Utc::now());
+    }
+
+    pub async fn build_stages(build: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ServiceVersionBuildStageDao::build_stages(build).await {
+            Ok(stages) => Ok(stages.into_iter().map(From::<ServiceVersionBuildStageDao>::from).collect()),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ServiceVersionBuildStageDao> for ServiceVersionBuildStage {
+    fn dao(&self) -> Box<ServiceVersionBuildStageDao> {
+        let status = self.status.to_string();
+        Box::new(ServiceVersionBuildStageDao::new(
+            self.id,
+            self.build,
+            self.stage,
+            self.name.as_str(),
+            self.description.as_deref(),
+            self.stage_meta.clone(),
+            Some(status.as_str()),
+            self.completed,
+            self.in_error,
+            self.error_reason.as_deref(),
+            self.logs_link.as_deref(),
+            self.start_on,
+            self.end_on
+        ))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ServiceVersionBuildStageDao, PgDao, Postgres> for ServiceVersionBuildStage {
+    async fn read<K: IntoDaoKey<ServiceVersionBuildStageKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceVersionBuildStageDao::read(dal_key(qry)?).await {
+            Ok(stage) => Ok(Box::new(From::<ServiceVersionBuildStageDao>::from(*stage))),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ServiceVersionBuildStageKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceVersionBuildStageDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ServiceVersionBuildStageDao, PgDao, Postgres> for ServiceVersionBuildStage {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut stage = self.clone();
+                stage.id = dao.id();
+                Ok(Box::new(stage))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ServiceVersionBuildStageDao, PgDao, Postgres> for ServiceVersionBuildStage {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const SERVICE_VERSION_BUILD_STAGE_TYPE_INFO: &str = "ServiceVersionBuildStage";
+
+impl TypeInfo for ServiceVersionBuildStage {
+    fn type_name() -> &'static str {
+        SERVICE_VERSION_BUILD_STAGE_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        SERVICE_VERSION_BUILD_STAGE_TYPE_INFO
+    }
+}
+
+impl From<ServiceVersionBuildStageDao> for ServiceVersionBuildStage {
+    fn from(value: ServiceVersionBuildStageDao) -> Self {
+        Self {
+            id: value.id(),
+            build: value.build(),
+            stage: value.stage(),
+            name: String::from(value.name()),
+            description: value.description().map(String::from),
+            stage_meta: value.stage_meta().cloned(),
+            status: value.status().and_then(|s| s.parse().ok()).unwrap_or(BuildStatus::Pending),
+            completed: value.completed(),
+            in_error: value.in_error(),
+            error_reason: value.error_reason().map(String::from),
+            logs_link: value.logs_link().map(String::from),
+            start_on: value.start_on(),
+            end_on: value.end_on(),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Postgres;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use o008_common::{TryAsyncFrom, TypeInfo};
+use o008_dal::{DalError, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, RepoReferenceKey, ServiceVersionRepoRefHistoryKey};
+use crate::{dal_key, DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+use crate::pg::RepoReference;
+
+type ServiceVersionRepoRefHistoryDao = o008_dal::pg::ServiceVersionRepoRefHistory;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServiceVersionRepoRefHistory {
+    #[serde(rename(serialize = "_id", deserialize = "id"))]
+    id: Uuid,
+    service_version: Uuid,
+    repo_ref: RepoReference,
+    pinned_ref: RepoReference,
+    pinned_on: DateTime<Utc>,
+}
+
+impl ServiceVersionRepoRefHistory {
+    pub fn new(service_version: Uuid, repo_ref: RepoReference, pinned_ref: RepoReference) -> Self {
+        Self {
+            id: Uuid::nil(),
+            service_version,
+            repo_ref,
+            pinned_ref,
+            pinned_on: Utc::now(),
+        }
+    }
+
+    pub fn load(id: Uuid, service_version: Uuid, repo_ref: RepoReference, pinned_ref: RepoReference, pinned_on: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            service_version,
+            repo_ref,
+            pinned_ref,
+            pinned_on,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn service_version(&self) -> Uuid {
+        self.service_version
+    }
+
+    pub fn repo_ref(&self) -> &RepoReference {
+        &self.repo_ref
+    }
+
+    pub fn pinned_ref(&self) -> &RepoReference {
+        &self.pinned_ref
+    }
+
+    pub fn pinned_on(&self) -> DateTime<Utc> {
+        self.pinned_on
+    }
+
+    pub async fn service_version_history(service_version: Uuid) -> Result<Vec<Self>, EntityError> {
+        match ServiceVersionRepoRefHistoryDao::service_version_history(service_version).await {
+            Ok(history) => {
+                let mut v: Vec<Self> = Vec::new();
+                for h in history {
+                    v.push(TryAsyncFrom::<ServiceVersionRepoRefHistoryDao>::try_from(h).await?)
+                }
+                Ok(v)
+            },
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+}
+
+impl Entity<ServiceVersionRepoRefHistoryDao> for ServiceVersionRepoRefHistory {
+    fn dao(&self) -> Box<ServiceVersionRepoRefHistoryDao> {
+        Box::new(ServiceVersionRepoRefHistoryDao::new(self.id, self.service_version, self.repo_ref.id(), self.pinned_ref.id(), self.pinned_on))
+    }
+}
+
+#[async_trait]
+impl QueryEntity<ServiceVersionRepoRefHistoryDao, PgDao, Postgres> for ServiceVersionRepoRefHistory {
+    async fn read<K: IntoDaoKey<ServiceVersionRepoRefHistoryKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match ServiceVersionRepoRefHistoryDao::read(dal_key(qry)?).await {
+            Ok(h) => Ok(Box::new(TryAsyncFrom::<ServiceVersionRepoRefHistoryDao>::try_from(*h).await?)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(format!("{}: {}", Self::type_name(), e))),
+                _ => Err(EntityError::NotFound(format!("{}: {}", Self::type_name(), e))),
+            }
+        }
+    }
+
+    async fn persisted<K: IntoDaoKey<ServiceVersionRepoRefHistoryKey>>(qry: K) -> Result<bool, EntityError> {
+        ServiceVersionRepoRefHistoryDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PersistEntity<ServiceVersionRepoRefHistoryDao, PgDao, Postgres> for ServiceVersionRepoRefHistory {
+    async fn persist(&self) -> Result<Box<Self>, EntityError> {
+        let dao = self.dao();
+        let r = if self.id.is_nil() {
+            dao.insert().await
+        } else {
+            dao.update().await
+        };
+        match r {
+            Ok(_) => {
+                let mut history = self.clone();
+                history.id = dao.id();
+                Ok(Box::new(history))
+            },
+            Err(e) => Err(EntityError::Persist(e))
+        }
+    }
+}
+
+#[async_trait]
+impl DestroyEntity<ServiceVersionRepoRefHistoryDao, PgDao, Postgres> for ServiceVersionRepoRefHistory {
+    async fn destroy(&self) -> Result<(), EntityError> {
+        if self.id.is_nil() {
+            Err(EntityError::UnPersisted(String::from(self.type_of())))
+        } else {
+            match self.dao().delete().await {
+                Ok(_) => Ok(()),
+                Err(e) => Err(EntityError::Destroy(e))
+            }
+        }
+    }
+}
+
+const SERVICE_VERSION_REPO_REF_HISTORY_TYPE_INFO: &str = "ServiceVersionRepoRefHistory";
+
+impl TypeInfo for ServiceVersionRepoRefHistory {
+    fn type_name() -> &'static str {
+        SERVICE_VERSION_REPO_REF_HISTORY_TYPE_INFO
+    }
+
+    fn type_of(&self) -> &'static str {
+        SERVICE_VERSION_REPO_REF_HISTORY_TYPE_INFO
+    }
+}
+
+#[async_trait]
+impl TryAsyncFrom<ServiceVersionRepoRefHistoryDao> for ServiceVersionRepoRefHistory {
+    type Error = EntityError;
+
+    async fn try_from(value: ServiceVersionRepoRefHistoryDao) -> Result<Self, EntityError> {
+        let repo_ref = RepoReference::read(RepoReferenceKey::Id(value.repo_ref())).await?;
+        let pinned_ref = RepoReference::read(RepoReferenceKey::Id(value.pinned_ref())).await?;
+        Ok(Self::load(value.id(), value.service_version(), *repo_ref, *pinned_ref, value.pinned_on()))
+    }
+}
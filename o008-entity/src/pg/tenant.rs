@@ -1,13 +1,12 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use sqlx::Postgres;
 use utoipa::ToSchema;
 use uuid::Uuid;
-use o008_common::{AsyncFrom, TenantRequest};
-use o008_dal::{DalError, DaoCommand, DaoQuery};
-use o008_dal::pg::{PgDao};
-use crate::{DestroyEntity, Entity, EntityError, PersistEntity, QueryEntity};
+use o008_common::{ListRequest, TenantRequest, TryAsyncFrom};
+use o008_dal::{DalError, DalFilter, DaoCommand, DaoQuery, IntoDaoKey};
+use o008_dal::pg::{PgDao, TenantKey};
+use crate::{dal_key, dal_page, DependentEntity, DestroyEntity, Entity, EntityError, EntityPage, PersistEntity, QueryEntity};
 
 pub type TenantDao = o008_dal::pg::Tenant;
 
@@ -48,6 +47,16 @@ impl Tenant {
     pub fn coexisting(&self) -> bool {
         self.coexisting
     }
+
+    pub async fn list(filter: DalFilter, lrq: &ListRequest) -> Result<EntityPage<Self>, EntityError> {
+        match TenantDao::list(filter, dal_page(lrq)).await {
+            Ok(tenants) => Ok(EntityPage::new(tenants.into_iter().map(From::<TenantDao>::from).collect(), lrq.limit(), Self::id)),
+            Err(e) => match e {
+                DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
+                _ => Err(EntityError::NotFound(e.to_string())),
+            }
+        }
+    }
 }
 
 impl Entity<TenantDao> for Tenant {
@@ -58,8 +67,8 @@ impl Entity<TenantDao> for Tenant {
 
 #[async_trait]
 impl QueryEntity<TenantDao, PgDao, Postgres> for Tenant {
-    async fn read(qry: Value) -> Result<Box<Self>, EntityError> {
-        match TenantDao::read(qry).await {
+    async fn read<K: IntoDaoKey<TenantKey>>(qry: K) -> Result<Box<Self>, EntityError> {
+        match TenantDao::read(dal_key(qry)?).await {
             Ok(bt) => Ok(Box::new(From::<TenantDao>::from(*bt))),
             Err(e) => match e {
                 DalError::InvalidKey(_) => Err(EntityError::WrongQuery(e.to_string())),
@@ -68,8 +77,8 @@ impl QueryEntity<TenantDao, PgDao, Postgres> for Tenant {
         }
     }
 
-    async fn persisted(qry: Value) -> bool {
-        TenantDao::exists(qry).await
+    async fn persisted<K: IntoDaoKey<TenantKey>>(qry: K) -> Result<bool, EntityError> {
+        TenantDao::exists(dal_key(qry)?).await.map_err(|e| EntityError::NotFound(e.to_string()))
     }
 }
 
@@ -109,6 +118,8 @@ impl DestroyEntity<TenantDao, PgDao, Postgres> for Tenant {
     }
 }
 
+impl DependentEntity<TenantDao, PgDao, Postgres> for Tenant {}
+
 impl From<TenantDao> for Tenant {
     fn from(value: TenantDao) -> Self {
         Self::load(value.id(), value.name(), value.coexisting())
@@ -116,8 +127,11 @@ impl From<TenantDao> for Tenant {
 }
 
 #[async_trait]
-impl AsyncFrom<TenantRequest> for Tenant {
-    async fn from(value: TenantRequest) -> Self {
-        *Tenant::read(serde_json::to_value(value).unwrap()).await.unwrap()
+impl TryAsyncFrom<TenantRequest> for Tenant {
+    type Error = EntityError;
+
+    async fn try_from(value: TenantRequest) -> Result<Self, EntityError> {
+        let qry = serde_json::to_value(value).map_err(|e| EntityError::WrongQuery(e.to_string()))?;
+        Ok(*Tenant::read(qry).await?)
     }
 }
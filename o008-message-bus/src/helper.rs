@@ -3,11 +3,11 @@ use serde_json::Value;
 use tokio::sync::broadcast::error::TryRecvError;
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 use o008_common::{CommandDispatcher, DispatchResponse, DispatchResult, InternalCommand, ResultDispatcher};
 use o008_setting::app_config;
-use crate::{AppRequestMessage, AppResponseMessage, request_bus, response_bus};
+use crate::{AppRequestMessage, AppResponseMessage, BuildEvent, build_event_bus, request_bus, response_bus};
 
 
 pub fn send_request(msg: AppRequestMessage) -> bool {
@@ -30,6 +30,16 @@ pub fn send_response(msg: AppResponseMessage) -> bool {
     }
 }
 
+pub fn send_build_event(event: BuildEvent) -> bool {
+    match build_event_bus().send(event) {
+        Ok(_) => true,
+        Err(e) => {
+            debug!("build event without subscribers: {:?}", e.0);
+            false
+        }
+    }
+}
+
 pub async fn bus_processor<D>(msg: AppRequestMessage, dispatcher: D) -> Option<DispatchResult<Value>>
     where D: CommandDispatcher + Send + Unpin + Sized + 'static
 {
@@ -13,6 +13,7 @@ pub mod handler;
 
 pub use message::request::RequestMessage;
 pub use message::response::ResponseMessage;
+pub use message::build_event::BuildEvent;
 
 use o008_setting::app_config;
 
@@ -21,6 +22,7 @@ type AppResponseMessage = ResponseMessage<DispatchResponse<Value>>;
 
 pub type RequestMessageBus = Bus<AppRequestMessage>;
 pub type ResponseMessageBus = Bus<AppResponseMessage>;
+pub type BuildEventBus = Bus<BuildEvent>;
 
 
 lazy_static! {
@@ -31,6 +33,10 @@ lazy_static! {
     static ref ST_RESPONSE_BUS: Arc<ResponseMessageBus> = {
         Arc::new(ResponseMessageBus::new(app_config().bus().response_capacity()))
     };
+
+    static ref ST_BUILD_EVENT_BUS: Arc<BuildEventBus> = {
+        Arc::new(BuildEventBus::new(app_config().bus().event_capacity()))
+    };
 }
 
 pub fn request_bus() -> Arc<RequestMessageBus> {
@@ -41,3 +47,7 @@ pub fn response_bus() -> Arc<ResponseMessageBus> {
     Arc::clone(&ST_RESPONSE_BUS)
 }
 
+pub fn build_event_bus() -> Arc<BuildEventBus> {
+    Arc::clone(&ST_BUILD_EVENT_BUS)
+}
+
@@ -0,0 +1,57 @@
+use serde::Serialize;
+use uuid::Uuid;
+use o008_common::BuildStatus;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BuildEvent {
+    Log {
+        build: Uuid,
+        stage: i32,
+        line: String,
+    },
+    Status {
+        build: Uuid,
+        stage: Option<i32>,
+        status: BuildStatus,
+    },
+}
+
+impl BuildEvent {
+    pub fn log(build: Uuid, stage: i32, line: &str) -> Self {
+        Self::Log {
+            build,
+            stage,
+            line: String::from(line),
+        }
+    }
+
+    pub fn status(build: Uuid, stage: Option<i32>, status: BuildStatus) -> Self {
+        Self::Status {
+            build,
+            stage,
+            status,
+        }
+    }
+
+    pub fn build(&self) -> Uuid {
+        match self {
+            BuildEvent::Log { build, .. } => *build,
+            BuildEvent::Status { build, .. } => *build,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildEvent::Log { .. } => "log",
+            BuildEvent::Status { .. } => "status",
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        match self {
+            BuildEvent::Status { stage: None, status, .. } => status.is_final(),
+            _ => false,
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod request;
-pub mod response;
\ No newline at end of file
+pub mod response;
+pub mod build_event;
\ No newline at end of file
@@ -1,6 +1,12 @@
+use std::path::PathBuf;
 use config::{Config, ConfigError};
 use serde::{Deserialize};
 
+const DEFAULT_BUS_EVENT_CAPACITY: usize = 1024;
+const DEFAULT_BUILD_MAX_CONCURRENCY: usize = 1;
+const DEFAULT_BUILD_POLL_INTERVAL: u64 = 1000;
+const DEFAULT_BUILD_LEASE: u64 = 60;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Database {
     provider: String,
@@ -24,6 +30,31 @@ pub struct Bus {
     request_capacity: usize,
     response_wait: u64,
     request_wait: u64,
+    event_capacity: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Storage {
+    logs_dir: String,
+    workspaces_dir: String,
+    artifacts_dir: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Build {
+    #[serde(default = "default_max_concurrency")]
+    max_concurrency: usize,
+    poll_interval: Option<u64>,
+    timeout: Option<u64>,
+    lease: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Deploy {
+    command: String,
+    timeout: Option<u64>,
+    switch_command: Option<String>,
+    retire_command: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -31,7 +62,10 @@ pub struct AppConfig {
     debug: bool,
     database: Option<Database>,
     deployment_api: Option<Api>,
-    bus: Option<Bus>
+    bus: Option<Bus>,
+    storage: Option<Storage>,
+    build: Option<Build>,
+    deploy: Option<Deploy>
 }
 
 impl AppConfig {
@@ -42,7 +76,9 @@ impl AppConfig {
             .add_source(config::File::with_name(&config_file))
             .build()?;
 
-        s.try_deserialize()
+        let config: Self = s.try_deserialize()?;
+        config.build().validate()?;
+        Ok(config)
     }
 
     pub fn debug(&self) -> bool {
@@ -60,6 +96,19 @@ impl AppConfig {
     pub fn deployment_api(&self) -> Api {
         self.deployment_api.clone().expect("deployment api settings not found")
     }
+
+    pub fn storage(&self) -> Storage {
+        self.storage.clone().expect("storage settings not found")
+    }
+
+    // the build settings are optional, the worker then runs with the defaults
+    pub fn build(&self) -> Build {
+        self.build.clone().unwrap_or_default()
+    }
+
+    pub fn deploy(&self) -> Deploy {
+        self.deploy.clone().expect("deploy settings not found")
+    }
 }
 
 impl Database {
@@ -87,6 +136,10 @@ impl Bus {
     pub fn request_wait(&self) -> u64 {
         self.request_wait
     }
+
+    pub fn event_capacity(&self) -> usize {
+        self.event_capacity.unwrap_or(DEFAULT_BUS_EVENT_CAPACITY)
+    }
 }
 
 impl Api {
@@ -94,3 +147,82 @@ impl Api {
         format!("{}:{}", self.host, self.port)
     }
 }
+
+impl Storage {
+    pub fn logs_dir(&self) -> PathBuf {
+        PathBuf::from(&self.logs_dir)
+    }
+
+    pub fn workspaces_dir(&self) -> PathBuf {
+        PathBuf::from(&self.workspaces_dir)
+    }
+
+    pub fn artifacts_dir(&self) -> PathBuf {
+        PathBuf::from(&self.artifacts_dir)
+    }
+}
+
+impl Default for Build {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_BUILD_MAX_CONCURRENCY,
+            poll_interval: None,
+            timeout: None,
+            lease: None,
+        }
+    }
+}
+
+fn default_max_concurrency() -> usize {
+    DEFAULT_BUILD_MAX_CONCURRENCY
+}
+
+impl Build {
+    // the worker needs a slot to run builds and periods to wait, a zero would block it or panic its timers
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_concurrency == 0 {
+            return Err(ConfigError::Message(String::from("build max_concurrency should be greater than 0")))
+        }
+        if self.poll_interval() == 0 {
+            return Err(ConfigError::Message(String::from("build poll_interval should be greater than 0")))
+        }
+        if self.lease() == 0 {
+            return Err(ConfigError::Message(String::from("build lease should be greater than 0")))
+        }
+        Ok(())
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub fn poll_interval(&self) -> u64 {
+        self.poll_interval.unwrap_or(DEFAULT_BUILD_POLL_INTERVAL)
+    }
+
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    pub fn lease(&self) -> u64 {
+        self.lease.unwrap_or(DEFAULT_BUILD_LEASE)
+    }
+}
+
+impl Deploy {
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    pub fn switch_command(&self) -> Option<&str> {
+        self.switch_command.as_deref()
+    }
+
+    pub fn retire_command(&self) -> Option<&str> {
+        self.retire_command.as_deref()
+    }
+}